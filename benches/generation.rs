@@ -0,0 +1,64 @@
+// 청크 생성 벤치마크, 창 없이 World::generate_region 만 측정
+//
+// cargo bench --bench generation
+//
+// 생성기마다 같은 시드, 같은 범위로 여러 번 반복해서 청크 하나당 평균 시간을 출력
+// 블록 채우기만 잰 값 (fill) 과 메쉬까지 포함한 값 (total) 을 따로 보여줌
+
+use pcg_engine::voxel::chunk::Chunk;
+use pcg_engine::voxel::coord::ChunkCoord;
+use pcg_engine::voxel::generator::GeneratorConfig;
+use pcg_engine::voxel::world::{World, WorldConfig};
+use std::time::{Duration, Instant};
+
+const SEED: u32 = 12345;
+const ITERATIONS: u32 = 5;
+const MIN: ChunkCoord = ChunkCoord { x: -2, z: -2 };
+const MAX: ChunkCoord = ChunkCoord { x: 2, z: 2 };
+
+fn bench(name: &str, generator: &str) {
+    let config = WorldConfig {
+        generator: GeneratorConfig::parse(generator).unwrap(),
+        seed: SEED,
+        ..Default::default()
+    };
+    let terrain = config.generator.build(config.seed).unwrap();
+    let chunk_count = ((MAX.x - MIN.x) * (MAX.z - MIN.z)) as u32;
+
+    let mut fill = Duration::ZERO;
+    let mut total = Duration::ZERO;
+
+    for _ in 0..ITERATIONS {
+        let start = Instant::now();
+        for z in MIN.z..MAX.z {
+            for x in MIN.x..MAX.x {
+                Chunk::generate(ChunkCoord::new(x, z), terrain.as_ref());
+            }
+        }
+        fill += start.elapsed();
+
+        // 매번 새 World 를 만들어 이미 생성된 청크를 건너뛰지 않도록 함
+        let mut world = World::from_config(&config).unwrap();
+        let start = Instant::now();
+        world.generate_region(&MIN, &MAX);
+        total += start.elapsed();
+    }
+
+    let per_chunk =
+        |duration: Duration| duration.as_secs_f64() * 1000.0 / (ITERATIONS * chunk_count) as f64;
+    println!(
+        "{:<8} {} chunks x {} iterations : fill {:.3} ms/chunk, total {:.3} ms/chunk",
+        name,
+        chunk_count,
+        ITERATIONS,
+        per_chunk(fill),
+        per_chunk(total)
+    );
+}
+
+fn main() {
+    bench("noise", "noise");
+    bench("density", "density");
+    bench("spline", "spline");
+    bench("flat", "superflat");
+}
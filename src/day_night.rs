@@ -0,0 +1,178 @@
+use bevy::prelude::*;
+use bevy_atmosphere::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::f32::consts::TAU;
+
+// 해 / 달 역할을 하는 DirectionalLight
+#[derive(Component)]
+pub struct Sun;
+
+// 하루 시간과 날짜, WorldConfig 에 같이 저장됨
+// hours : 0 ~ 24, 6 시에 해가 뜨고 18 시에 짐
+#[derive(Resource, Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TimeOfDay {
+    hours: f32,
+    day: u32,
+    // 실제 시간으로 하루가 몇 초인지
+    day_length_seconds: f32,
+    paused: bool,
+}
+
+impl Default for TimeOfDay {
+    fn default() -> Self {
+        TimeOfDay {
+            hours: 9.0,
+            day: 0,
+            day_length_seconds: 600.0,
+            paused: false,
+        }
+    }
+}
+
+impl TimeOfDay {
+    // 달이 한 번 차고 기우는 날 수
+    pub const LUNAR_CYCLE_DAYS: f32 = 29.53;
+    // 해가 지나가는 궤도를 남쪽으로 기울임, 0 이면 정오에 해가 바로 위에 있어 그림자가 없음
+    const SUN_PATH_TILT: f32 = 0.4;
+
+    pub fn hours(&self) -> f32 {
+        self.hours
+    }
+
+    pub fn day(&self) -> u32 {
+        self.day
+    }
+
+    pub fn set_hours(&mut self, hours: f32) {
+        self.hours = hours.rem_euclid(24.0);
+    }
+
+    pub fn set_day(&mut self, day: u32) {
+        self.day = day;
+    }
+
+    pub fn day_length_seconds(&self) -> f32 {
+        self.day_length_seconds
+    }
+
+    pub fn set_day_length_seconds(&mut self, seconds: f32) {
+        self.day_length_seconds = seconds.max(1.0);
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    pub fn resume(&mut self) {
+        self.paused = false;
+    }
+
+    // 실제 시간 seconds 만큼 진행, 자정을 넘으면 날짜가 바뀜
+    pub fn advance(&mut self, seconds: f32) {
+        if self.paused {
+            return;
+        }
+
+        let hours = self.hours + seconds / self.day_length_seconds * 24.0;
+        self.day += (hours / 24.0).floor() as u32;
+        self.hours = hours.rem_euclid(24.0);
+    }
+
+    // 해가 있는 방향 (단위 벡터), 동쪽 (+X) 에서 떠서 서쪽 (-X) 으로 짐
+    pub fn sun_direction(&self) -> Vec3 {
+        let angle = (self.hours - 6.0) / 24.0 * TAU;
+        Quat::from_rotation_x(-Self::SUN_PATH_TILT) * Vec3::new(angle.cos(), angle.sin(), 0.0)
+    }
+
+    // 달은 해의 반대편
+    pub fn moon_direction(&self) -> Vec3 {
+        -self.sun_direction()
+    }
+
+    // 0 : 밤, 1 : 낮, 해가 지평선 근처일 때 부드럽게 바뀜
+    pub fn daylight(&self) -> f32 {
+        let elevation = self.sun_direction().y;
+        let t = ((elevation + 0.1) / 0.3).clamp(0.0, 1.0);
+        t * t * (3.0 - 2.0 * t)
+    }
+
+    // 0 : 삭 (new moon), 0.5 : 보름, 1 직전 : 그믐
+    pub fn moon_phase(&self) -> f32 {
+        ((self.day as f32 + self.hours / 24.0) / Self::LUNAR_CYCLE_DAYS).fract()
+    }
+
+    // 달이 빛나는 정도 0 ~ 1 (보름일 때 1)
+    pub fn moon_illumination(&self) -> f32 {
+        (1.0 - (self.moon_phase() * TAU).cos()) * 0.5
+    }
+}
+
+// TimeOfDay 를 진행시키고 해, 달, 주변광, 대기 모델에 반영
+pub struct DayNightPlugin;
+
+impl Plugin for DayNightPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TimeOfDay>().add_systems(
+            Update,
+            (
+                advance_time_of_day,
+                update_sun.run_if(resource_changed::<TimeOfDay>),
+            )
+                .chain(),
+        );
+    }
+}
+
+fn advance_time_of_day(time: Res<Time>, mut time_of_day: ResMut<TimeOfDay>) {
+    // 멈춰 있으면 ResMut 을 건드리지 않아서 update_sun 도 실행되지 않음
+    if time_of_day.is_paused() {
+        return;
+    }
+    time_of_day.advance(time.delta_seconds());
+}
+
+// 조도는 게임에서 밤에도 지형이 보이도록 실제보다 밝게 잡은 값
+const DAY_ILLUMINANCE: f32 = light_consts::lux::OVERCAST_DAY;
+const FULL_MOON_ILLUMINANCE: f32 = light_consts::lux::OVERCAST_DAY * 0.05;
+const DAY_AMBIENT_BRIGHTNESS: f32 = 200.0;
+const NIGHT_AMBIENT_BRIGHTNESS: f32 = 20.0;
+
+fn update_sun(
+    time_of_day: Res<TimeOfDay>,
+    mut suns: Query<(&mut DirectionalLight, &mut Transform), With<Sun>>,
+    ambient_light: Option<ResMut<AmbientLight>>,
+    atmosphere: Option<ResMut<AtmosphereModel>>,
+) {
+    let daylight = time_of_day.daylight();
+    let moonlight = time_of_day.moon_illumination() * (1.0 - daylight);
+
+    // 해가 지평선 아래면 달빛을 그림자를 만드는 빛으로 사용
+    let light_direction = if time_of_day.sun_direction().y > 0.0 {
+        time_of_day.sun_direction()
+    } else {
+        time_of_day.moon_direction()
+    };
+    let illuminance = DAY_ILLUMINANCE * daylight + FULL_MOON_ILLUMINANCE * moonlight;
+
+    for (mut light, mut transform) in suns.iter_mut() {
+        light.illuminance = illuminance;
+        // DirectionalLight 는 -Z 방향으로 비춤
+        transform.look_to(-light_direction, Vec3::Y);
+    }
+
+    if let Some(mut ambient_light) = ambient_light {
+        ambient_light.brightness = NIGHT_AMBIENT_BRIGHTNESS
+            + (DAY_AMBIENT_BRIGHTNESS - NIGHT_AMBIENT_BRIGHTNESS) * daylight.max(moonlight * 0.2);
+    }
+
+    if let Some(mut atmosphere) = atmosphere {
+        if let Some(nishita) = atmosphere.to_mut::<Nishita>() {
+            nishita.sun_position = time_of_day.sun_direction();
+        }
+    }
+}
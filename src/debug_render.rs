@@ -0,0 +1,529 @@
+use crate::voxel::block::Block;
+use crate::voxel::chunk::{Chunk, ChunkCoord, ChunkMesher};
+use crate::voxel::chunk_render::apply_chunk_meshes;
+use crate::voxel::floating_origin::FloatingOrigin;
+use crate::voxel::mesh::VoxelData;
+use crate::voxel::world::World;
+use bevy::pbr::wireframe::{WireframeConfig, WireframePlugin};
+use bevy::prelude::*;
+use bevy::render::primitives::Aabb;
+use bevy::render::render_resource::WgpuFeatures;
+use bevy::render::renderer::RenderDevice;
+use bevy_flycam::prelude::*;
+use std::ops::Range;
+
+// 실행 중에 켜고 끄는 디버그 표시
+//
+// F1 : HUD (카메라 위치, 청크 좌표, 청크 수, 삼각형 수)
+// F2 : 와이어프레임 (GPU 가 POLYGON_MODE_LINE 을 지원할 때만)
+// F3 : 카메라 주변 청크 경계와 청크 좌표
+// F4 : 카메라 주변 청크의 면 노멀
+// F5 : 청크 메쉬 색칠 모드 전환 (ColorizeMode)
+#[derive(Resource, Clone, Debug)]
+pub struct DebugRender {
+    pub hud: bool,
+    pub wireframe: bool,
+    pub chunk_borders: bool,
+    pub normals: bool,
+    pub colorize: ColorizeMode,
+    wireframe_supported: bool,
+}
+
+impl Default for DebugRender {
+    fn default() -> Self {
+        DebugRender {
+            hud: false,
+            wireframe: false,
+            chunk_borders: false,
+            normals: false,
+            colorize: ColorizeMode::Off,
+            wireframe_supported: true,
+        }
+    }
+}
+
+impl DebugRender {
+    pub fn wireframe_supported(&self) -> bool {
+        self.wireframe_supported
+    }
+}
+
+// 청크 메쉬에 버텍스 색으로 칠하는 값 (텍스처 색에 곱해짐)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ColorizeMode {
+    #[default]
+    Off,
+    // LOD 가 아직 없어서 LOD 를 고를 때 쓸 카메라 청크와의 거리 (청크 단위) 로 칠함
+    Lod,
+    // 하늘빛 레벨 (0 ~ MAX_LIGHT_LEVEL), 블록 조명이 없어서 heightmap 으로 계산한 근사값
+    LightLevel,
+    // 바이옴이 따로 없어서 컬럼 표면 블록의 대표 색 (map_color) 으로 대신함
+    Biome,
+}
+
+impl ColorizeMode {
+    pub fn next(self) -> Self {
+        match self {
+            ColorizeMode::Off => ColorizeMode::Lod,
+            ColorizeMode::Lod => ColorizeMode::LightLevel,
+            ColorizeMode::LightLevel => ColorizeMode::Biome,
+            ColorizeMode::Biome => ColorizeMode::Off,
+        }
+    }
+}
+
+pub const MAX_LIGHT_LEVEL: i32 = 15;
+// 경계 / 노멀을 그릴 카메라 주변 청크 범위, 너무 넓으면 기즈모가 많아서 느려짐
+const BORDER_RADIUS: i32 = 2;
+const NORMAL_RADIUS: i32 = 1;
+
+// DefaultPlugins (렌더링, 기즈모, UI) 다음에 추가
+pub struct DebugRenderPlugin;
+
+impl Plugin for DebugRenderPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<WireframePlugin>() {
+            app.add_plugins(WireframePlugin);
+        }
+
+        app.init_resource::<DebugRender>()
+            .add_systems(Startup, spawn_debug_hud)
+            .add_systems(
+                Update,
+                (
+                    toggle_debug_render,
+                    apply_wireframe.run_if(resource_changed::<DebugRender>),
+                    draw_chunk_borders,
+                    update_chunk_labels,
+                    draw_face_normals,
+                    update_debug_hud,
+                )
+                    .chain(),
+            )
+            .add_systems(PostUpdate, colorize_chunks.after(apply_chunk_meshes));
+    }
+
+    // RenderDevice 는 RenderPlugin::finish 에서 들어옴
+    fn finish(&self, app: &mut App) {
+        let supported = app
+            .world
+            .get_resource::<RenderDevice>()
+            .map(|device| device.features().contains(WgpuFeatures::POLYGON_MODE_LINE))
+            .unwrap_or(false);
+        app.world.resource_mut::<DebugRender>().wireframe_supported = supported;
+    }
+}
+
+fn toggle_debug_render(keys: Res<ButtonInput<KeyCode>>, mut debug_render: ResMut<DebugRender>) {
+    if keys.just_pressed(KeyCode::F1) {
+        debug_render.hud = !debug_render.hud;
+    }
+    if keys.just_pressed(KeyCode::F2) {
+        if debug_render.wireframe_supported {
+            debug_render.wireframe = !debug_render.wireframe;
+        } else {
+            warn!("wireframe needs POLYGON_MODE_LINE, not supported by this GPU");
+        }
+    }
+    if keys.just_pressed(KeyCode::F3) {
+        debug_render.chunk_borders = !debug_render.chunk_borders;
+    }
+    if keys.just_pressed(KeyCode::F4) {
+        debug_render.normals = !debug_render.normals;
+    }
+    if keys.just_pressed(KeyCode::F5) {
+        debug_render.colorize = debug_render.colorize.next();
+    }
+}
+
+fn apply_wireframe(debug_render: Res<DebugRender>, mut config: ResMut<WireframeConfig>) {
+    let global = debug_render.wireframe && debug_render.wireframe_supported;
+    if config.global != global {
+        config.global = global;
+    }
+}
+
+fn camera_chunk(floating_origin: &FloatingOrigin, camera_transform: &Transform) -> ChunkCoord {
+    floating_origin.chunk_coord_of(camera_transform.translation)
+}
+
+fn chunk_size() -> Vec3 {
+    Vec3::new(
+        VoxelData::CHUNK_WIDTH as f32,
+        VoxelData::CHUNK_HEIGHT as f32,
+        VoxelData::CHUNK_WIDTH as f32,
+    )
+}
+
+// center 주변 radius 안에서 생성된 청크
+fn chunks_around<'a>(
+    voxel_world: &'a World,
+    center: &ChunkCoord,
+    radius: i32,
+) -> impl Iterator<Item = &'a Chunk> + 'a {
+    let center = center.clone();
+    (-radius..=radius).flat_map(move |dz| {
+        let center = center.clone();
+        (-radius..=radius).filter_map(move |dx| {
+            voxel_world.get_chunk(&ChunkCoord::new(center.x + dx, center.z + dz))
+        })
+    })
+}
+
+// 카메라가 있는 청크는 노란색, 보이는 청크는 흰색, 오클루전 컬링으로 가려진 청크는 빨간색
+fn draw_chunk_borders(
+    debug_render: Res<DebugRender>,
+    voxel_world: Res<World>,
+    floating_origin: Res<FloatingOrigin>,
+    cameras: Query<&Transform, With<FlyCam>>,
+    mut gizmos: Gizmos,
+) {
+    if !debug_render.chunk_borders {
+        return;
+    }
+    let Ok(camera_transform) = cameras.get_single() else {
+        return;
+    };
+
+    let center = camera_chunk(&floating_origin, camera_transform);
+    let size = chunk_size();
+    for chunk in chunks_around(&voxel_world, &center, BORDER_RADIUS) {
+        let coord = &chunk.chunk_coord;
+        let color = if *coord == center {
+            Color::YELLOW
+        } else if voxel_world.is_chunk_visible(coord) {
+            Color::WHITE
+        } else {
+            Color::rgb(0.8, 0.2, 0.2)
+        };
+
+        let min = floating_origin.chunk_translation(coord);
+        gizmos.cuboid(
+            Transform::from_translation(min + size * 0.5).with_scale(size),
+            color,
+        );
+    }
+}
+
+// 청크 경계 위에 띄우는 청크 좌표, 필요한 만큼 만들어 두고 돌려 씀
+#[derive(Component)]
+struct ChunkLabel;
+
+fn update_chunk_labels(
+    debug_render: Res<DebugRender>,
+    voxel_world: Res<World>,
+    floating_origin: Res<FloatingOrigin>,
+    cameras: Query<(&Camera, &GlobalTransform, &Transform), With<FlyCam>>,
+    mut commands: Commands,
+    mut labels: Query<(&mut Text, &mut Style, &mut Visibility), With<ChunkLabel>>,
+) {
+    let mut shown = Vec::new();
+    if debug_render.chunk_borders {
+        if let Ok((camera, camera_global, camera_transform)) = cameras.get_single() {
+            let center = camera_chunk(&floating_origin, camera_transform);
+            let size = chunk_size();
+            for chunk in chunks_around(&voxel_world, &center, BORDER_RADIUS) {
+                let coord = &chunk.chunk_coord;
+                let top = floating_origin.chunk_translation(coord)
+                    + Vec3::new(size.x * 0.5, size.y + 1.0, size.z * 0.5);
+                if let Some(screen) = camera.world_to_viewport(camera_global, top) {
+                    shown.push((format!("{}, {}", coord.x, coord.z), screen));
+                }
+            }
+        }
+    }
+
+    let mut shown = shown.into_iter();
+    for (mut text, mut style, mut visibility) in labels.iter_mut() {
+        match shown.next() {
+            Some((label, screen)) => {
+                text.sections[0].value = label;
+                style.left = Val::Px(screen.x);
+                style.top = Val::Px(screen.y);
+                *visibility = Visibility::Visible;
+            }
+            None => *visibility = Visibility::Hidden,
+        }
+    }
+
+    for (label, screen) in shown {
+        commands.spawn((
+            TextBundle::from_section(
+                label,
+                TextStyle {
+                    font_size: 14.0,
+                    color: Color::WHITE,
+                    ..default()
+                },
+            )
+            .with_style(Style {
+                position_type: PositionType::Absolute,
+                left: Val::Px(screen.x),
+                top: Val::Px(screen.y),
+                ..default()
+            }),
+            ChunkLabel,
+        ));
+    }
+}
+
+// 청크 메쉬 표면의 (청크 로컬 좌표, 바깥쪽 노멀, 해당하는 버텍스 범위)
+// 블록 메쉬는 면 (버텍스 4개, 인덱스 6개) 마다, SurfaceNets 메쉬는 버텍스마다 하나
+fn chunk_surface_points(chunk: &Chunk) -> Vec<(Vec3, Vec3, Range<usize>)> {
+    if chunk.mesher == ChunkMesher::SurfaceNets {
+        return chunk
+            .vertices
+            .iter()
+            .zip(&chunk.normals)
+            .enumerate()
+            .map(|(i, (vertex, normal))| (*vertex, *normal, i..i + 1))
+            .collect();
+    }
+
+    chunk
+        .triangles
+        .chunks_exact(6)
+        .map(|face| {
+            let v0 = chunk.vertices[face[0] as usize];
+            let v1 = chunk.vertices[face[1] as usize];
+            let v2 = chunk.vertices[face[2] as usize];
+            let v3 = chunk.vertices[face[5] as usize];
+            // 반시계 방향 삼각형이라 외적이 바깥쪽을 향함
+            let normal = (v1 - v0).cross(v2 - v0).normalize_or_zero();
+            let start = face[0] as usize;
+            ((v0 + v1 + v2 + v3) * 0.25, normal, start..start + 4)
+        })
+        .collect()
+}
+
+fn draw_face_normals(
+    debug_render: Res<DebugRender>,
+    voxel_world: Res<World>,
+    floating_origin: Res<FloatingOrigin>,
+    cameras: Query<&Transform, With<FlyCam>>,
+    mut gizmos: Gizmos,
+) {
+    if !debug_render.normals {
+        return;
+    }
+    let Ok(camera_transform) = cameras.get_single() else {
+        return;
+    };
+
+    let center = camera_chunk(&floating_origin, camera_transform);
+    for chunk in chunks_around(&voxel_world, &center, NORMAL_RADIUS) {
+        if !voxel_world.is_chunk_visible(&chunk.chunk_coord) {
+            continue;
+        }
+
+        let offset = floating_origin.chunk_translation(&chunk.chunk_coord);
+        for (point, normal, _) in chunk_surface_points(chunk) {
+            // 축마다 색을 다르게 (x : 빨강, y : 초록, z : 파랑)
+            let color = Color::rgb(normal.x.abs(), normal.y.abs(), normal.z.abs());
+            gizmos.ray(offset + point, normal * 0.4, color);
+        }
+    }
+}
+
+// 색칠 모드나 (Lod 모드에서) 카메라 청크가 바뀌면 모든 청크를 다시 칠하고,
+// 그 외에는 새로 스폰됐거나 리메쉬된 청크 (Aabb 가 바뀐 청크) 만 칠함
+// 리메쉬할 때 write_mesh 가 청크의 원래 색으로 덮어쓰므로 끄면 원래 색으로 돌아감
+fn colorize_chunks(
+    debug_render: Res<DebugRender>,
+    voxel_world: Res<World>,
+    floating_origin: Res<FloatingOrigin>,
+    cameras: Query<&Transform, With<FlyCam>>,
+    chunks: Query<(&ChunkCoord, &Handle<Mesh>, Ref<Aabb>)>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut last: Local<Option<(ColorizeMode, ChunkCoord)>>,
+) {
+    let mode = debug_render.colorize;
+    let center = match (mode, cameras.get_single()) {
+        (ColorizeMode::Lod, Ok(camera_transform)) => {
+            camera_chunk(&floating_origin, camera_transform)
+        }
+        _ => ChunkCoord::default(),
+    };
+    let key = (mode, center.clone());
+    let recolor_all = last.as_ref() != Some(&key);
+    *last = Some(key);
+
+    if mode == ColorizeMode::Off {
+        if recolor_all {
+            for (coord, mesh_handle, _) in chunks.iter() {
+                if let (Some(mesh), Some(chunk)) =
+                    (meshes.get_mut(mesh_handle), voxel_world.get_chunk(coord))
+                {
+                    chunk.write_colors(mesh);
+                }
+            }
+        }
+        return;
+    }
+
+    let block = Block::new();
+    for (coord, mesh_handle, aabb) in chunks.iter() {
+        if !recolor_all && !aabb.is_changed() {
+            continue;
+        }
+        let Some(chunk) = voxel_world.get_chunk(coord) else {
+            continue;
+        };
+
+        let colors = chunk_vertex_colors(chunk, mode, &center, &block);
+        if let Some(mesh) = meshes.get_mut(mesh_handle) {
+            mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, colors);
+        }
+    }
+}
+
+// 표면 점마다 한 색, 블록 메쉬는 면의 버텍스 4개에 같은 색을 넣음
+fn chunk_vertex_colors(
+    chunk: &Chunk,
+    mode: ColorizeMode,
+    camera_chunk: &ChunkCoord,
+    block: &Block,
+) -> Vec<[f32; 4]> {
+    let mut colors = vec![[1.0; 4]; chunk.vertices.len()];
+
+    let lod_color = {
+        let distance = (chunk.chunk_coord.x - camera_chunk.x)
+            .abs()
+            .max((chunk.chunk_coord.z - camera_chunk.z).abs());
+        Color::hsl((distance * 45 % 360) as f32, 0.8, 0.6)
+    };
+
+    for (point, normal, vertices) in chunk_surface_points(chunk) {
+        let color = match mode {
+            ColorizeMode::Off => Color::WHITE,
+            ColorizeMode::Lod => lod_color,
+            ColorizeMode::LightLevel => {
+                let level = sky_light_level(chunk, point + normal * 0.5);
+                let t = level as f32 / MAX_LIGHT_LEVEL as f32;
+                Color::rgb(0.1 + 0.9 * t, 0.1 + 0.8 * t, 0.4 * (1.0 - t) + 0.2 * t)
+            }
+            ColorizeMode::Biome => {
+                let [r, g, b] = surface_color(chunk, point - normal * 0.5, block);
+                Color::rgb_u8(r, g, b)
+            }
+        };
+
+        for vertex in &mut colors[vertices] {
+            *vertex = color.as_linear_rgba_f32();
+        }
+    }
+
+    colors
+}
+
+// 면이 닿아 있는 빈 칸의 하늘빛, 컬럼에서 가장 높은 블록보다 위면 최대,
+// 아래로 한 칸 내려갈 때마다 1 씩 어두워짐, 청크 밖 컬럼은 하늘이 열려 있는 것으로 봄
+fn sky_light_level(chunk: &Chunk, air: Vec3) -> i32 {
+    let air = air.floor().as_ivec3();
+    let Some(height) = chunk.highest_solid_block(air.x, air.z) else {
+        return MAX_LIGHT_LEVEL;
+    };
+
+    if air.y > height {
+        MAX_LIGHT_LEVEL
+    } else {
+        (MAX_LIGHT_LEVEL - (height - air.y)).max(0)
+    }
+}
+
+// 면을 가진 블록이 있는 컬럼의 맨 위 블록 색
+fn surface_color(chunk: &Chunk, solid: Vec3, block: &Block) -> [u8; 3] {
+    let solid = solid.floor().as_ivec3();
+    let Some(height) = chunk.highest_solid_block(solid.x, solid.z) else {
+        return [255, 255, 255];
+    };
+
+    let block_id = chunk.voxel_map[solid.x as usize][height as usize][solid.z as usize];
+    block.block_types[block_id as usize].map_color
+}
+
+#[derive(Component)]
+struct DebugHud;
+
+fn spawn_debug_hud(mut commands: Commands) {
+    commands.spawn((
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font_size: 16.0,
+                color: Color::WHITE,
+                ..default()
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            left: Val::Px(8.0),
+            top: Val::Px(8.0),
+            ..default()
+        })
+        .with_background_color(Color::rgba(0.0, 0.0, 0.0, 0.5)),
+        DebugHud,
+    ));
+}
+
+fn update_debug_hud(
+    debug_render: Res<DebugRender>,
+    voxel_world: Res<World>,
+    floating_origin: Res<FloatingOrigin>,
+    cameras: Query<&Transform, With<FlyCam>>,
+    mut huds: Query<(&mut Text, &mut Visibility), With<DebugHud>>,
+) {
+    let Ok((mut text, mut visibility)) = huds.get_single_mut() else {
+        return;
+    };
+    if !debug_render.hud {
+        *visibility = Visibility::Hidden;
+        return;
+    }
+    *visibility = Visibility::Visible;
+
+    let mut lines = Vec::new();
+    if let Ok(camera_transform) = cameras.get_single() {
+        let pos = floating_origin.world_position(camera_transform.translation);
+        let coord = camera_chunk(&floating_origin, camera_transform);
+        lines.push(format!("pos {:.1} {:.1} {:.1}", pos.x, pos.y, pos.z));
+        lines.push(format!("chunk {} {}", coord.x, coord.z));
+    }
+
+    let mut triangles = 0;
+    let mut visible_triangles = 0;
+    for chunk in voxel_world.chunks() {
+        let count = chunk.triangles.len() / 3;
+        triangles += count;
+        if voxel_world.is_chunk_visible(&chunk.chunk_coord) {
+            visible_triangles += count;
+        }
+    }
+    lines.push(format!(
+        "chunks loaded {} active {} visible {}",
+        voxel_world.chunk_count(),
+        voxel_world.active_chunk_count(),
+        voxel_world.visible_chunk_count()
+    ));
+    lines.push(format!(
+        "triangles {} (visible {})",
+        triangles, visible_triangles
+    ));
+    lines.push(format!(
+        "F2 wireframe {}  F3 borders {}  F4 normals {}  F5 color {:?}",
+        on_off(debug_render.wireframe),
+        on_off(debug_render.chunk_borders),
+        on_off(debug_render.normals),
+        debug_render.colorize
+    ));
+
+    text.sections[0].value = lines.join("\n");
+}
+
+fn on_off(value: bool) -> &'static str {
+    if value {
+        "on"
+    } else {
+        "off"
+    }
+}
@@ -0,0 +1,100 @@
+use crate::day_night::TimeOfDay;
+use crate::voxel::mesh::VoxelData;
+use crate::voxel::world::World;
+use bevy::pbr::{FogFalloff, FogSettings};
+use bevy::prelude::*;
+use bevy_flycam::prelude::*;
+
+// 뷰 거리 끝에서 청크가 생기고 사라지는 게 보이지 않도록 FlyCam 에 거리 안개를 씌움
+// 범위는 World 의 현재 뷰 거리, 색은 TimeOfDay 에 맞춘 하늘 (대기) 색을 따라감
+#[derive(Resource, Clone, Debug)]
+pub struct DistanceFog {
+    pub enabled: bool,
+    // 안개가 시작되는 거리, 끝 거리에 대한 비율
+    pub start_fraction: f32,
+}
+
+impl Default for DistanceFog {
+    fn default() -> Self {
+        DistanceFog {
+            enabled: true,
+            start_fraction: 0.5,
+        }
+    }
+}
+
+pub struct DistanceFogPlugin;
+
+impl Plugin for DistanceFogPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<DistanceFog>()
+            .add_systems(PostUpdate, update_distance_fog);
+    }
+}
+
+// 지평선 근처의 하늘 색, 대기 모델 (Nishita) 의 낮 / 밤 / 노을 색에 맞춘 근사값
+const DAY_SKY: Vec3 = Vec3::new(0.62, 0.74, 0.92);
+const NIGHT_SKY: Vec3 = Vec3::new(0.01, 0.015, 0.035);
+const SUNSET_SKY: Vec3 = Vec3::new(0.9, 0.5, 0.3);
+
+pub fn fog_color(time_of_day: &TimeOfDay) -> Color {
+    let daylight = time_of_day.daylight();
+    let color = NIGHT_SKY.lerp(DAY_SKY, daylight);
+
+    // 해가 지평선에 가까울수록 노을 색을 섞음
+    let sunset = (1.0 - time_of_day.sun_direction().y.abs() / 0.25).clamp(0.0, 1.0) * daylight;
+    let color = color.lerp(SUNSET_SKY, sunset * 0.5);
+
+    Color::rgb(color.x, color.y, color.z)
+}
+
+// 카메라가 있는 청크 경계에서 가장 가까운 뷰 범위 끝까지의 거리
+// 이보다 멀리 있는 청크는 완전히 안개에 가려짐
+pub fn fog_end_distance(view_distance: i32) -> f32 {
+    ((view_distance - 1).max(1) * VoxelData::CHUNK_WIDTH) as f32
+}
+
+fn update_distance_fog(
+    distance_fog: Res<DistanceFog>,
+    voxel_world: Option<Res<World>>,
+    time_of_day: Option<Res<TimeOfDay>>,
+    mut commands: Commands,
+    mut cameras: Query<(Entity, Option<&mut FogSettings>), With<FlyCam>>,
+) {
+    for (camera, fog_settings) in cameras.iter_mut() {
+        if !distance_fog.enabled {
+            if fog_settings.is_some() {
+                commands.entity(camera).remove::<FogSettings>();
+            }
+            continue;
+        }
+
+        let view_distance = voxel_world
+            .as_ref()
+            .map(|voxel_world| voxel_world.view_distance())
+            .unwrap_or(VoxelData::VIEW_DISTANCE_IN_CHUNKS);
+        let end = fog_end_distance(view_distance);
+        let (color, daylight) = match &time_of_day {
+            Some(time_of_day) => (fog_color(time_of_day), time_of_day.daylight()),
+            None => (fog_color(&TimeOfDay::default()), 1.0),
+        };
+
+        let settings = FogSettings {
+            color,
+            // 해 쪽을 볼 때 안개가 밝게 보이도록
+            directional_light_color: Color::rgba(1.0, 0.9, 0.7, 0.5 * daylight),
+            directional_light_exponent: 30.0,
+            falloff: FogFalloff::Linear {
+                start: end * distance_fog.start_fraction,
+                end,
+            },
+        };
+
+        match fog_settings {
+            Some(mut fog_settings) => *fog_settings = settings,
+            None => {
+                commands.entity(camera).insert(settings);
+            }
+        }
+    }
+}
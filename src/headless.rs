@@ -0,0 +1,409 @@
+use bevy::math::{IVec2, IVec3};
+use pcg_engine::voxel::chunk::{Chunk, ChunkCoord, ChunkMesher};
+use pcg_engine::voxel::erosion::{ErodedRegion, ErosionMap, ErosionSettings};
+use pcg_engine::voxel::generator::GeneratorConfig;
+use pcg_engine::voxel::image_export;
+use pcg_engine::voxel::mesh::VoxelData;
+use pcg_engine::voxel::mesh_export;
+use pcg_engine::voxel::terrain::TerrainPreset;
+use pcg_engine::voxel::wfc::TileSet;
+use pcg_engine::voxel::world::{World, WorldConfig};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+// 창 없이 지형을 생성해서 PNG 로 내보내는 모드
+// cargo run -- --headless --seed 132 --radius 8 --out target/terrain
+//     [--mesh terrain.glb] [--preset assets/terrain/default.ron]
+//     [--generator noise|density|spline|void|checkerboard|superflat[:bedrock,3*dirt,grass]]
+//     [--bench 5]  (생성 시간만 측정하고 이미지는 만들지 않음, 생성기별 비교는 cargo bench --bench generation)
+//     [--erosion-check]  (수력 침식 영역 경계에서 이웃 영역과 높이가 맞는지 측정, noise / spline 생성기)
+//     [--mesher blocky|surface_nets]  (--mesh 로 내보낼 메쉬 방식)
+//     [--structure assets/structures/dungeon.ron]  (WFC 구조물을 원점 지표에 찍음)
+//     [--structure-size 6,1,6]  (타일 단위 구조물 크기)
+//     [--world world.ron]  (WorldConfig, 주어지면 --seed, --generator, --preset, --mesher 는 무시)
+pub struct HeadlessArgs {
+    pub seed: u32,
+    pub radius: i32,
+    pub out_dir: PathBuf,
+    // out_dir 기준 경로, 확장자는 .obj 또는 .glb
+    pub mesh_file: Option<PathBuf>,
+    // noise 생성기의 프리셋, 없으면 TerrainPreset::default()
+    pub preset: Option<PathBuf>,
+    pub generator: GeneratorConfig,
+    pub mesher: ChunkMesher,
+    pub world_config: Option<PathBuf>,
+    // 반복 횟수
+    pub bench: Option<u32>,
+    pub erosion_check: bool,
+    // WFC 타일 세트
+    pub structure: Option<PathBuf>,
+    pub structure_size: IVec3,
+}
+
+impl Default for HeadlessArgs {
+    fn default() -> Self {
+        HeadlessArgs {
+            seed: VoxelData::DEFAULT_SEED,
+            radius: 8,
+            out_dir: PathBuf::from("headless_output"),
+            mesh_file: None,
+            preset: None,
+            generator: GeneratorConfig::default(),
+            mesher: ChunkMesher::default(),
+            world_config: None,
+            bench: None,
+            erosion_check: false,
+            structure: None,
+            structure_size: IVec3::new(6, 1, 6),
+        }
+    }
+}
+
+impl HeadlessArgs {
+    // --headless 가 없으면 None
+    pub fn parse(args: &[String]) -> Result<Option<HeadlessArgs>, String> {
+        if !args.iter().any(|arg| arg == "--headless") {
+            return Ok(None);
+        }
+
+        let mut result = HeadlessArgs::default();
+        let mut iter = args.iter().skip(1);
+        while let Some(arg) = iter.next() {
+            match arg.as_str() {
+                "--headless" => {}
+                "--seed" => result.seed = parse_value(arg, iter.next())?,
+                "--radius" => result.radius = parse_value(arg, iter.next())?,
+                "--bench" => result.bench = Some(parse_value(arg, iter.next())?),
+                "--erosion-check" => result.erosion_check = true,
+                "--out" => {
+                    let value = iter.next().ok_or(format!("{} needs a value", arg))?;
+                    result.out_dir = PathBuf::from(value);
+                }
+                "--mesh" => {
+                    let value = iter.next().ok_or(format!("{} needs a value", arg))?;
+                    result.mesh_file = Some(PathBuf::from(value));
+                }
+                "--preset" => {
+                    let value = iter.next().ok_or(format!("{} needs a value", arg))?;
+                    result.preset = Some(PathBuf::from(value));
+                }
+                "--generator" => {
+                    let value = iter.next().ok_or(format!("{} needs a value", arg))?;
+                    result.generator = GeneratorConfig::parse(value)?;
+                }
+                "--mesher" => {
+                    let value = iter.next().ok_or(format!("{} needs a value", arg))?;
+                    result.mesher = ChunkMesher::parse(value)?;
+                }
+                "--structure" => {
+                    let value = iter.next().ok_or(format!("{} needs a value", arg))?;
+                    result.structure = Some(PathBuf::from(value));
+                }
+                "--structure-size" => {
+                    let value = iter.next().ok_or(format!("{} needs a value", arg))?;
+                    let size: Vec<i32> = value
+                        .split(',')
+                        .map(|v| v.trim().parse())
+                        .collect::<Result<_, _>>()
+                        .map_err(|_| format!("invalid value for {}: {}", arg, value))?;
+                    let [x, y, z] = size[..] else {
+                        return Err(format!("{} needs x,y,z: {}", arg, value));
+                    };
+                    result.structure_size = IVec3::new(x, y, z);
+                }
+                "--world" => {
+                    let value = iter.next().ok_or(format!("{} needs a value", arg))?;
+                    result.world_config = Some(PathBuf::from(value));
+                }
+                _ => return Err(format!("unknown argument: {}", arg)),
+            }
+        }
+
+        if result.radius <= 0 {
+            return Err("--radius must be positive".to_string());
+        }
+
+        Ok(Some(result))
+    }
+}
+
+fn parse_value<T: std::str::FromStr>(name: &str, value: Option<&String>) -> Result<T, String> {
+    let value = value.ok_or(format!("{} needs a value", name))?;
+    value
+        .parse()
+        .map_err(|_| format!("invalid value for {}: {}", name, value))
+}
+
+pub fn run(args: &HeadlessArgs) -> Result<(), String> {
+    let min = ChunkCoord::new(-args.radius, -args.radius);
+    let max = ChunkCoord::new(args.radius, args.radius);
+
+    let config = match &args.world_config {
+        Some(path) => WorldConfig::load(path).map_err(|e| e.to_string())?,
+        None => {
+            let generator = match (&args.generator, &args.preset) {
+                (GeneratorConfig::Noise(_), Some(path)) => {
+                    GeneratorConfig::Noise(TerrainPreset::load(path).map_err(|e| e.to_string())?)
+                }
+                (generator, _) => generator.clone(),
+            };
+            WorldConfig {
+                seed: args.seed,
+                generator,
+                mesher: args.mesher,
+                ..Default::default()
+            }
+        }
+    };
+
+    if let Some(iterations) = args.bench {
+        return bench_generation(&config, &min, &max, iterations);
+    }
+
+    if args.erosion_check {
+        return erosion_check(&config, args.radius);
+    }
+
+    let mut world = World::from_config(&config)?;
+    world.generate_region(&min, &max);
+
+    if let Some(path) = &args.structure {
+        stamp_structure(&mut world, path, args.structure_size)?;
+    }
+
+    std::fs::create_dir_all(&args.out_dir).map_err(|e| e.to_string())?;
+
+    let heightmap_path = args.out_dir.join("heightmap.png");
+    let slice_x_path = args.out_dir.join("slice_x.png");
+    let slice_z_path = args.out_dir.join("slice_z.png");
+
+    // 단면은 영역 중앙(월드 원점)을 지나도록
+    image_export::export_heightmap(&world, &min, &max, &heightmap_path)
+        .map_err(|e| e.to_string())?;
+    image_export::export_slice_x(&world, &min, &max, 0, &slice_x_path)
+        .map_err(|e| e.to_string())?;
+    image_export::export_slice_z(&world, &min, &max, 0, &slice_z_path)
+        .map_err(|e| e.to_string())?;
+
+    if let Some(mesh_file) = &args.mesh_file {
+        let atlas_path = PathBuf::from("assets").join(VoxelData::TEXTURE_ATLAS_PATH);
+        mesh_export::export_mesh(
+            &world,
+            &min,
+            &max,
+            args.out_dir.join(mesh_file),
+            &atlas_path,
+        )
+        .map_err(|e| e.to_string())?;
+    }
+
+    println!(
+        "seed {} : {}x{} chunks -> {}",
+        world.seed(),
+        max.x - min.x,
+        max.z - min.z,
+        args.out_dir.display()
+    );
+
+    Ok(())
+}
+
+// 월드 시드로 구조물을 만들어 원점 지표 위에 (ground_offset 만큼 띄워서) 가운데를 맞춰 찍음
+fn stamp_structure(world: &mut World, path: &PathBuf, size: IVec3) -> Result<(), String> {
+    let tile_set = TileSet::load(path).map_err(|e| e.to_string())?;
+    let structure = tile_set.solve(size, world.seed())?;
+
+    let ground = world.highest_solid_block(0, 0).unwrap_or(0);
+    let origin = IVec3::new(
+        -structure.size.x / 2,
+        ground + 1 + tile_set.ground_offset,
+        -structure.size.z / 2,
+    );
+    let placed = structure.stamp(world, origin);
+
+    println!(
+        "structure {} : {} blocks at {}",
+        path.display(),
+        placed,
+        origin
+    );
+    Ok(())
+}
+
+// 같은 영역을 반복 생성하고 청크당 평균 시간을 출력
+// fill : 블록 채우기 + heightmap, total : World::generate_region (메쉬 생성 포함)
+fn bench_generation(
+    config: &WorldConfig,
+    min: &ChunkCoord,
+    max: &ChunkCoord,
+    iterations: u32,
+) -> Result<(), String> {
+    let iterations = iterations.max(1);
+    let chunk_count = ((max.x - min.x) * (max.z - min.z)) as u32;
+    let generator = config.generator.build(config.seed)?;
+
+    let mut fill = std::time::Duration::ZERO;
+    let mut total = std::time::Duration::ZERO;
+
+    for _ in 0..iterations {
+        let start = std::time::Instant::now();
+        for z in min.z..max.z {
+            for x in min.x..max.x {
+                Chunk::generate(ChunkCoord::new(x, z), generator.as_ref());
+            }
+        }
+        fill += start.elapsed();
+
+        // 매번 새 World 를 만들어 이미 생성된 청크를 건너뛰지 않도록 함
+        let mut world = World::from_config(config)?;
+        let start = std::time::Instant::now();
+        world.generate_region(min, max);
+        total += start.elapsed();
+    }
+
+    let per_chunk = |duration: std::time::Duration| {
+        duration.as_secs_f64() * 1000.0 / (iterations * chunk_count) as f64
+    };
+    println!(
+        "{} chunks x {} iterations : fill {:.3} ms/chunk, total {:.3} ms/chunk",
+        chunk_count,
+        iterations,
+        per_chunk(fill),
+        per_chunk(total)
+    );
+
+    Ok(())
+}
+
+// radius 청크 안의 침식 영역을 모두 만들고 영역 경계에서 높이가 이어지는지 측정
+// overlap : 이웃한 두 영역이 경계 양쪽 BAND 칸에서 따로 계산한 높이의 차이 (섞기 전)
+// height step : 섞은 뒤 실제로 쓰이는 높이의 이웃 칸 차이, 경계를 넘는 쪽이 영역 안보다
+//               MAX_STEP_RATIO 배 넘게 크면 실패
+fn erosion_check(config: &WorldConfig, radius: i32) -> Result<(), String> {
+    const BAND: i32 = 4;
+    const MAX_STEP_RATIO: f32 = 1.5;
+
+    let (height, mut settings): (Box<dyn Fn(i32, i32) -> f32>, ErosionSettings) =
+        match &config.generator {
+            GeneratorConfig::Noise(preset) => {
+                let terrain = preset.build(config.seed);
+                (
+                    Box::new(move |x, z| terrain.terrain_height(x as f32, z as f32)),
+                    preset.hydraulic_erosion.clone().unwrap_or_default(),
+                )
+            }
+            GeneratorConfig::Spline(preset) => {
+                let terrain = preset.build(config.seed);
+                (
+                    Box::new(move |x, z| terrain.terrain_height(x as f32, z as f32)),
+                    preset.hydraulic_erosion.clone().unwrap_or_default(),
+                )
+            }
+            _ => return Err("--erosion-check needs the noise or spline generator".to_string()),
+        };
+
+    let size = settings.region_size();
+    let count = (radius * VoxelData::CHUNK_WIDTH / size).max(1);
+    // 측정 범위 바깥의 이웃 영역까지 섞이므로 모두 캐시에 남도록 함
+    settings.max_cached_regions = ((count + 1) * 2 + 1).pow(2) as usize;
+    let map = ErosionMap::new(&settings, config.seed);
+
+    let start = std::time::Instant::now();
+    let mut regions: HashMap<IVec2, Arc<ErodedRegion>> = HashMap::new();
+    for z in -count..count {
+        for x in -count..count {
+            let region = IVec2::new(x, z);
+            regions.insert(region, map.region(region, height.as_ref()));
+        }
+    }
+    let elapsed = start.elapsed();
+
+    let used = |x: i32, z: i32| map.height(x, z, height.as_ref());
+
+    let mut depth = (0.0, 0);
+    let mut overlap = (0.0, 0.0f32, 0);
+    let mut border_step = (0.0, 0);
+    let mut inner_step = (0.0, 0);
+
+    for (region, eroded) in &regions {
+        let min = *region * size;
+        for z in min.y..min.y + size {
+            for x in min.x..min.x + size {
+                let value = used(x, z);
+                depth.0 += (value - height(x, z)).abs();
+                depth.1 += 1;
+
+                // 영역 안에서 -x, -z 쪽 칸과의 높이 차이
+                if x > min.x {
+                    inner_step.0 += (value - used(x - 1, z)).abs();
+                    inner_step.1 += 1;
+                }
+                if z > min.y {
+                    inner_step.0 += (value - used(x, z - 1)).abs();
+                    inner_step.1 += 1;
+                }
+            }
+        }
+
+        // 오른쪽 (+x), 아래쪽 (+z) 이웃과의 경계
+        for axis in [IVec2::X, IVec2::Y] {
+            let Some(neighbor) = regions.get(&(*region + axis)) else {
+                continue;
+            };
+            let border = *region * size + axis * size;
+
+            for along in 0..size {
+                let cell = |offset: i32| {
+                    if axis == IVec2::X {
+                        (border.x + offset, border.y + along)
+                    } else {
+                        (border.x + along, border.y + offset)
+                    }
+                };
+
+                for offset in -BAND..BAND {
+                    let (x, z) = cell(offset);
+                    let diff = (eroded.get(x, z).unwrap() - neighbor.get(x, z).unwrap()).abs();
+                    overlap.0 += diff;
+                    overlap.1 = overlap.1.max(diff);
+                    overlap.2 += 1;
+                }
+
+                let ((x0, z0), (x1, z1)) = (cell(-1), cell(0));
+                border_step.0 += (used(x1, z1) - used(x0, z0)).abs();
+                border_step.1 += 1;
+            }
+        }
+    }
+
+    let mean = |(sum, count): (f32, i32)| sum / count.max(1) as f32;
+    println!(
+        "{}x{} regions ({} blocks, margin {}) in {:.2} s",
+        count * 2,
+        count * 2,
+        size,
+        settings.margin,
+        elapsed.as_secs_f64()
+    );
+    println!("  mean erosion depth : {:.3} blocks", mean(depth));
+    println!(
+        "  overlap near borders : mean diff {:.4}, max diff {:.4} blocks",
+        overlap.0 / overlap.2.max(1) as f32,
+        overlap.1
+    );
+    println!(
+        "  height step : across borders {:.3}, inside regions {:.3} blocks",
+        mean(border_step),
+        mean(inner_step)
+    );
+
+    if mean(border_step) > mean(inner_step) * MAX_STEP_RATIO {
+        return Err(format!(
+            "height steps across region borders are {:.2}x those inside regions (limit {})",
+            mean(border_step) / mean(inner_step).max(f32::EPSILON),
+            MAX_STEP_RATIO
+        ));
+    }
+    Ok(())
+}
@@ -0,0 +1,8 @@
+pub mod day_night;
+pub mod debug_render;
+pub mod fog;
+pub mod noise;
+pub mod settings;
+pub mod voxel;
+
+pub use voxel::plugin::{VoxelWorldPlugin, VoxelWorldPluginBuilder};
@@ -1,63 +1,66 @@
-mod noise;
-mod voxel;
+mod headless;
 
 use bevy::{
-    diagnostic::FrameTimeDiagnosticsPlugin, diagnostic::LogDiagnosticsPlugin, pbr::wireframe::{NoWireframe, Wireframe, WireframeColor, WireframeConfig, WireframePlugin}, prelude::*, render::{
-        render_resource::WgpuFeatures,
-        settings::{RenderCreation, WgpuSettings},
-        view::NoFrustumCulling,
-        RenderPlugin,
-    }, ui::update, window::{PresentMode, WindowTheme}
+    diagnostic::FrameTimeDiagnosticsPlugin, diagnostic::LogDiagnosticsPlugin, prelude::*, ui::update, window::{PresentMode, WindowTheme}
 };
 use bevy_flycam::prelude::*;
 use image::{flat::View, Rgb, Rgba};
-use voxel::world;
+use pcg_engine::voxel::world;
+use pcg_engine::day_night::DayNightPlugin;
+use pcg_engine::debug_render::DebugRenderPlugin;
+use pcg_engine::fog::DistanceFogPlugin;
+use pcg_engine::settings::{Settings, SettingsPlugin};
+use pcg_engine::VoxelWorldPlugin;
 use bevy_atmosphere::prelude::*;
 
-#[derive(Resource)]
-struct WindowSize {
-    x: i32,
-    y: i32,
-}
+const WORLD_SAVE_PATH: &str = "saves/world.ron";
 
-impl Default for WindowSize {
-    fn default() -> WindowSize {
-        WindowSize { x: 800, y: 600 }
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    match headless::HeadlessArgs::parse(&args) {
+        Ok(Some(headless_args)) => {
+            if let Err(e) = headless::run(&headless_args) {
+                eprintln!("headless export failed: {}", e);
+                std::process::exit(1);
+            }
+            return;
+        }
+        Ok(None) => {}
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(2);
+        }
     }
-}
 
-fn main() {
-    let window_size = WindowSize::default();
-    let mut voxel_world = voxel::world::World::new();
-    voxel_world.generate_world();
+    let settings = Settings::load_or_default(Settings::DEFAULT_PATH);
+    // 저장된 월드가 없으면 기본 설정으로 새로 시작
+    let world_config = world::WorldConfig::load(WORLD_SAVE_PATH).unwrap_or_default();
+    let mut voxel_world = match world::World::from_config(&world_config) {
+        Ok(voxel_world) => voxel_world,
+        Err(e) => {
+            eprintln!("{}: {}", WORLD_SAVE_PATH, e);
+            std::process::exit(2);
+        }
+    };
+    voxel_world.generate_world(settings.world_size);
+    // 플러그인이 미리 생성한 월드에 빌더의 뷰 거리 / 제거 거리를 적용하므로 설정 값을 그대로 넘김
+    let mut voxel_world_plugin = VoxelWorldPlugin::builder()
+        .world_config(world_config)
+        .world(voxel_world)
+        .view_distance(settings.view_distance)
+        .save_to(WORLD_SAVE_PATH);
+    if let Some(unload_distance) = settings.unload_distance {
+        voxel_world_plugin = voxel_world_plugin.unload_distance(unload_distance);
+    }
 
     App::new()
-        // .add_plugins((
-        //     DefaultPlugins.set(RenderPlugin {
-        //         render_creation: RenderCreation::Automatic(WgpuSettings {
-        //             features: WgpuFeatures::POLYGON_MODE_LINE,
-        //             ..default()
-        //         }),
-        //         ..default()
-        //     }),
-        //     //WireframePlugin,
-        // ))
-        // .insert_resource(WireframeConfig {
-        //     global: true,
-        //     default_color: Color::Rgba {
-        //         red: 1.0,
-        //         green: 1.0,
-        //         blue: 1.0,
-        //         alpha: 1.0,
-        //     },
-        // })
         .add_plugins(DefaultPlugins.set(WindowPlugin {
             primary_window: Some(Window {
-                title: "I am a boy".into(),
+                title: settings.window.title.clone(),
                 name: Some("bevy.app".into()),
                 position: WindowPosition::At(IVec2 { x: 0, y: 0 }),
-                resolution: (window_size.x as f32, window_size.y as f32).into(),
-                present_mode: PresentMode::AutoNoVsync,
+                resolution: (settings.window.width, settings.window.height).into(),
+                present_mode: settings.window.present_mode.into(),
                 prevent_default_event_handling: false,
                 window_theme: Some(WindowTheme::Dark),
                 enabled_buttons: bevy::window::EnabledButtons {
@@ -73,10 +76,14 @@ fn main() {
         .add_plugins((PlayerPlugin,AtmospherePlugin))
         .add_plugins(LogDiagnosticsPlugin::default())
         // .add_plugins(FrameTimeDiagnosticsPlugin::default())
-        .insert_resource(window_size)
-        .insert_resource(voxel_world)
-        .add_systems(Startup, world::setup)
-        .add_systems(Update, world::update)
-        .add_systems(PostUpdate, world::update_chunk_in_view_range)
+        .insert_resource(settings)
+        .add_plugins(SettingsPlugin::default())
+        .add_plugins(
+            voxel_world_plugin
+                .build()
+                .expect("world config was validated by World::from_config"),
+        )
+        .add_plugins((DayNightPlugin, DistanceFogPlugin, DebugRenderPlugin))
+        .add_systems(Startup, world::setup_lighting)
         .run();
 }
@@ -1,2 +1,4 @@
 pub mod basic_perlin;
 pub mod random_perlin;
+pub mod graph;
+pub mod hash;
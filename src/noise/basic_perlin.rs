@@ -1,4 +1,4 @@
-use rand::prelude::*;
+use super::hash::hash;
 use lazy_static::lazy_static;
 
 #[derive(Copy, Clone, Debug)]
@@ -35,21 +35,71 @@ impl Vector3 {
 }
 
 
-fn shuffle<T>(mut array: Vec<T>) -> Vec<T> {
-    let mut rng = thread_rng();
-    array.shuffle(&mut rng);
-    array
+// 0..256 을 시드로 섞은 순열표를 두 번 이어 붙임 (인덱스 + 1 이 범위를 넘지 않도록)
+// 같은 시드면 실행할 때마다 같은 노이즈
+pub struct BasicPerlin {
+    permutation: Vec<i32>,
 }
 
+impl BasicPerlin {
+    // 시드 없이 쓰는 perlin_noise2d / perlin_noise3d 의 시드
+    pub const DEFAULT_SEED: u32 = 0;
 
-lazy_static! {
-    static ref PERMUTATION: Vec<i32> = {
+    pub fn new(seed: u32) -> BasicPerlin {
         let mut permutation: Vec<i32> = (0..256).collect();
-        permutation = shuffle(permutation);
+        // Fisher-Yates, rand 구현이 바뀌어도 같은 순열이 나오도록 좌표 해시를 씀
+        for i in (1..256usize).rev() {
+            let j = hash(seed, i as i32, 0, 0) as usize % (i + 1);
+            permutation.swap(i, j);
+        }
         let mut result = permutation.clone();
         result.extend(permutation);
-        result
-    };
+        BasicPerlin {
+            permutation: result,
+        }
+    }
+
+    pub fn noise2d(&self, x: f32, y: f32) -> f32 {
+        noise2d(&self.permutation, x, y)
+    }
+
+    pub fn noise3d(&self, x: f32, y: f32, z: f32) -> f32 {
+        noise3d(&self.permutation, x, y, z)
+    }
+
+    pub fn perlin_noise2d(&self, x: f32, y: f32, num_octaves: i32) -> f32 {
+        let mut result = 0.0;
+        let mut amplitude = 1.0;
+        let mut frequency = 0.01;
+        let persistence = 0.5;
+
+        for _ in 0..num_octaves {
+            result += amplitude * self.noise2d(x * frequency, y * frequency);
+            amplitude *= persistence;
+            frequency *= 2.0;
+        }
+
+        result.clamp(-1.0, 1.0)
+    }
+
+    pub fn perlin_noise3d(&self, x: f32, y: f32, z: f32, num_octaves: i32) -> f32 {
+        let mut result = 0.0;
+        let mut amplitude = 1.0;
+        let mut frequency = 0.005;
+        let persistence = 0.5;
+
+        for _ in 0..num_octaves {
+            result += amplitude * self.noise3d(x * frequency, y * frequency, z * frequency);
+            amplitude *= persistence;
+            frequency *= 2.0;
+        }
+
+        result.clamp(-1.0, 1.0)
+    }
+}
+
+lazy_static! {
+    static ref DEFAULT: BasicPerlin = BasicPerlin::new(BasicPerlin::DEFAULT_SEED);
 }
 
 fn get_gradient_vector2d(v: i32) -> Vector2 {
@@ -62,13 +112,23 @@ fn get_gradient_vector2d(v: i32) -> Vector2 {
     }
 }
 
+// Improved Perlin noise 의 12개 모서리 방향 그라디언트
+// 16개로 맞추기 위해 12~15 는 (1,1,0), (-1,1,0), (0,-1,1), (0,-1,-1) 을 한 번 더 사용
 fn get_gradient_vector3d(v: i32) -> Vector3 {
     let h = v & 15;
     match h {
-        0 | 1 | 4 | 11 => Vector3::new(1.0, 1.0, 0.0),
-        2 | 3 | 5 | 10 => Vector3::new(-1.0, 1.0, 0.0),
-        6 | 7 | 8 | 9 => Vector3::new(-1.0, -1.0, 0.0),
-        _ => Vector3::new(1.0, -1.0, 0.0),
+        0 | 12 => Vector3::new(1.0, 1.0, 0.0),
+        1 | 13 => Vector3::new(-1.0, 1.0, 0.0),
+        2 => Vector3::new(1.0, -1.0, 0.0),
+        3 => Vector3::new(-1.0, -1.0, 0.0),
+        4 => Vector3::new(1.0, 0.0, 1.0),
+        5 => Vector3::new(-1.0, 0.0, 1.0),
+        6 => Vector3::new(1.0, 0.0, -1.0),
+        7 => Vector3::new(-1.0, 0.0, -1.0),
+        8 => Vector3::new(0.0, 1.0, 1.0),
+        9 | 14 => Vector3::new(0.0, -1.0, 1.0),
+        10 => Vector3::new(0.0, 1.0, -1.0),
+        _ => Vector3::new(0.0, -1.0, -1.0),
     }
 }
 
@@ -81,7 +141,7 @@ fn lerp(t: f32, a1: f32, a2: f32) -> f32 {
 }
 
 
-fn noise2d(x: f32, y: f32) -> f32 {
+fn noise2d(permutation: &[i32], x: f32, y: f32) -> f32 {
     let origin_x = x;
     let origin_y = y;
     let x = (x.floor() as i32) & 255;
@@ -90,10 +150,10 @@ fn noise2d(x: f32, y: f32) -> f32 {
     let xf = origin_x as f32 - origin_x.floor();
     let yf = origin_y as f32 - origin_y.floor();
 
-    let value_top_right = PERMUTATION[(PERMUTATION[((x + 1) & 255) as usize] + (y + 1) & 255) as usize];
-    let value_top_left = PERMUTATION[(PERMUTATION[x as usize] + (y + 1) & 255) as usize];
-    let value_bottom_right = PERMUTATION[(PERMUTATION[((x + 1) & 255) as usize] + y) as usize];
-    let value_bottom_left = PERMUTATION[(PERMUTATION[x as usize] + y) as usize];
+    let value_top_right = permutation[(permutation[((x + 1) & 255) as usize] + (y + 1) & 255) as usize];
+    let value_top_left = permutation[(permutation[x as usize] + (y + 1) & 255) as usize];
+    let value_bottom_right = permutation[(permutation[((x + 1) & 255) as usize] + y) as usize];
+    let value_bottom_left = permutation[(permutation[x as usize] + y) as usize];
 
     let top_right = Vector2::new(xf - 1.0, yf - 1.0);
     let top_left = Vector2::new(xf, yf - 1.0);
@@ -114,11 +174,11 @@ fn noise2d(x: f32, y: f32) -> f32 {
         lerp(u, dot_top_left, dot_top_right),
     )
 }
-fn noise3d(x: f32, y: f32, z: f32) -> f32 {
+fn noise3d(permutation: &[i32], x: f32, y: f32, z: f32) -> f32 {
     let origin_x = x;
     let origin_y = y;
     let origin_z = z;
-    
+
     let x = (x.floor() as i32) & 255;
     let y = (y.floor() as i32) & 255;
     let z = (z.floor() as i32) & 255;
@@ -127,32 +187,34 @@ fn noise3d(x: f32, y: f32, z: f32) -> f32 {
     let yf = origin_y - origin_y.floor();
     let zf = origin_z - origin_z.floor();
 
-    let value_bottom_front_left = PERMUTATION[(PERMUTATION[((x + 1) & 255) as usize] + (y + 1) & 255) as usize];
-    let value_bottom_front_right = PERMUTATION[(PERMUTATION[x as usize] + (y + 1) & 255) as usize];
-    let value_bottom_back_left = PERMUTATION[(PERMUTATION[((x + 1) & 255) as usize] + y) as usize];
-    let value_bottom_back_right = PERMUTATION[(PERMUTATION[x as usize] + y) as usize];
-    let value_top_front_left = PERMUTATION[(PERMUTATION[((x + 1) & 255) as usize] + (y + 1) & 255) as usize];
-    let value_top_front_right = PERMUTATION[(PERMUTATION[x as usize] + (y + 1) & 255) as usize];
-    let value_top_back_left = PERMUTATION[(PERMUTATION[((x + 1) & 255) as usize] + y) as usize];
-    let value_top_back_right = PERMUTATION[(PERMUTATION[x as usize] + y) as usize];
-
-    let bottom_front_left = Vector3::new(xf - 1.0, yf - 1.0, zf - 1.0);
-    let bottom_front_right = Vector3::new(xf, yf - 1.0, zf - 1.0);
-    let bottom_back_left = Vector3::new(xf - 1.0, yf, zf - 1.0);
-    let bottom_back_right = Vector3::new(xf, yf, zf - 1.0);
-    let top_front_left = Vector3::new(xf - 1.0, yf - 1.0, zf);
-    let top_front_right = Vector3::new(xf, yf - 1.0, zf);
-    let top_back_left = Vector3::new(xf - 1.0, yf, zf);
-    let top_back_right = Vector3::new(xf, yf, zf);
-
-    let dot_bottom_front_left = bottom_front_left.dot(&get_gradient_vector3d(value_bottom_front_left));
-    let dot_bottom_front_right = bottom_front_right.dot(&get_gradient_vector3d(value_bottom_front_right));
-    let dot_bottom_back_left = bottom_back_left.dot(&get_gradient_vector3d(value_bottom_back_left));
-    let dot_bottom_back_right = bottom_back_right.dot(&get_gradient_vector3d(value_bottom_back_right));
-    let dot_top_front_left = top_front_left.dot(&get_gradient_vector3d(value_top_front_left));
-    let dot_top_front_right = top_front_right.dot(&get_gradient_vector3d(value_top_front_right));
-    let dot_top_back_left = top_back_left.dot(&get_gradient_vector3d(value_top_back_left));
-    let dot_top_back_right = top_back_right.dot(&get_gradient_vector3d(value_top_back_right));
+    // 8개 꼭짓점의 해시, x -> y -> z 순서로 순열표를 거침
+    // permutation 은 512 개이므로 (0..=255) + (0..=255) + 1 은 범위를 넘지 않음
+    let a = permutation[x as usize] + y;
+    let b = permutation[(x + 1) as usize] + y;
+    let aa = permutation[a as usize] + z;
+    let ab = permutation[(a + 1) as usize] + z;
+    let ba = permutation[b as usize] + z;
+    let bb = permutation[(b + 1) as usize] + z;
+
+    // value_(x)(y)(z), 0 은 셀의 아래쪽 꼭짓점, 1 은 위쪽 꼭짓점
+    let value_000 = permutation[aa as usize];
+    let value_100 = permutation[ba as usize];
+    let value_010 = permutation[ab as usize];
+    let value_110 = permutation[bb as usize];
+    let value_001 = permutation[(aa + 1) as usize];
+    let value_101 = permutation[(ba + 1) as usize];
+    let value_011 = permutation[(ab + 1) as usize];
+    let value_111 = permutation[(bb + 1) as usize];
+
+    let dot_000 = Vector3::new(xf, yf, zf).dot(&get_gradient_vector3d(value_000));
+    let dot_100 = Vector3::new(xf - 1.0, yf, zf).dot(&get_gradient_vector3d(value_100));
+    let dot_010 = Vector3::new(xf, yf - 1.0, zf).dot(&get_gradient_vector3d(value_010));
+    let dot_110 = Vector3::new(xf - 1.0, yf - 1.0, zf).dot(&get_gradient_vector3d(value_110));
+    let dot_001 = Vector3::new(xf, yf, zf - 1.0).dot(&get_gradient_vector3d(value_001));
+    let dot_101 = Vector3::new(xf - 1.0, yf, zf - 1.0).dot(&get_gradient_vector3d(value_101));
+    let dot_011 = Vector3::new(xf, yf - 1.0, zf - 1.0).dot(&get_gradient_vector3d(value_011));
+    let dot_111 =
+        Vector3::new(xf - 1.0, yf - 1.0, zf - 1.0).dot(&get_gradient_vector3d(value_111));
 
     let u = fade(xf);
     let v = fade(yf);
@@ -162,43 +224,98 @@ fn noise3d(x: f32, y: f32, z: f32) -> f32 {
         w,
         lerp(
             v,
-            lerp(u, dot_bottom_back_left, dot_bottom_back_right),
-            lerp(u, dot_bottom_front_left, dot_bottom_front_right),
+            lerp(u, dot_000, dot_100),
+            lerp(u, dot_010, dot_110),
         ),
         lerp(
             v,
-            lerp(u, dot_top_back_left, dot_top_back_right),
-            lerp(u, dot_top_front_left, dot_top_front_right),
+            lerp(u, dot_001, dot_101),
+            lerp(u, dot_011, dot_111),
         ),
     )
 }
 
 pub fn perlin_noise2d(x: f32, y: f32, num_octaves: i32) -> f32 {
-    let mut result = 0.0;
-    let mut amplitude = 1.0;
-    let mut frequency = 0.01;
-    let persistence = 0.5;
+    DEFAULT.perlin_noise2d(x, y, num_octaves)
+}
+
+pub fn perlin_noise3d(x: f32, y: f32, z: f32, num_octaves: i32) -> f32 {
+    DEFAULT.perlin_noise3d(x, y, z, num_octaves)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    for _ in 0..num_octaves {
-        result += amplitude * noise2d(x * frequency, y * frequency);
-        amplitude *= persistence;
-        frequency *= 2.0;
+    // 격자점을 피한 표본 좌표
+    fn samples() -> impl Iterator<Item = (f32, f32, f32)> {
+        (0..4000).map(|i| {
+            let i = i as f32;
+            (i * 0.137 - 200.0, i * 0.291 + 13.5, i * 0.053 - 71.3)
+        })
     }
 
-    result.clamp(-1.0, 1.0)
-}
+    #[test]
+    fn same_seed_gives_same_noise() {
+        let (a, b, c) = (
+            BasicPerlin::new(7),
+            BasicPerlin::new(7),
+            BasicPerlin::new(8),
+        );
+        let mut differs = false;
+        for (x, y, z) in samples().take(200) {
+            assert_eq!(a.noise2d(x, y), b.noise2d(x, y));
+            assert_eq!(a.noise3d(x, y, z), b.noise3d(x, y, z));
+            differs |= a.noise3d(x, y, z) != c.noise3d(x, y, z);
+        }
+        assert!(differs);
+    }
 
-pub fn perlin_noise3d(x: f32, y: f32, z: f32, num_octaves: i32) -> f32 {
-    let mut result = 0.0;
-    let mut amplitude = 1.0;
-    let mut frequency = 0.005;
-    let persistence = 0.5;
+    #[test]
+    fn range_and_mean() {
+        let perlin = BasicPerlin::new(3);
+        let (mut sum2, mut sum3, mut max2, mut max3) = (0.0f64, 0.0f64, 0.0f32, 0.0f32);
+        for (x, y, z) in samples() {
+            let v2 = perlin.noise2d(x, y);
+            let v3 = perlin.noise3d(x, y, z);
+            assert!(v2.abs() <= 1.0, "2d {} at {} {}", v2, x, y);
+            assert!(v3.abs() <= 1.1, "3d {} at {} {} {}", v3, x, y, z);
+            sum2 += v2 as f64;
+            sum3 += v3 as f64;
+            max2 = max2.max(v2.abs());
+            max3 = max3.max(v3.abs());
+        }
+        let count = samples().count() as f64;
+        assert!((sum2 / count).abs() < 0.05, "2d mean {}", sum2 / count);
+        assert!((sum3 / count).abs() < 0.05, "3d mean {}", sum3 / count);
+        // 값이 한쪽으로 눌려 있지 않은지
+        assert!(max2 > 0.5 && max3 > 0.5, "max {} {}", max2, max3);
+    }
 
-    for _ in 0..num_octaves {
-        result += amplitude * noise3d(x * frequency, y * frequency, z * frequency);
-        amplitude *= persistence;
-        frequency *= 2.0;
+    #[test]
+    fn zero_at_lattice_points() {
+        let perlin = BasicPerlin::new(11);
+        for i in -5..5 {
+            let (x, y, z) = (i as f32, (i * 3) as f32, (i * 7) as f32);
+            assert!(perlin.noise2d(x, y).abs() < 1e-6);
+            assert!(perlin.noise3d(x, y, z).abs() < 1e-6);
+        }
     }
 
-    result.clamp(-1.0, 1.0)
+    #[test]
+    fn continuous_across_lattice_cells() {
+        let perlin = BasicPerlin::new(5);
+        let eps = 1e-3;
+        // 순열표가 한 바퀴 도는 255 -> 256 과 음수 경계 포함
+        for k in [-256.0, -1.0, 0.0, 1.0, 17.0, 255.0, 256.0] {
+            for (_, t, u) in samples().take(50) {
+                let across = |f: &dyn Fn(f32) -> f32| (f(k - eps) - f(k + eps)).abs();
+                assert!(across(&|v| perlin.noise2d(v, t)) < 0.01, "x = {}", k);
+                assert!(across(&|v| perlin.noise2d(t, v)) < 0.01, "y = {}", k);
+                assert!(across(&|v| perlin.noise3d(v, t, u)) < 0.01, "x = {}", k);
+                assert!(across(&|v| perlin.noise3d(t, v, u)) < 0.01, "y = {}", k);
+                assert!(across(&|v| perlin.noise3d(t, u, v)) < 0.01, "z = {}", k);
+            }
+        }
+    }
 }
@@ -0,0 +1,381 @@
+use super::basic_perlin::BasicPerlin;
+use super::random_perlin::RandomPerlin;
+use noise::{Fbm, MultiFractal, NoiseFn, Perlin, Simplex};
+use serde::{Deserialize, Serialize};
+
+// 설정 파일 (RON) 로 기술하는 노이즈 노드 트리
+// 모든 노드는 월드 좌표 (x, y, z) 에서 값을 계산하고, 2D 소스는 (x, z) 만 사용함
+//
+// 예)
+// ScaleBias(
+//     source: Add([Perlin2d(frequency: 0.001), Perlin2d(frequency: 0.002)]),
+//     scale: 19.0,
+//     bias: 0.0,
+// )
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum NoiseNode {
+    // noise crate 소스, seed_offset 은 월드 시드에 더해짐
+    Perlin2d {
+        #[serde(default)]
+        seed_offset: u32,
+        frequency: f64,
+    },
+    Perlin3d {
+        #[serde(default)]
+        seed_offset: u32,
+        frequency: f64,
+    },
+    Simplex2d {
+        #[serde(default)]
+        seed_offset: u32,
+        frequency: f64,
+    },
+    Simplex3d {
+        #[serde(default)]
+        seed_offset: u32,
+        frequency: f64,
+    },
+    Fbm2d {
+        #[serde(default)]
+        seed_offset: u32,
+        frequency: f64,
+        octaves: usize,
+        persistence: f64,
+        lacunarity: f64,
+    },
+
+    // 직접 구현한 노이즈 (crate::noise), 내부 주파수에 frequency 를 곱함
+    // 시드는 위의 소스처럼 월드 시드 + seed_offset
+    BasicPerlin2d {
+        #[serde(default)]
+        seed_offset: u32,
+        frequency: f64,
+        octaves: i32,
+    },
+    BasicPerlin3d {
+        #[serde(default)]
+        seed_offset: u32,
+        frequency: f64,
+        octaves: i32,
+    },
+    RandomPerlin2d {
+        #[serde(default)]
+        seed_offset: u32,
+        frequency: f64,
+        octaves: i32,
+    },
+    RandomPerlin3d {
+        #[serde(default)]
+        seed_offset: u32,
+        frequency: f64,
+        octaves: i32,
+    },
+
+    Constant(f64),
+    Add(Vec<NoiseNode>),
+    Multiply(Vec<NoiseNode>),
+    // source * scale + bias
+    ScaleBias {
+        source: Box<NoiseNode>,
+        scale: f64,
+        bias: f64,
+    },
+    // (입력, 출력) 제어점 사이를 선형 보간, 범위 밖은 양 끝 값으로 고정
+    Curve {
+        source: Box<NoiseNode>,
+        points: Vec<(f64, f64)>,
+    },
+    // control < threshold 이면 low, 아니면 high
+    // falloff 가 0 보다 크면 threshold ± falloff 구간에서 부드럽게 섞음
+    Select {
+        control: Box<NoiseNode>,
+        low: Box<NoiseNode>,
+        high: Box<NoiseNode>,
+        threshold: f64,
+        #[serde(default)]
+        falloff: f64,
+    },
+    Clamp {
+        source: Box<NoiseNode>,
+        min: f64,
+        max: f64,
+    },
+}
+
+// 시드를 적용해 노이즈 인스턴스를 미리 만들어 둔 그래프
+// 복셀마다 Perlin::new 를 호출하지 않도록 한 번만 만들어서 재사용
+pub enum NoiseGraph {
+    Perlin2d(Perlin, f64),
+    Perlin3d(Perlin, f64),
+    Simplex2d(Simplex, f64),
+    Simplex3d(Simplex, f64),
+    Fbm2d(Fbm<Perlin>),
+    BasicPerlin2d(BasicPerlin, f64, i32),
+    BasicPerlin3d(BasicPerlin, f64, i32),
+    RandomPerlin2d(RandomPerlin, f64, i32),
+    RandomPerlin3d(RandomPerlin, f64, i32),
+    Constant(f64),
+    Add(Vec<NoiseGraph>),
+    Multiply(Vec<NoiseGraph>),
+    ScaleBias(Box<NoiseGraph>, f64, f64),
+    Curve(Box<NoiseGraph>, Vec<(f64, f64)>),
+    Select {
+        control: Box<NoiseGraph>,
+        low: Box<NoiseGraph>,
+        high: Box<NoiseGraph>,
+        threshold: f64,
+        falloff: f64,
+    },
+    Clamp(Box<NoiseGraph>, f64, f64),
+}
+
+impl NoiseNode {
+    pub fn build(&self, seed: u32) -> NoiseGraph {
+        let build = |node: &NoiseNode| Box::new(node.build(seed));
+
+        match self {
+            NoiseNode::Perlin2d {
+                seed_offset,
+                frequency,
+            } => NoiseGraph::Perlin2d(Perlin::new(seed.wrapping_add(*seed_offset)), *frequency),
+            NoiseNode::Perlin3d {
+                seed_offset,
+                frequency,
+            } => NoiseGraph::Perlin3d(Perlin::new(seed.wrapping_add(*seed_offset)), *frequency),
+            NoiseNode::Simplex2d {
+                seed_offset,
+                frequency,
+            } => NoiseGraph::Simplex2d(Simplex::new(seed.wrapping_add(*seed_offset)), *frequency),
+            NoiseNode::Simplex3d {
+                seed_offset,
+                frequency,
+            } => NoiseGraph::Simplex3d(Simplex::new(seed.wrapping_add(*seed_offset)), *frequency),
+            NoiseNode::Fbm2d {
+                seed_offset,
+                frequency,
+                octaves,
+                persistence,
+                lacunarity,
+            } => NoiseGraph::Fbm2d(
+                Fbm::<Perlin>::new(seed.wrapping_add(*seed_offset))
+                    .set_frequency(*frequency)
+                    .set_octaves(*octaves)
+                    .set_persistence(*persistence)
+                    .set_lacunarity(*lacunarity),
+            ),
+            NoiseNode::BasicPerlin2d {
+                seed_offset,
+                frequency,
+                octaves,
+            } => NoiseGraph::BasicPerlin2d(
+                BasicPerlin::new(seed.wrapping_add(*seed_offset)),
+                *frequency,
+                *octaves,
+            ),
+            NoiseNode::BasicPerlin3d {
+                seed_offset,
+                frequency,
+                octaves,
+            } => NoiseGraph::BasicPerlin3d(
+                BasicPerlin::new(seed.wrapping_add(*seed_offset)),
+                *frequency,
+                *octaves,
+            ),
+            NoiseNode::RandomPerlin2d {
+                seed_offset,
+                frequency,
+                octaves,
+            } => NoiseGraph::RandomPerlin2d(
+                RandomPerlin::new(seed.wrapping_add(*seed_offset)),
+                *frequency,
+                *octaves,
+            ),
+            NoiseNode::RandomPerlin3d {
+                seed_offset,
+                frequency,
+                octaves,
+            } => NoiseGraph::RandomPerlin3d(
+                RandomPerlin::new(seed.wrapping_add(*seed_offset)),
+                *frequency,
+                *octaves,
+            ),
+            NoiseNode::Constant(value) => NoiseGraph::Constant(*value),
+            NoiseNode::Add(sources) => {
+                NoiseGraph::Add(sources.iter().map(|node| node.build(seed)).collect())
+            }
+            NoiseNode::Multiply(sources) => {
+                NoiseGraph::Multiply(sources.iter().map(|node| node.build(seed)).collect())
+            }
+            NoiseNode::ScaleBias {
+                source,
+                scale,
+                bias,
+            } => NoiseGraph::ScaleBias(build(source), *scale, *bias),
+            NoiseNode::Curve { source, points } => {
+                let mut points = points.clone();
+                points.sort_by(|a, b| a.0.total_cmp(&b.0));
+                NoiseGraph::Curve(build(source), points)
+            }
+            NoiseNode::Select {
+                control,
+                low,
+                high,
+                threshold,
+                falloff,
+            } => NoiseGraph::Select {
+                control: build(control),
+                low: build(low),
+                high: build(high),
+                threshold: *threshold,
+                falloff: *falloff,
+            },
+            NoiseNode::Clamp { source, min, max } => NoiseGraph::Clamp(build(source), *min, *max),
+        }
+    }
+}
+
+fn evaluate_curve(points: &[(f64, f64)], value: f64) -> f64 {
+    let (Some(first), Some(last)) = (points.first(), points.last()) else {
+        return value;
+    };
+    if value <= first.0 {
+        return first.1;
+    }
+    if value >= last.0 {
+        return last.1;
+    }
+
+    for pair in points.windows(2) {
+        let (x0, y0) = pair[0];
+        let (x1, y1) = pair[1];
+        if value <= x1 {
+            if x1 == x0 {
+                return y1;
+            }
+            let t = (value - x0) / (x1 - x0);
+            return y0 + t * (y1 - y0);
+        }
+    }
+    last.1
+}
+
+impl NoiseGraph {
+    pub fn get(&self, pos: [f64; 3]) -> f64 {
+        let [x, y, z] = pos;
+
+        match self {
+            NoiseGraph::Perlin2d(perlin, f) => perlin.get([x * f, z * f]),
+            NoiseGraph::Perlin3d(perlin, f) => perlin.get([x * f, y * f, z * f]),
+            NoiseGraph::Simplex2d(simplex, f) => simplex.get([x * f, z * f]),
+            NoiseGraph::Simplex3d(simplex, f) => simplex.get([x * f, y * f, z * f]),
+            NoiseGraph::Fbm2d(fbm) => fbm.get([x, z]),
+            NoiseGraph::BasicPerlin2d(perlin, f, octaves) => {
+                perlin.perlin_noise2d((x * f) as f32, (z * f) as f32, *octaves) as f64
+            }
+            NoiseGraph::BasicPerlin3d(perlin, f, octaves) => {
+                perlin.perlin_noise3d((x * f) as f32, (y * f) as f32, (z * f) as f32, *octaves)
+                    as f64
+            }
+            NoiseGraph::RandomPerlin2d(perlin, f, octaves) => {
+                perlin.perlin_noise2d((x * f) as f32, (z * f) as f32, *octaves) as f64
+            }
+            NoiseGraph::RandomPerlin3d(perlin, f, octaves) => {
+                perlin.perlin_noise3d((x * f) as f32, (y * f) as f32, (z * f) as f32, *octaves)
+                    as f64
+            }
+            NoiseGraph::Constant(value) => *value,
+            NoiseGraph::Add(sources) => sources.iter().map(|node| node.get(pos)).sum(),
+            NoiseGraph::Multiply(sources) => sources.iter().map(|node| node.get(pos)).product(),
+            NoiseGraph::ScaleBias(source, scale, bias) => source.get(pos) * scale + bias,
+            NoiseGraph::Curve(source, points) => evaluate_curve(points, source.get(pos)),
+            NoiseGraph::Select {
+                control,
+                low,
+                high,
+                threshold,
+                falloff,
+            } => {
+                let control = control.get(pos);
+                if *falloff <= 0.0 {
+                    return if control < *threshold {
+                        low.get(pos)
+                    } else {
+                        high.get(pos)
+                    };
+                }
+
+                let lower = threshold - falloff;
+                let upper = threshold + falloff;
+                if control <= lower {
+                    low.get(pos)
+                } else if control >= upper {
+                    high.get(pos)
+                } else {
+                    // smoothstep 으로 두 출력을 섞음
+                    let t = (control - lower) / (upper - lower);
+                    let t = t * t * (3.0 - 2.0 * t);
+                    let low = low.get(pos);
+                    low + t * (high.get(pos) - low)
+                }
+            }
+            NoiseGraph::Clamp(source, min, max) => source.get(pos).clamp(*min, *max),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 모든 소스 노드를 한 번씩 쓰는 그래프
+    fn all_sources() -> NoiseNode {
+        ron::from_str(
+            "Add([
+                Perlin2d(frequency: 0.01),
+                Perlin3d(seed_offset: 1, frequency: 0.02),
+                Simplex2d(frequency: 0.01),
+                Simplex3d(frequency: 0.02),
+                Fbm2d(frequency: 0.01, octaves: 3, persistence: 0.5, lacunarity: 2.0),
+                BasicPerlin2d(frequency: 1.0, octaves: 3),
+                BasicPerlin3d(seed_offset: 2, frequency: 1.0, octaves: 3),
+                RandomPerlin2d(frequency: 1.0, octaves: 2),
+                RandomPerlin3d(frequency: 1.0, octaves: 2),
+            ])",
+        )
+        .unwrap()
+    }
+
+    fn samples() -> impl Iterator<Item = [f64; 3]> {
+        (0..200).map(|i| {
+            let i = i as f64;
+            [i * 3.7 - 300.0, i * 0.31, i * 5.3 + 17.0]
+        })
+    }
+
+    #[test]
+    fn same_seed_gives_same_output() {
+        let node = all_sources();
+        let (a, b) = (node.build(42), node.build(42));
+        for pos in samples() {
+            assert_eq!(a.get(pos), b.get(pos));
+        }
+    }
+
+    #[test]
+    fn custom_sources_follow_the_seed() {
+        for source in [
+            "BasicPerlin2d(frequency: 1.0, octaves: 3)",
+            "BasicPerlin3d(frequency: 1.0, octaves: 3)",
+            "RandomPerlin2d(frequency: 1.0, octaves: 2)",
+            "RandomPerlin3d(frequency: 1.0, octaves: 2)",
+        ] {
+            let node: NoiseNode = ron::from_str(source).unwrap();
+            let (a, b) = (node.build(1), node.build(2));
+            assert!(
+                samples().any(|pos| a.get(pos) != b.get(pos)),
+                "{} ignores the seed",
+                source
+            );
+        }
+    }
+}
@@ -0,0 +1,19 @@
+// 좌표 기반 정수 해시, 같은 (seed, x, z, i) 는 항상 같은 값
+// 물방울 / 강 시작점처럼 생성 순서와 상관없이 월드 좌표마다 정해져야 하는 난수에 사용
+pub fn hash(seed: u32, x: i32, z: i32, i: u32) -> u32 {
+    let mut h = seed
+        ^ (x as u32).wrapping_mul(0x27d4_eb2d)
+        ^ (z as u32).wrapping_mul(0x1656_67b1)
+        ^ i.wrapping_mul(0x9e37_79b9);
+    h ^= h >> 15;
+    h = h.wrapping_mul(0x2c1b_3c6d);
+    h ^= h >> 12;
+    h = h.wrapping_mul(0x297a_2d39);
+    h ^= h >> 15;
+    h
+}
+
+// hash 값을 0 ~ 1 로
+pub fn unit(h: u32) -> f32 {
+    (h >> 8) as f32 / (1 << 24) as f32
+}
@@ -11,74 +11,179 @@ pub fn interpolate(a0: f32, a1: f32, w: f32) -> f32 {
     (a1 - a0) * w + a0
 }
 
-pub fn random_gradient(ix: u32, iy: u32) -> Vector2 {
-    let w: u32 = 8 * 4;
-    let s: u32 = w / 2;
-    let mut a = ix;
-    let mut b = iy;
-    a = a.wrapping_mul(3284157443);
-    b ^= a << s | a >> w - s;
-    b = b.wrapping_mul(1911520717);
-    a ^= b << s | b >> w - s;
-    a = a.wrapping_mul(2048419325);
-
-    let random = a as f32 * (PI / 2147483648.0); // in [0, 2*Pi]
-    Vector2 {
-        x: random.cos(),
-        y: random.sin(),
+// 시드별 그라디언트 노이즈, 순열표 없이 격자점 좌표를 해시해서 그라디언트를 고름
+// 시드 0 은 예전 (시드 없는) 결과와 같음
+#[derive(Copy, Clone, Debug, Default)]
+pub struct RandomPerlin {
+    seed: u32,
+}
+
+impl RandomPerlin {
+    pub fn new(seed: u32) -> RandomPerlin {
+        RandomPerlin { seed }
+    }
+
+    pub fn random_gradient(&self, ix: u32, iy: u32) -> Vector2 {
+        let w: u32 = 8 * 4;
+        let s: u32 = w / 2;
+        let mut a = ix ^ self.seed.wrapping_mul(0x9e37_79b9);
+        let mut b = iy;
+        a = a.wrapping_mul(3284157443);
+        b ^= (a << s) | (a >> (w - s));
+        b = b.wrapping_mul(1911520717);
+        a ^= (b << s) | (b >> (w - s));
+        a = a.wrapping_mul(2048419325);
+
+        let random = a as f32 * (PI / 2147483648.0); // in [0, 2*Pi]
+        Vector2 {
+            x: random.cos(),
+            y: random.sin(),
+        }
+    }
+
+    pub fn dot_grid_gradient(&self, ix: i32, iy: i32, x: f32, y: f32) -> f32 {
+        let gradient = self.random_gradient(ix as u32, iy as u32);
+        let dx = x - ix as f32;
+        let dy = y - iy as f32;
+        dx * gradient.x + dy * gradient.y
+    }
+
+    pub fn perlin(&self, x: f32, y: f32) -> f32 {
+        let x0 = x.floor() as i32;
+        let x1 = x0 + 1;
+        let y0 = y.floor() as i32;
+        let y1 = y0 + 1;
+
+        let sx = x - x0 as f32;
+        let sy = y - y0 as f32;
+
+        let n0 = self.dot_grid_gradient(x0, y0, x, y);
+        let n1 = self.dot_grid_gradient(x1, y0, x, y);
+        let ix0 = interpolate(n0, n1, sx);
+
+        let n0 = self.dot_grid_gradient(x0, y1, x, y);
+        let n1 = self.dot_grid_gradient(x1, y1, x, y);
+        let ix1 = interpolate(n0, n1, sx);
+
+        interpolate(ix0, ix1, sy)
+    }
+
+    pub fn perlin_noise2d(&self, x: f32, y: f32, num_octaves: i32) -> f32 {
+        let mut result = 0.0;
+        let mut amplitude = 1.0;
+        let mut frequency = 0.05;
+        let persistence = 1.0; // Persistence value adjustment
+
+        for _ in 0..num_octaves {
+            let n = amplitude * self.perlin(x * frequency, y * frequency);
+            result += n;
+            amplitude *= persistence; // Amplitude decay
+            frequency *= 2.0;
+        }
+
+        result.clamp(-1., 1.)
+    }
+
+    pub fn perlin_noise3d(&self, x: f32, y: f32, z: f32, num_octaves: i32) -> f32 {
+        let noise_xy = self.perlin_noise2d(x, y, num_octaves);
+        let noise_yz = self.perlin_noise2d(y, z, num_octaves);
+        let noise_zx = self.perlin_noise2d(z, x, num_octaves);
+
+        noise_xy * noise_yz * noise_zx
     }
 }
 
+pub fn random_gradient(ix: u32, iy: u32) -> Vector2 {
+    RandomPerlin::default().random_gradient(ix, iy)
+}
+
 pub fn dot_grid_gradient(ix: i32, iy: i32, x: f32, y: f32) -> f32 {
-    let gradient = random_gradient(ix as u32, iy as u32);
-    let dx = x - ix as f32;
-    let dy = y - iy as f32;
-    dx * gradient.x + dy * gradient.y
+    RandomPerlin::default().dot_grid_gradient(ix, iy, x, y)
 }
 
 pub fn perlin(x: f32, y: f32) -> f32 {
-    let x0 = x.floor() as i32;
-    let x1 = x0 + 1;
-    let y0 = y.floor() as i32;
-    let y1 = y0 + 1;
-
-    let sx = x - x0 as f32;
-    let sy = y - y0 as f32;
+    RandomPerlin::default().perlin(x, y)
+}
 
-    let n0 = dot_grid_gradient(x0, y0, x, y);
-    let n1 = dot_grid_gradient(x1, y0, x, y);
-    let ix0 = interpolate(n0, n1, sx);
+pub fn perlin_noise2d(x: f32, y: f32, num_octaves: i32) -> f32 {
+    RandomPerlin::default().perlin_noise2d(x, y, num_octaves)
+}
 
-    let n0 = dot_grid_gradient(x0, y1, x, y);
-    let n1 = dot_grid_gradient(x1, y1, x, y);
-    let ix1 = interpolate(n0, n1, sx);
+pub fn perlin_noise3d(x: f32, y: f32, z: f32, num_octaves: i32) -> f32 {
+    RandomPerlin::default().perlin_noise3d(x, y, z, num_octaves)
+}
 
-    let result = interpolate(ix0, ix1, sy);
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    result
-}
+    fn samples() -> impl Iterator<Item = (f32, f32)> {
+        (0..4000).map(|i| {
+            let i = i as f32;
+            (i * 0.137 - 200.0, i * 0.291 + 13.5)
+        })
+    }
 
-pub fn perlin_noise2d(x: f32, y: f32, num_octaves: i32) -> f32 {
-    let mut result = 0.0;
-    let mut amplitude = 1.0;
-    let mut frequency = 0.05;
-    let persistence = 1.0; // Persistence value adjustment
-
-    for _ in 0..num_octaves {
-        let n = amplitude * perlin(x * frequency, y * frequency);
-        result += n;
-        amplitude *= persistence; // Amplitude decay
-        frequency *= 2.0;
+    #[test]
+    fn range_and_mean() {
+        let (mut sum, mut max) = (0.0f64, 0.0f32);
+        for (x, y) in samples() {
+            let v = perlin(x, y);
+            // 단위 길이 그라디언트이므로 ±√2/2 안
+            assert!(v.abs() <= 0.7072, "{} at {} {}", v, x, y);
+            sum += v as f64;
+            max = max.max(v.abs());
+
+            let octaves = perlin_noise2d(x * 10.0, y * 10.0, 3);
+            assert!((-1.0..=1.0).contains(&octaves));
+            let v3 = perlin_noise3d(x * 10.0, y * 10.0, (x - y) * 10.0, 3);
+            assert!((-1.0..=1.0).contains(&v3));
+        }
+        let mean = sum / samples().count() as f64;
+        assert!(mean.abs() < 0.05, "mean {}", mean);
+        assert!(max > 0.4, "max {}", max);
     }
 
-    result = result.clamp(-1., 1.);
-    result
-}
+    #[test]
+    fn same_seed_gives_same_noise() {
+        let (a, b, c) = (
+            RandomPerlin::new(7),
+            RandomPerlin::new(7),
+            RandomPerlin::new(8),
+        );
+        let mut differs = false;
+        for (x, y) in samples().take(200) {
+            assert_eq!(a.perlin(x, y), b.perlin(x, y));
+            differs |= a.perlin(x, y) != c.perlin(x, y);
+        }
+        assert!(differs);
+        // 시드 0 은 시드 없는 함수와 같음
+        assert_eq!(RandomPerlin::new(0).perlin(1.3, -2.7), perlin(1.3, -2.7));
+    }
 
-pub fn perlin_noise3d(x: f32, y: f32, z: f32, num_octaves: i32) -> f32 {
-    let noise_xy = perlin_noise2d(x, y, num_octaves);
-    let noise_yz = perlin_noise2d(y, z, num_octaves);
-    let noise_zx = perlin_noise2d(z, x, num_octaves);
+    #[test]
+    fn zero_at_lattice_points() {
+        for i in -5..5 {
+            assert!(perlin(i as f32, (i * 3) as f32).abs() < 1e-6);
+        }
+    }
 
-    noise_xy * noise_yz * noise_zx
+    #[test]
+    fn continuous_across_lattice_cells() {
+        let eps = 1e-3;
+        for k in [-256.0, -1.0, 0.0, 1.0, 17.0, 255.0, 256.0] {
+            for (_, t) in samples().take(50) {
+                assert!(
+                    (perlin(k - eps, t) - perlin(k + eps, t)).abs() < 0.01,
+                    "x = {}",
+                    k
+                );
+                assert!(
+                    (perlin(t, k - eps) - perlin(t, k + eps)).abs() < 0.01,
+                    "y = {}",
+                    k
+                );
+            }
+        }
+    }
 }
@@ -0,0 +1,390 @@
+use crate::fog::DistanceFog;
+use crate::voxel::mesh::VoxelData;
+use crate::voxel::world::World;
+use bevy::prelude::*;
+use bevy::window::{PresentMode, PrimaryWindow};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+// 실행 중에 바꿀 수 있는 설정, RON 파일 (기본 settings.ron)
+// 빠진 항목은 기본값을 사용
+#[derive(Resource, Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub window: WindowSettings,
+    // 카메라 주변으로 활성화할 청크 범위 (청크 단위)
+    pub view_distance: i32,
+    // 카메라에서 이보다 먼 청크는 메모리에서 제거, None 이면 제거하지 않음
+    pub unload_distance: Option<i32>,
+    // 시작할 때 원점 주변에 미리 생성할 청크 수 (한 변), 시작할 때만 사용
+    pub world_size: i32,
+    pub shadows: bool,
+    pub fog: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WindowSettings {
+    pub title: String,
+    pub width: f32,
+    pub height: f32,
+    pub present_mode: PresentModeSetting,
+}
+
+// bevy 의 PresentMode 는 serde 기능 없이 직렬화되지 않아서 따로 둠
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PresentModeSetting {
+    AutoVsync,
+    AutoNoVsync,
+    Fifo,
+    Immediate,
+    Mailbox,
+}
+
+impl From<PresentModeSetting> for PresentMode {
+    fn from(mode: PresentModeSetting) -> Self {
+        match mode {
+            PresentModeSetting::AutoVsync => PresentMode::AutoVsync,
+            PresentModeSetting::AutoNoVsync => PresentMode::AutoNoVsync,
+            PresentModeSetting::Fifo => PresentMode::Fifo,
+            PresentModeSetting::Immediate => PresentMode::Immediate,
+            PresentModeSetting::Mailbox => PresentMode::Mailbox,
+        }
+    }
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            window: WindowSettings::default(),
+            view_distance: VoxelData::VIEW_DISTANCE_IN_CHUNKS,
+            unload_distance: None,
+            world_size: VoxelData::WORLD_SIZE as i32,
+            shadows: true,
+            fog: true,
+        }
+    }
+}
+
+impl Default for WindowSettings {
+    fn default() -> Self {
+        WindowSettings {
+            title: "I am a boy".to_string(),
+            width: 800.0,
+            height: 600.0,
+            present_mode: PresentModeSetting::AutoNoVsync,
+        }
+    }
+}
+
+impl Settings {
+    pub const DEFAULT_PATH: &'static str = "settings.ron";
+
+    // 읽기만 하고 값은 검사하지 않음, 적용하기 전에 validate 를 부를 것
+    pub fn load<P: AsRef<Path>>(path: P) -> std::io::Result<Settings> {
+        let text = std::fs::read_to_string(path)?;
+        ron::from_str(&text)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+    }
+
+    // 파일이 없거나 잘못됐으면 경고만 남기고 기본값 사용
+    // 앱을 만들기 전 (로그 플러그인 전) 에도 부르므로 eprintln 사용
+    pub fn load_or_default<P: AsRef<Path>>(path: P) -> Settings {
+        match Self::load(path.as_ref()) {
+            Ok(mut settings) => {
+                for warning in settings.validate() {
+                    eprintln!("{}: {}", path.as_ref().display(), warning);
+                }
+                settings
+            }
+            Err(e) => {
+                eprintln!("{}: {}, using default settings", path.as_ref().display(), e);
+                Settings::default()
+            }
+        }
+    }
+
+    // 쓸 수 없는 값을 가까운 값으로 고치고, 고친 항목마다 경고 문구를 돌려줌
+    // 월드에 넣을 값과 같게 맞춰둬야 적용할 때마다 다르다고 판단해서 다시 넣지 않음
+    pub fn validate(&mut self) -> Vec<String> {
+        let mut warnings = Vec::new();
+        if self.view_distance < 1 {
+            warnings.push(format!(
+                "view_distance {} is less than 1, using 1",
+                self.view_distance
+            ));
+            self.view_distance = 1;
+        }
+        if let Some(unload_distance) = self.unload_distance {
+            if unload_distance < self.view_distance {
+                warnings.push(format!(
+                    "unload_distance {} is less than view_distance, using {}",
+                    unload_distance, self.view_distance
+                ));
+                self.unload_distance = Some(self.view_distance);
+            }
+        }
+        if self.world_size < 0 {
+            warnings.push(format!(
+                "world_size {} is negative, using 0",
+                self.world_size
+            ));
+            self.world_size = 0;
+        }
+        let window = &mut self.window;
+        if !(window.width > 0.0
+            && window.height > 0.0
+            && window.width.is_finite()
+            && window.height.is_finite())
+        {
+            let default = WindowSettings::default();
+            warnings.push(format!(
+                "window size {}x{} is invalid, using {}x{}",
+                window.width, window.height, default.width, default.height
+            ));
+            window.width = default.width;
+            window.height = default.height;
+        }
+        warnings
+    }
+}
+
+impl WindowSettings {
+    // 바뀐 값만 넣어서 창의 change detection 을 불필요하게 건드리지 않음
+    pub fn apply(&self, window: &mut Window) {
+        if window.title != self.title {
+            window.title = self.title.clone();
+        }
+        if window.resolution.width() != self.width || window.resolution.height() != self.height {
+            window.resolution.set(self.width, self.height);
+        }
+        let present_mode = self.present_mode.into();
+        if window.present_mode != present_mode {
+            window.present_mode = present_mode;
+        }
+    }
+}
+
+// 설정 파일을 읽어 Settings 리소스로 넣고, 파일이 바뀌면 다시 읽어서 적용
+pub struct SettingsPlugin {
+    pub path: PathBuf,
+}
+
+impl Default for SettingsPlugin {
+    fn default() -> Self {
+        SettingsPlugin {
+            path: PathBuf::from(Settings::DEFAULT_PATH),
+        }
+    }
+}
+
+// 설정 파일 변경 확인 상태
+#[derive(Resource)]
+struct SettingsFile {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+    timer: Timer,
+}
+
+impl Plugin for SettingsPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.world.contains_resource::<Settings>() {
+            app.insert_resource(Settings::load_or_default(&self.path));
+        }
+
+        app.insert_resource(SettingsFile {
+            path: self.path.clone(),
+            modified: modified_time(&self.path),
+            timer: Timer::from_seconds(1.0, TimerMode::Repeating),
+        })
+        .add_systems(Update, reload_settings)
+        .add_systems(
+            Update,
+            (apply_window_settings, apply_world_settings, apply_render_settings)
+                .after(reload_settings)
+                .run_if(resource_changed::<Settings>),
+        );
+    }
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+// 1 초마다 파일 수정 시간을 확인
+fn reload_settings(
+    time: Res<Time>,
+    mut settings_file: ResMut<SettingsFile>,
+    mut settings: ResMut<Settings>,
+) {
+    if !settings_file.timer.tick(time.delta()).just_finished() {
+        return;
+    }
+
+    let modified = modified_time(&settings_file.path);
+    if modified == settings_file.modified {
+        return;
+    }
+    settings_file.modified = modified;
+
+    match Settings::load(&settings_file.path) {
+        Ok(mut loaded) => {
+            info!("reloaded {}", settings_file.path.display());
+            for warning in loaded.validate() {
+                warn!("{}: {}", settings_file.path.display(), warning);
+            }
+            settings.set_if_neq(loaded);
+        }
+        // 저장 중인 파일을 읽었을 수도 있으므로 이전 설정을 유지
+        Err(e) => warn!("{}: {}", settings_file.path.display(), e),
+    }
+}
+
+fn apply_window_settings(
+    settings: Res<Settings>,
+    mut windows: Query<&mut Window, With<PrimaryWindow>>,
+) {
+    for mut window in windows.iter_mut() {
+        settings.window.apply(&mut window);
+    }
+}
+
+// 뷰 거리가 바뀌면 다음 update 에서 청크 스트리밍에 바로 반영됨
+fn apply_world_settings(settings: Res<Settings>, voxel_world: Option<ResMut<World>>) {
+    let Some(mut voxel_world) = voxel_world else {
+        return;
+    };
+
+    if voxel_world.view_distance() != settings.view_distance {
+        voxel_world.set_view_distance(settings.view_distance);
+    }
+    if voxel_world.unload_distance() != settings.unload_distance {
+        voxel_world.set_unload_distance(settings.unload_distance);
+    }
+}
+
+// 안개의 범위와 색은 DistanceFogPlugin 이 뷰 거리와 시간에 맞춰 정함
+fn apply_render_settings(
+    settings: Res<Settings>,
+    mut lights: Query<&mut DirectionalLight>,
+    distance_fog: Option<ResMut<DistanceFog>>,
+) {
+    for mut light in lights.iter_mut() {
+        light.shadows_enabled = settings.shadows;
+    }
+
+    if let Some(mut distance_fog) = distance_fog {
+        distance_fog.enabled = settings.fog;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::voxel::generator::GeneratorConfig;
+    use crate::voxel::world::WorldConfig;
+    use bevy::time::TimeUpdateStrategy;
+    use std::time::Duration;
+
+    fn temp_file(name: &str, text: &str) -> PathBuf {
+        let path =
+            std::env::temp_dir().join(format!("pcg_engine_{}_{}.ron", name, std::process::id()));
+        std::fs::write(&path, text).unwrap();
+        path
+    }
+
+    #[test]
+    fn shipped_settings_parse() {
+        let mut settings: Settings = ron::from_str(include_str!("../settings.ron")).unwrap();
+        assert!(settings.validate().is_empty());
+        assert_eq!(settings.view_distance, 10);
+        assert_eq!(settings.world_size, 30);
+        assert_eq!(settings.window, WindowSettings::default());
+    }
+
+    #[test]
+    fn defaults_are_valid() {
+        let mut settings = Settings::default();
+        assert!(settings.validate().is_empty());
+        assert_eq!(settings, Settings::default());
+    }
+
+    #[test]
+    fn missing_fields_use_defaults() {
+        let path = temp_file(
+            "partial_settings",
+            "(view_distance: 4, window: (width: 1024.0))",
+        );
+        let settings = Settings::load_or_default(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(settings.view_distance, 4);
+        assert_eq!(settings.window.width, 1024.0);
+        assert_eq!(settings.window.height, WindowSettings::default().height);
+        assert_eq!(
+            Settings {
+                view_distance: Settings::default().view_distance,
+                window: WindowSettings::default(),
+                ..settings
+            },
+            Settings::default()
+        );
+    }
+
+    #[test]
+    fn invalid_or_missing_file_falls_back_to_defaults() {
+        let path = temp_file("invalid_settings", "(view_distance: \"far\")");
+        assert!(Settings::load(&path).is_err());
+        assert_eq!(Settings::load_or_default(&path), Settings::default());
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(Settings::load_or_default(&path), Settings::default());
+    }
+
+    #[test]
+    fn out_of_range_values_are_clamped() {
+        let mut settings = Settings {
+            view_distance: 0,
+            unload_distance: Some(-2),
+            world_size: -1,
+            ..Default::default()
+        };
+        settings.window.height = 0.0;
+        assert_eq!(settings.validate().len(), 4);
+        assert_eq!(settings.view_distance, 1);
+        assert_eq!(settings.unload_distance, Some(1));
+        assert_eq!(settings.world_size, 0);
+        assert_eq!(settings.window, WindowSettings::default());
+        // 한 번 고치면 다시 경고하지 않음
+        assert!(settings.validate().is_empty());
+    }
+
+    #[test]
+    fn reloaded_view_distance_is_clamped_before_reaching_the_world() {
+        let path = temp_file("reload_settings", "(view_distance: 4)");
+        let config = WorldConfig {
+            generator: GeneratorConfig::Void,
+            ..Default::default()
+        };
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs(1)))
+            .insert_resource(World::from_config(&config).unwrap())
+            .add_plugins(SettingsPlugin { path: path.clone() });
+        app.update();
+        assert_eq!(app.world.resource::<World>().view_distance(), 4);
+
+        std::fs::write(&path, "(view_distance: 0)").unwrap();
+        // 수정 시간 해상도가 낮아도 다시 읽도록
+        app.world.resource_mut::<SettingsFile>().modified = None;
+        // 가상 시간은 프레임마다 최대 0.25 초씩만 흐름
+        for _ in 0..5 {
+            app.update();
+        }
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(app.world.resource::<Settings>().view_distance, 1);
+        assert_eq!(app.world.resource::<World>().view_distance(), 1);
+    }
+}
@@ -2,3 +2,19 @@ pub mod world;
 pub mod block;
 pub mod chunk;
 pub mod mesh;
+pub mod image_export;
+pub mod mesh_export;
+pub mod vox;
+pub mod terrain;
+pub mod spline;
+pub mod erosion;
+pub mod river;
+pub mod wfc;
+pub mod coord;
+pub mod generator;
+pub mod floating_origin;
+pub mod occlusion;
+pub mod surface_nets;
+pub mod chunk_render;
+pub mod diagnostics;
+pub mod plugin;
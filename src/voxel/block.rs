@@ -9,6 +9,8 @@ pub struct BlockType {
     pub bottom_face_texture: i32,
     pub right_face_texture: i32,
     pub left_face_texture: i32,
+    // heightmap, 단면 이미지 export에 쓰이는 대표 색상
+    pub map_color: [u8; 3],
 }
 
 impl BlockType {
@@ -34,6 +36,7 @@ impl BlockType {
             bottom_face_texture: 13,
             right_face_texture: 14,
             left_face_texture: 14,
+            map_color: [95, 159, 53],
         }
     }
 
@@ -47,6 +50,7 @@ impl BlockType {
             bottom_face_texture: 12,
             right_face_texture: 12,
             left_face_texture: 12,
+            map_color: [125, 125, 125],
         }
     }
     pub fn new_bed_rock() -> BlockType {
@@ -59,6 +63,7 @@ impl BlockType {
             bottom_face_texture: 5,
             right_face_texture: 5,
             left_face_texture: 5,
+            map_color: [48, 48, 48],
         }
     }
 
@@ -72,6 +77,7 @@ impl BlockType {
             bottom_face_texture: 6,
             right_face_texture: 6,
             left_face_texture: 6,
+            map_color: [219, 207, 163],
         }
     }
 
@@ -85,6 +91,7 @@ impl BlockType {
             bottom_face_texture: 6,
             right_face_texture: 6,
             left_face_texture: 6,
+            map_color: [0, 0, 0],
         }
     }
     pub fn new_dirt() -> BlockType {
@@ -97,10 +104,12 @@ impl BlockType {
             bottom_face_texture: 13,
             right_face_texture: 13,
             left_face_texture: 13,
+            map_color: [134, 96, 67],
         }
     }
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum EBlockType {
     Grass = 0,
     Stone,
@@ -110,6 +119,21 @@ pub enum EBlockType {
     Dirt,
 }
 
+impl EBlockType {
+    // 설정 파일용 이름, 대소문자 구분 없음
+    pub fn from_name(name: &str) -> Option<EBlockType> {
+        match name.to_lowercase().as_str() {
+            "grass" => Some(EBlockType::Grass),
+            "stone" => Some(EBlockType::Stone),
+            "bedrock" => Some(EBlockType::BedRock),
+            "sand" => Some(EBlockType::Sand),
+            "air" => Some(EBlockType::Air),
+            "dirt" => Some(EBlockType::Dirt),
+            _ => None,
+        }
+    }
+}
+
 pub struct Block {
     pub block_types: Vec<BlockType>,
 }
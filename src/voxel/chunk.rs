@@ -1,13 +1,25 @@
 use std::default;
 
 use super::block::*;
+pub use super::coord::ChunkCoord;
+use super::coord::LocalVoxelPos;
 use super::mesh::*;
+use super::generator::TerrainGenerator;
+use super::occlusion::VisibilityGraph;
+use super::surface_nets;
 use bevy::ecs::world;
 use bevy::prelude::*;
+use bevy::render::primitives::Aabb;
+use bevy::render::{
+    mesh::{Indices, VertexAttributeValues},
+    render_asset::RenderAssetUsages,
+    render_resource::PrimitiveTopology,
+};
 use bevy::utils::warn;
 use noise::NoiseFn;
 use noise::Perlin;
 use other_noise::core::simplex;
+use serde::{Deserialize, Serialize};
 use std::hash::{Hash, Hasher};
 
 use crate::noise::basic_perlin;
@@ -16,13 +28,46 @@ use crate::noise::random_perlin;
 use crate::noise::random_perlin::perlin_noise2d;
 extern crate noise as other_noise;
 
+// 청크 메쉬를 만드는 방식, 월드마다 하나 (WorldConfig::mesher)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ChunkMesher {
+    // 블록마다 면을 그리는 기본 메쉬
+    #[default]
+    Blocky,
+    // 생성기의 밀도로 만드는 부드러운 지형 (텍스처 대신 블록 색을 섞은 버텍스 색)
+    SurfaceNets,
+}
+
+impl ChunkMesher {
+    // 커맨드라인용 : blocky, surface_nets
+    pub fn parse(text: &str) -> Result<ChunkMesher, String> {
+        match text {
+            "blocky" => Ok(ChunkMesher::Blocky),
+            "surface_nets" => Ok(ChunkMesher::SurfaceNets),
+            _ => Err(format!("unknown mesher: {}", text)),
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct Chunk {
     pub vertices: Vec<Vec3>,
     pub triangles: Vec<u32>,
     pub uvs: Vec<Vec2>,
     pub voxel_map: Vec<Vec<Vec<i32>>>,
+    // [x * CHUNK_WIDTH + z] 컬럼에서 가장 높은 고체 블록의 y, 없으면 -1
+    // 블록을 바꾼 뒤에는 rebuild_mesh (또는 update_heightmap) 로 갱신
+    pub heightmap: Vec<i32>,
+    // SurfaceNets 메쉬에만 있음 (블록 메쉬는 비어 있음)
+    pub normals: Vec<Vec3>,
+    pub colors: Vec<[f32; 4]>,
+    // SurfaceNets 메쉬에 쓰는 밀도 샘플 (surface_nets::density_index), 블록 메쉬면 비어 있음
+    pub density: Vec<f32>,
+    pub mesher: ChunkMesher,
+    // 빈 공간으로 이어진 청크 면들, 메쉬를 만들 때 같이 갱신 (오클루전 컬링에 사용)
+    pub visibility_graph: VisibilityGraph,
     pub chunk_coord: ChunkCoord,
+    pub seed: u32,
     pub is_updated: bool,
     pub is_active: bool,
 }
@@ -33,18 +78,6 @@ impl Hash for Chunk {
     }
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, Hash, Component)]
-pub struct ChunkCoord {
-    pub x: i32,
-    pub y: i32,
-}
-
-impl ChunkCoord {
-    fn new() -> Self {
-        ChunkCoord { x: 0, y: 0 }
-    }
-}
-
 impl Chunk {
     pub fn default() -> Self {
         let chunk = Chunk {
@@ -52,7 +85,14 @@ impl Chunk {
             triangles: Vec::new(),
             uvs: Vec::new(),
             voxel_map: Vec::new(),
-            chunk_coord: ChunkCoord { x: 0, y: 0 },
+            heightmap: Vec::new(),
+            normals: Vec::new(),
+            colors: Vec::new(),
+            density: Vec::new(),
+            mesher: ChunkMesher::Blocky,
+            visibility_graph: VisibilityGraph::open(),
+            chunk_coord: ChunkCoord { x: 0, z: 0 },
+            seed: VoxelData::DEFAULT_SEED,
             is_updated: false,
             is_active: false,
         };
@@ -60,13 +100,38 @@ impl Chunk {
         chunk
     }
 
-    pub fn new(chunk_coord: ChunkCoord) -> Self {
+    pub fn new(chunk_coord: ChunkCoord, generator: &dyn TerrainGenerator) -> Self {
+        let mut chunk = Self::generate(chunk_coord, generator);
+        chunk.create_mesh_data();
+
+        chunk
+    }
+
+    // 블록만 채우고 메쉬는 만들지 않음
+    pub fn generate(chunk_coord: ChunkCoord, generator: &dyn TerrainGenerator) -> Self {
+        let _span = info_span!("generate_chunk", x = chunk_coord.x, z = chunk_coord.z).entered();
+        let mut chunk = Self::empty(chunk_coord.clone(), generator.seed());
+        generator.fill_chunk(&chunk_coord, &mut chunk);
+        chunk.update_heightmap();
+
+        chunk
+    }
+
+    // 공기로 가득 찬 청크
+    pub fn empty(chunk_coord: ChunkCoord, seed: u32) -> Self {
         let mut chunk = Chunk {
             vertices: Vec::new(),
             triangles: Vec::new(),
             uvs: Vec::new(),
             voxel_map: Vec::new(),
-            chunk_coord: chunk_coord.clone(),
+            heightmap: vec![-1; (VoxelData::CHUNK_WIDTH * VoxelData::CHUNK_WIDTH) as usize],
+            normals: Vec::new(),
+            colors: Vec::new(),
+            density: Vec::new(),
+            mesher: ChunkMesher::Blocky,
+            visibility_graph: VisibilityGraph::open(),
+            chunk_coord,
+            seed,
             is_updated: false,
             is_active: false,
         };
@@ -76,24 +141,233 @@ impl Chunk {
             for _ in 0..VoxelData::CHUNK_HEIGHT {
                 let mut col = Vec::with_capacity(VoxelData::CHUNK_WIDTH as usize);
                 for _ in 0..VoxelData::CHUNK_WIDTH {
-                    col.push(0);
+                    col.push(EBlockType::Air as i32);
                 }
                 row.push(col);
             }
             chunk.voxel_map.push(row);
         }
 
-        chunk.populate_voxel_map(chunk_coord.clone());
-        chunk.create_mesh_data();
-
         chunk
     }
 
-    fn create_mesh_data(&mut self) {
+    // voxel_map 이 바뀐 뒤 heightmap 과 메쉬 데이터를 처음부터 다시 만듦
+    pub fn rebuild_mesh(&mut self) {
+        self.update_heightmap();
+        self.vertices.clear();
+        self.triangles.clear();
+        self.uvs.clear();
+        self.normals.clear();
+        self.colors.clear();
+        self.create_mesh_data();
+        self.is_updated = true;
+    }
+
+    pub fn update_heightmap(&mut self) {
+        let block = Block::new();
+
+        for x in 0..VoxelData::CHUNK_WIDTH {
+            for z in 0..VoxelData::CHUNK_WIDTH {
+                let column = &self.voxel_map[x as usize];
+                let height = (0..VoxelData::CHUNK_HEIGHT)
+                    .rev()
+                    .find(|y| {
+                        let block_id = column[*y as usize][z as usize];
+                        block.block_types[block_id as usize].is_solid
+                    })
+                    .unwrap_or(-1);
+
+                self.heightmap[(x * VoxelData::CHUNK_WIDTH + z) as usize] = height;
+            }
+        }
+    }
+
+    // 로컬 컬럼 (x, z) 에서 가장 높은 고체 블록의 y
+    pub fn highest_solid_block(&self, x: i32, z: i32) -> Option<i32> {
+        let range = 0..VoxelData::CHUNK_WIDTH;
+        if !range.contains(&x) || !range.contains(&z) {
+            return None;
+        }
+
+        let height = self.heightmap[(x * VoxelData::CHUNK_WIDTH + z) as usize];
+        if height < 0 {
+            None
+        } else {
+            Some(height)
+        }
+    }
+
+    // 메쉬 버텍스는 청크 로컬 좌표이고, 청크 엔티티의 Transform 으로 이만큼 옮겨서 배치
+    // 원점에서 멀어져도 버텍스 값이 커지지 않아 f32 정밀도 손실이 줄어듦
+    pub fn world_offset(&self) -> Vec3 {
+        self.chunk_coord.origin().to_vec3() + Vec3::new(0.0, VoxelData::CHUNK_Y_OFFSET, 0.0)
+    }
+
+    // 청크 로컬 좌표 기준으로 버텍스를 딱 맞게 감싸는 AABB, 프러스텀 컬링에 사용
+    pub fn aabb(&self) -> Aabb {
+        Aabb::enclosing(self.vertices.iter().copied())
+            .unwrap_or(Aabb::from_min_max(Vec3::ZERO, Vec3::ZERO))
+    }
+
+    pub fn create_mesh(&self) -> Mesh {
+        let mut mesh = Mesh::new(
+            PrimitiveTopology::TriangleList,
+            RenderAssetUsages::MAIN_WORLD | RenderAssetUsages::RENDER_WORLD,
+        );
+        self.write_mesh(&mut mesh);
+        mesh
+    }
+
+    // 이미 있는 메쉬 에셋의 버퍼를 재사용해서 버텍스 데이터를 덮어씀 (리메쉬할 때 새 에셋을 만들지 않음)
+    pub fn write_mesh(&self, mesh: &mut Mesh) {
+        match mesh.attribute_mut(Mesh::ATTRIBUTE_POSITION) {
+            Some(VertexAttributeValues::Float32x3(positions)) => {
+                positions.clear();
+                positions.extend(self.vertices.iter().map(|v| v.to_array()));
+            }
+            _ => mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, self.vertices.clone()),
+        }
+
+        // SurfaceNets 메쉬는 UV 가 없어서 텍스처 없이 버텍스 색만 씀
+        if self.uvs.is_empty() {
+            mesh.remove_attribute(Mesh::ATTRIBUTE_UV_0);
+        } else {
+            match mesh.attribute_mut(Mesh::ATTRIBUTE_UV_0) {
+                Some(VertexAttributeValues::Float32x2(uvs)) => {
+                    uvs.clear();
+                    uvs.extend(self.uvs.iter().map(|uv| uv.to_array()));
+                }
+                _ => mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, self.uvs.clone()),
+            }
+        }
+
+        if self.normals.is_empty() {
+            mesh.remove_attribute(Mesh::ATTRIBUTE_NORMAL);
+        } else {
+            match mesh.attribute_mut(Mesh::ATTRIBUTE_NORMAL) {
+                Some(VertexAttributeValues::Float32x3(normals)) => {
+                    normals.clear();
+                    normals.extend(self.normals.iter().map(|n| n.to_array()));
+                }
+                _ => mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, self.normals.clone()),
+            }
+        }
+
+        match mesh.indices_mut() {
+            Some(Indices::U32(indices)) => {
+                indices.clear();
+                indices.extend_from_slice(&self.triangles);
+            }
+            _ => mesh.insert_indices(Indices::U32(self.triangles.clone())),
+        }
+
+        // 디버그 색칠 (debug_render) 로 넣은 버텍스 색도 여기서 덮어씀, 필요하면 다시 칠함
+        self.write_colors(mesh);
+    }
+
+    // 청크의 버텍스 색 (SurfaceNets 재질 색), 없으면 색 속성을 지움
+    pub fn write_colors(&self, mesh: &mut Mesh) {
+        if self.colors.is_empty() {
+            mesh.remove_attribute(Mesh::ATTRIBUTE_COLOR);
+        } else {
+            match mesh.attribute_mut(Mesh::ATTRIBUTE_COLOR) {
+                Some(VertexAttributeValues::Float32x4(colors)) => {
+                    colors.clear();
+                    colors.extend_from_slice(&self.colors);
+                }
+                _ => mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, self.colors.clone()),
+            }
+        }
+    }
+
+    // 블록 데이터 (voxel_map, heightmap) 가 차지하는 메모리, 메쉬 데이터는 제외
+    pub fn voxel_memory_bytes(&self) -> usize {
+        let voxels: usize = self
+            .voxel_map
+            .iter()
+            .flat_map(|row| row.iter())
+            .map(|col| col.capacity() * std::mem::size_of::<i32>())
+            .sum();
+        voxels
+            + self.heightmap.capacity() * std::mem::size_of::<i32>()
+            + self.density.capacity() * std::mem::size_of::<f32>()
+    }
+
+    pub fn get_voxel(&self, local: LocalVoxelPos) -> Option<i32> {
+        if !local.is_in_chunk() {
+            return None;
+        }
+        Some(self.voxel_map[local.x as usize][local.y as usize][local.z as usize])
+    }
+
+    pub fn set_voxel(&mut self, local: LocalVoxelPos, block_id: i32) -> bool {
+        if !local.is_in_chunk() {
+            return false;
+        }
+        self.voxel_map[local.x as usize][local.y as usize][local.z as usize] = block_id;
+
+        // 고친 블록이 SurfaceNets 메쉬에도 보이도록 밀도를 블록에 맞춤
+        self.set_density_sample(local, block_id);
+        true
+    }
+
+    // 밀도 샘플 하나를 블록에 맞춤, 청크 밖 한 칸 (이웃 청크의 경계 블록) 까지 가능
+    // 이웃 청크의 경계 블록이 바뀌었을 때 World::set_block_id 가 호출해서 경계에 틈이 생기지 않도록 함
+    // 밀도가 없거나 (블록 메쉬) 격자 밖이면 false
+    pub fn set_density_sample(&mut self, local: LocalVoxelPos, block_id: i32) -> bool {
+        let Some(index) = surface_nets::density_index(local.x, local.y, local.z) else {
+            return false;
+        };
+        let Some(density) = self.density.get_mut(index) else {
+            return false;
+        };
+        *density = if Block::new().block_types[block_id as usize].is_solid {
+            density.max(surface_nets::SOLID_DENSITY)
+        } else {
+            density.min(surface_nets::AIR_DENSITY)
+        };
+        true
+    }
+
+    // SurfaceNets 메쉬에 쓸 밀도 샘플을 채움, create_mesh_data 전에 호출
+    pub fn sample_density(&mut self, generator: &dyn TerrainGenerator) {
+        self.density = surface_nets::sample_density(self, Some(generator));
+    }
+
+    // 비어 있는 메쉬 데이터에 면을 추가함, 이미 메쉬가 있는 청크는 rebuild_mesh 사용
+    pub fn create_mesh_data(&mut self) {
+        let _span =
+            info_span!("mesh_chunk", x = self.chunk_coord.x, z = self.chunk_coord.z).entered();
+        if self.mesher == ChunkMesher::SurfaceNets {
+            self.create_smooth_mesh_data();
+        } else {
+            self.create_blocky_mesh_data();
+        }
+
+        self.visibility_graph = VisibilityGraph::compute(self);
+    }
+
+    fn create_smooth_mesh_data(&mut self) {
+        // 밀도를 샘플하지 않은 청크 (블록만 있는 청크) 는 블록으로 만든 밀도 사용
+        let density = if self.density.is_empty() {
+            surface_nets::sample_density(self, None)
+        } else {
+            std::mem::take(&mut self.density)
+        };
+
+        let mesh = surface_nets::build(self, &density);
+        self.vertices = mesh.vertices;
+        self.normals = mesh.normals;
+        self.colors = mesh.colors;
+        self.triangles = mesh.triangles;
+        self.density = density;
+    }
+
+    fn create_blocky_mesh_data(&mut self) {
         for y in 0..VoxelData::CHUNK_HEIGHT {
             for x in 0..VoxelData::CHUNK_WIDTH {
                 for z in 0..VoxelData::CHUNK_WIDTH {
-                    self.add_voxel_data(Vec3::new(x as f32, y as f32, z as f32));
+                    self.add_voxel_data(LocalVoxelPos::new(x, y, z));
                 }
             }
         }
@@ -125,40 +399,31 @@ impl Chunk {
         )); // 우하단 (RB)
     }
 
-    fn add_voxel_data(&mut self, pos: Vec3) {
+    fn add_voxel_data(&mut self, local: LocalVoxelPos) {
         let mut vertex_index = self.vertices.len() as u32;
+        let pos = local.to_vec3();
 
         // 6방향의 면 그리기
         for p in 0..6 {
-            if self.check_voxel(pos) && !self.check_voxel(pos + VoxelData::FACE_CHECKS[p]) {
+            let neighbor = local + VoxelData::FACE_CHECKS[p].as_ivec3();
+            if self.check_voxel(local) && !self.check_voxel(neighbor) {
                 //println!("외부 Voxel 입니다");
                 // 각 면(삼각형 2개) 그리기
                 // // 1. Vertex, UV 4개 추가
 
-                let block_id =
-                    self.voxel_map[pos.x as usize][pos.y as usize][pos.z as usize] as usize;
-
-                let offset = Vec3::new(
-                    (self.chunk_coord.x as f32 * VoxelData::CHUNK_WIDTH as f32),
-                    -10.0,
-                    (self.chunk_coord.y as f32 * VoxelData::CHUNK_WIDTH as f32),
-                );
+                let block_id = self.get_voxel(local).unwrap() as usize;
 
                 self.vertices.push(
-                    pos + VoxelData::VOXEL_VERTS[VoxelData::VOXEL_TRIS[p as usize][0] as usize]
-                        + offset,
+                    pos + VoxelData::VOXEL_VERTS[VoxelData::VOXEL_TRIS[p as usize][0] as usize],
                 );
                 self.vertices.push(
-                    pos + VoxelData::VOXEL_VERTS[VoxelData::VOXEL_TRIS[p as usize][1] as usize]
-                        + offset,
+                    pos + VoxelData::VOXEL_VERTS[VoxelData::VOXEL_TRIS[p as usize][1] as usize],
                 );
                 self.vertices.push(
-                    pos + VoxelData::VOXEL_VERTS[VoxelData::VOXEL_TRIS[p as usize][2] as usize]
-                        + offset,
+                    pos + VoxelData::VOXEL_VERTS[VoxelData::VOXEL_TRIS[p as usize][2] as usize],
                 );
                 self.vertices.push(
-                    pos + VoxelData::VOXEL_VERTS[VoxelData::VOXEL_TRIS[p as usize][3] as usize]
-                        + offset,
+                    pos + VoxelData::VOXEL_VERTS[VoxelData::VOXEL_TRIS[p as usize][3] as usize],
                 );
 
                 let mut block = Block::new();
@@ -178,97 +443,12 @@ impl Chunk {
         }
     }
 
-    fn check_voxel(&mut self, pos: Vec3) -> bool {
-        let x = pos.x.floor() as i32;
-        let y = pos.y.floor() as i32;
-        let z = pos.z.floor() as i32;
-
-        if !Self::isin_voxel_in_chunk(pos.x as i32, pos.y as i32, pos.z as i32) {
+    // 청크 밖은 비어 있는 것으로 취급
+    fn check_voxel(&self, local: LocalVoxelPos) -> bool {
+        let Some(block_id) = self.get_voxel(local) else {
             return false;
-        }
+        };
         let block = Block::new();
-        return block.block_types[self.voxel_map[x as usize][y as usize][z as usize] as usize]
-            .is_solid;
-    }
-
-    fn voxel_to_world_pos(&mut self, pos: Vec2, chunk_coord: ChunkCoord) -> Vec2 {
-        let offset = Vec2::new(
-            (chunk_coord.x as f32 * VoxelData::CHUNK_WIDTH as f32),
-            (chunk_coord.y as f32 * VoxelData::CHUNK_WIDTH as f32),
-        );
-
-        Vec2::new(pos.x + offset.x, pos.y + offset.y)
-    }
-
-    fn populate_voxel_map(&mut self, chunk_coord: ChunkCoord) {
-        for y in 0..VoxelData::CHUNK_HEIGHT {
-            for x in 0..VoxelData::CHUNK_WIDTH {
-                for z in 0..VoxelData::CHUNK_WIDTH {
-                    self.voxel_map[x as usize][y as usize][z as usize] = self.get_block_type(
-                        Vec3::new(x as f32, y as f32, z as f32),
-                        chunk_coord.clone(),
-                    );
-                }
-            }
-        }
-    }
-
-    fn get_block_type(&mut self, pos: Vec3, chunk_coord: ChunkCoord) -> i32 {
-        if pos.y < 1.0 {
-            return EBlockType::BedRock as i32;
-        }
-
-        let world_pos =
-            self.voxel_to_world_pos(Vec2::new(pos.x as f32, pos.z as f32), chunk_coord.clone());
-        let perlin = other_noise::Perlin::new(132);
-        let simplex = noise::Simplex::new(132);
-
-        let mut perlin_result = 0.0;
-        let octave : i32 = 6;
-        let mut frequency = 0.001;
-        for _ in 0..octave {
-            perlin_result += perlin.get([(world_pos.x * frequency) as f64, (world_pos.y * frequency) as f64]);
-            frequency *= 2.0;
-        }
-
-        //let result = crate::noise::basic_perlin::perlin_noise2d(world_pos.x, world_pos.y, 4) as f64;
-
-        let simplex_result = simplex.get([
-            world_pos.x as f64 * 0.1,
-            pos.y as f64 * 0.1,
-            world_pos.y as f64 * 0.1,
-        ]);
-
-        let min_ground = VoxelData::CHUNK_HEIGHT as f32 * 0.5;
-
-        let terrain_height = ((VoxelData::CHUNK_HEIGHT - 1) as f64 * perlin_result) as f32;
-        let terrain_height = terrain_height.clamp(min_ground, (VoxelData::CHUNK_HEIGHT - 1) as f32);
-
-        if pos.y == terrain_height.floor() {
-            EBlockType::Grass as i32
-        } else if pos.y < terrain_height.floor() {
-            if pos.y > min_ground {
-                EBlockType::Dirt as i32
-            } else if simplex_result < 0.0 {
-                EBlockType::Air as i32
-            } else {
-                EBlockType::Stone as i32
-            }
-        } else {
-            EBlockType::Air as i32
-        }
-    }
-
-    fn isin_voxel_in_chunk(x: i32, y: i32, z: i32) -> bool {
-        if (x < 0
-            || x > VoxelData::CHUNK_WIDTH - 1
-            || y < 0
-            || y > VoxelData::CHUNK_HEIGHT - 1
-            || z < 0
-            || z > VoxelData::CHUNK_WIDTH - 1)
-        {
-            return false;
-        }
-        return true;
+        return block.block_types[block_id as usize].is_solid;
     }
 }
@@ -0,0 +1,149 @@
+use super::world::{ChunkEntities, World};
+use bevy::prelude::*;
+use bevy::render::primitives::Aabb;
+
+// 모든 청크가 같이 쓰는 블록 머티리얼, 렌더 레이어마다 하나씩
+// 지금은 불투명 블록만 있음 (물 같은 반투명 블록이 생기면 레이어를 추가)
+//
+// fade 는 청크가 나타날 때 잠깐 쓰는 반투명 단계별 머티리얼 (FADE_STEPS 개)
+// 청크마다 머티리얼을 만들지 않고 단계별 핸들을 돌려 쓰므로 머티리얼 수는 일정함
+#[derive(Resource)]
+pub struct BlockMaterials {
+    pub opaque: Handle<StandardMaterial>,
+    pub fade: Vec<Handle<StandardMaterial>>,
+}
+
+impl BlockMaterials {
+    // atlas_path : assets 폴더 기준 텍스처 아틀라스 경로
+    pub fn load(world: &mut bevy::ecs::world::World, atlas_path: &str) -> Self {
+        let texture_handle: Handle<Image> = world
+            .resource::<AssetServer>()
+            .load(atlas_path.to_string());
+        let mut materials = world.resource_mut::<Assets<StandardMaterial>>();
+        let opaque = materials.add(StandardMaterial {
+            base_color_texture: Some(texture_handle.clone()),
+            ..default()
+        });
+        let fade = (0..FADE_STEPS)
+            .map(|step| {
+                let alpha = (step + 1) as f32 / (FADE_STEPS + 1) as f32;
+                materials.add(StandardMaterial {
+                    base_color: Color::rgba(1.0, 1.0, 1.0, alpha),
+                    base_color_texture: Some(texture_handle.clone()),
+                    alpha_mode: AlphaMode::Blend,
+                    ..default()
+                })
+            })
+            .collect();
+
+        BlockMaterials { opaque, fade }
+    }
+}
+
+pub const FADE_STEPS: usize = 8;
+pub const FADE_IN_SECONDS: f32 = 0.6;
+
+// 나타나는 중인 청크, 끝나면 불투명 머티리얼로 돌아가고 컴포넌트는 제거됨
+#[derive(Component, Default)]
+pub struct ChunkFadeIn {
+    elapsed: f32,
+}
+
+// 숨겨져 있던 청크가 보이게 될 때 호출, 첫 단계 머티리얼로 바꾸고 페이드 시작
+pub fn start_fade_in(commands: &mut Commands, block_materials: &BlockMaterials, entity: Entity) {
+    commands
+        .entity(entity)
+        .insert((ChunkFadeIn::default(), block_materials.fade[0].clone()));
+}
+
+pub fn fade_in_chunks(
+    time: Res<Time>,
+    block_materials: Res<BlockMaterials>,
+    mut commands: Commands,
+    mut query: Query<(Entity, &mut ChunkFadeIn, &mut Handle<StandardMaterial>)>,
+) {
+    for (entity, mut fade, mut material) in query.iter_mut() {
+        fade.elapsed += time.delta_seconds();
+        let t = fade.elapsed / FADE_IN_SECONDS;
+
+        let next = if t >= 1.0 {
+            commands.entity(entity).remove::<ChunkFadeIn>();
+            &block_materials.opaque
+        } else {
+            &block_materials.fade[((t * FADE_STEPS as f32) as usize).min(FADE_STEPS - 1)]
+        };
+        if *material != *next {
+            *material = next.clone();
+        }
+    }
+}
+
+// 청크가 스트리밍되는 동안 에셋 수가 늘지 않는지 확인하기 위한 카운터
+// meshes 는 청크 엔티티 수와 같고, materials 는 청크 수와 상관없이 일정해야 함
+#[derive(Resource, Default, Clone, Debug, PartialEq, Eq)]
+pub struct ChunkAssetStats {
+    pub chunk_entities: usize,
+    pub meshes: usize,
+    pub materials: usize,
+    // 새 에셋 없이 기존 메쉬를 덮어쓴 횟수
+    pub mesh_rewrites: u64,
+}
+
+// World::rebuild_chunk_mesh 로 다시 만든 청크의 메쉬 에셋과 Aabb 를 제자리에서 갱신
+// 렌더링 없이 (MinimalPlugins) 돌 때는 쌓인 목록만 비움
+pub fn apply_chunk_meshes(
+    mut voxel_world: ResMut<World>,
+    chunk_entities: Res<ChunkEntities>,
+    meshes: Option<ResMut<Assets<Mesh>>>,
+    mut query: Query<(&Handle<Mesh>, &mut Aabb)>,
+    mut stats: ResMut<ChunkAssetStats>,
+) {
+    let voxel_world = voxel_world.bypass_change_detection();
+    let remeshed_chunks = voxel_world.take_remeshed_chunks();
+    let Some(mut meshes) = meshes else {
+        return;
+    };
+
+    for coord in remeshed_chunks {
+        let Some(entity) = chunk_entities.get(&coord) else {
+            continue;
+        };
+        let Ok((mesh_handle, mut aabb)) = query.get_mut(entity) else {
+            continue;
+        };
+        let (Some(mesh), Some(chunk)) = (meshes.get_mut(mesh_handle), voxel_world.get_chunk(&coord))
+        else {
+            continue;
+        };
+
+        chunk.write_mesh(mesh);
+        *aabb = chunk.aabb();
+        stats.mesh_rewrites += 1;
+    }
+}
+
+pub fn update_chunk_asset_stats(
+    chunk_entities: Res<ChunkEntities>,
+    meshes: Res<Assets<Mesh>>,
+    materials: Res<Assets<StandardMaterial>>,
+    mut stats: ResMut<ChunkAssetStats>,
+) {
+    let chunk_entities = chunk_entities.len();
+    let mesh_count = meshes.len();
+    let material_count = materials.len();
+
+    if stats.chunk_entities == chunk_entities
+        && stats.meshes == mesh_count
+        && stats.materials == material_count
+    {
+        return;
+    }
+
+    stats.chunk_entities = chunk_entities;
+    stats.meshes = mesh_count;
+    stats.materials = material_count;
+    info!(
+        "chunk entities {} : meshes {} materials {}",
+        chunk_entities, mesh_count, material_count
+    );
+}
@@ -0,0 +1,234 @@
+use super::mesh::VoxelData;
+use bevy::prelude::*;
+use std::ops::Add;
+
+// 좌표계
+// WorldVoxelPos : 월드 전체에서의 블록 좌표 (음수 가능)
+// ChunkCoord    : 청크 단위 좌표, 청크 하나는 CHUNK_WIDTH x CHUNK_HEIGHT x CHUNK_WIDTH
+// LocalVoxelPos : 청크 안에서의 블록 좌표, 이웃 블록 검사를 위해 범위 밖 값도 표현 가능
+//
+// 음수 좌표도 올바르게 나뉘도록 변환은 모두 div_euclid / rem_euclid 를 사용
+// 예) x = -1 -> 청크 -1 의 로컬 x = CHUNK_WIDTH - 1
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct WorldVoxelPos {
+    pub x: i32,
+    pub y: i32,
+    pub z: i32,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, Component)]
+pub struct ChunkCoord {
+    pub x: i32,
+    pub z: i32,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct LocalVoxelPos {
+    pub x: i32,
+    pub y: i32,
+    pub z: i32,
+}
+
+impl WorldVoxelPos {
+    pub fn new(x: i32, y: i32, z: i32) -> Self {
+        WorldVoxelPos { x, y, z }
+    }
+
+    // 해당 위치를 포함하는 블록
+    pub fn from_world_pos(pos: Vec3) -> Self {
+        WorldVoxelPos {
+            x: pos.x.floor() as i32,
+            y: pos.y.floor() as i32,
+            z: pos.z.floor() as i32,
+        }
+    }
+
+    pub fn chunk_coord(&self) -> ChunkCoord {
+        ChunkCoord {
+            x: self.x.div_euclid(VoxelData::CHUNK_WIDTH),
+            z: self.z.div_euclid(VoxelData::CHUNK_WIDTH),
+        }
+    }
+
+    pub fn local(&self) -> LocalVoxelPos {
+        LocalVoxelPos {
+            x: self.x.rem_euclid(VoxelData::CHUNK_WIDTH),
+            y: self.y,
+            z: self.z.rem_euclid(VoxelData::CHUNK_WIDTH),
+        }
+    }
+
+    // 블록의 최소 꼭짓점
+    pub fn to_vec3(&self) -> Vec3 {
+        Vec3::new(self.x as f32, self.y as f32, self.z as f32)
+    }
+
+    pub fn to_ivec3(&self) -> IVec3 {
+        IVec3::new(self.x, self.y, self.z)
+    }
+}
+
+impl From<IVec3> for WorldVoxelPos {
+    fn from(v: IVec3) -> Self {
+        WorldVoxelPos::new(v.x, v.y, v.z)
+    }
+}
+
+impl Add<IVec3> for WorldVoxelPos {
+    type Output = WorldVoxelPos;
+
+    fn add(self, offset: IVec3) -> WorldVoxelPos {
+        WorldVoxelPos::new(self.x + offset.x, self.y + offset.y, self.z + offset.z)
+    }
+}
+
+impl ChunkCoord {
+    pub fn new(x: i32, z: i32) -> Self {
+        ChunkCoord { x, z }
+    }
+
+    pub fn from_world_pos(pos: Vec3) -> Self {
+        WorldVoxelPos::from_world_pos(pos).chunk_coord()
+    }
+
+    // 청크의 (0, 0, 0) 로컬 블록의 월드 좌표
+    pub fn origin(&self) -> WorldVoxelPos {
+        WorldVoxelPos {
+            x: self.x * VoxelData::CHUNK_WIDTH,
+            y: 0,
+            z: self.z * VoxelData::CHUNK_WIDTH,
+        }
+    }
+
+    pub fn to_world(&self, local: LocalVoxelPos) -> WorldVoxelPos {
+        let origin = self.origin();
+        WorldVoxelPos {
+            x: origin.x + local.x,
+            y: local.y,
+            z: origin.z + local.z,
+        }
+    }
+}
+
+impl LocalVoxelPos {
+    pub fn new(x: i32, y: i32, z: i32) -> Self {
+        LocalVoxelPos { x, y, z }
+    }
+
+    pub fn is_in_chunk(&self) -> bool {
+        self.x >= 0
+            && self.x < VoxelData::CHUNK_WIDTH
+            && self.y >= 0
+            && self.y < VoxelData::CHUNK_HEIGHT
+            && self.z >= 0
+            && self.z < VoxelData::CHUNK_WIDTH
+    }
+
+    pub fn to_vec3(&self) -> Vec3 {
+        Vec3::new(self.x as f32, self.y as f32, self.z as f32)
+    }
+}
+
+impl Add<IVec3> for LocalVoxelPos {
+    type Output = LocalVoxelPos;
+
+    fn add(self, offset: IVec3) -> LocalVoxelPos {
+        LocalVoxelPos::new(self.x + offset.x, self.y + offset.y, self.z + offset.z)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const W: i32 = VoxelData::CHUNK_WIDTH;
+
+    #[test]
+    fn chunk_boundary_values() {
+        let cases = [
+            (-W - 1, -2, W - 1),
+            (-W, -1, 0),
+            (-1, -1, W - 1),
+            (0, 0, 0),
+            (W - 1, 0, W - 1),
+            (W, 1, 0),
+            (2 * W + 3, 2, 3),
+        ];
+        for (world, chunk, local) in cases {
+            let pos = WorldVoxelPos::new(world, 5, world);
+            assert_eq!(
+                pos.chunk_coord(),
+                ChunkCoord::new(chunk, chunk),
+                "{}",
+                world
+            );
+            assert_eq!(
+                pos.local(),
+                LocalVoxelPos::new(local, 5, local),
+                "{}",
+                world
+            );
+        }
+    }
+
+    #[test]
+    fn world_to_chunk_and_back_for_every_position() {
+        for x in -3 * W..3 * W {
+            for z in [-W - 1, -W, -1, 0, W - 1, W, x] {
+                let pos = WorldVoxelPos::new(x, 7, z);
+                let (coord, local) = (pos.chunk_coord(), pos.local());
+                assert!(local.is_in_chunk(), "{:?}", pos);
+                assert_eq!(coord.to_world(local), pos);
+                assert_eq!(coord.origin().x, coord.x * W);
+                assert!(coord.origin().x <= x && x < coord.origin().x + W);
+                assert!(coord.origin().z <= z && z < coord.origin().z + W);
+            }
+        }
+    }
+
+    #[test]
+    fn chunk_to_world_and_back_for_every_local_position() {
+        for chunk in [-2, -1, 0, 1] {
+            let coord = ChunkCoord::new(chunk, -chunk);
+            for x in 0..W {
+                for z in 0..W {
+                    let local = LocalVoxelPos::new(x, 3, z);
+                    let pos = coord.to_world(local);
+                    assert_eq!(pos.chunk_coord(), coord);
+                    assert_eq!(pos.local(), local);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn float_positions_round_down() {
+        let pos = WorldVoxelPos::from_world_pos(Vec3::new(-0.5, 0.5, -W as f32 - 0.01));
+        assert_eq!(pos, WorldVoxelPos::new(-1, 0, -W - 1));
+        assert_eq!(
+            ChunkCoord::from_world_pos(Vec3::new(-0.01, 0.0, W as f32)),
+            ChunkCoord::new(-1, 1)
+        );
+        assert_eq!(
+            ChunkCoord::from_world_pos(Vec3::new(W as f32 - 0.01, 0.0, 0.0)),
+            ChunkCoord::new(0, 0)
+        );
+    }
+
+    #[test]
+    fn local_positions_outside_the_chunk() {
+        assert!(LocalVoxelPos::new(0, 0, 0).is_in_chunk());
+        assert!(LocalVoxelPos::new(W - 1, VoxelData::CHUNK_HEIGHT - 1, W - 1).is_in_chunk());
+        for outside in [
+            LocalVoxelPos::new(-1, 0, 0),
+            LocalVoxelPos::new(W, 0, 0),
+            LocalVoxelPos::new(0, -1, 0),
+            LocalVoxelPos::new(0, VoxelData::CHUNK_HEIGHT, 0),
+            LocalVoxelPos::new(0, 0, -1),
+            LocalVoxelPos::new(0, 0, W),
+        ] {
+            assert!(!outside.is_in_chunk(), "{:?}", outside);
+        }
+    }
+}
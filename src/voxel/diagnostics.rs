@@ -0,0 +1,217 @@
+use super::world::World;
+use bevy::diagnostic::{Diagnostic, DiagnosticPath, Diagnostics, RegisterDiagnostic};
+use bevy::prelude::*;
+use std::collections::VecDeque;
+use std::time::Duration;
+
+// 청크 생성 / 메쉬 생성 성능을 bevy 진단 (DiagnosticsStore) 으로 내보냄
+// LogDiagnosticsPlugin 을 같이 추가하면 로그로 출력됨
+// 청크 하나의 생성 / 메쉬 생성 구간은 tracing span (generate_chunk, mesh_chunk) 으로도 남음
+pub struct VoxelDiagnosticsPlugin;
+
+impl VoxelDiagnosticsPlugin {
+    pub const CHUNKS_GENERATED_PER_SECOND: DiagnosticPath =
+        DiagnosticPath::const_new("voxel/chunks_generated_per_second");
+    pub const CHUNKS_MESHED_PER_SECOND: DiagnosticPath =
+        DiagnosticPath::const_new("voxel/chunks_meshed_per_second");
+    pub const GENERATION_TIME_MEAN: DiagnosticPath =
+        DiagnosticPath::const_new("voxel/generation_time_mean");
+    pub const GENERATION_TIME_P99: DiagnosticPath =
+        DiagnosticPath::const_new("voxel/generation_time_p99");
+    pub const MESHING_TIME_MEAN: DiagnosticPath =
+        DiagnosticPath::const_new("voxel/meshing_time_mean");
+    pub const MESHING_TIME_P99: DiagnosticPath =
+        DiagnosticPath::const_new("voxel/meshing_time_p99");
+    pub const REMESH_QUEUE: DiagnosticPath = DiagnosticPath::const_new("voxel/remesh_queue");
+    pub const VISIBILITY_QUEUE: DiagnosticPath =
+        DiagnosticPath::const_new("voxel/visibility_queue");
+    pub const LOADED_CHUNKS: DiagnosticPath = DiagnosticPath::const_new("voxel/loaded_chunks");
+    pub const VERTICES: DiagnosticPath = DiagnosticPath::const_new("voxel/vertices");
+    pub const VOXEL_MEMORY: DiagnosticPath = DiagnosticPath::const_new("voxel/voxel_memory");
+
+    // 평균 / p99 를 계산할 때 보는 최근 청크 수
+    pub const TIMING_WINDOW: usize = 512;
+}
+
+impl Plugin for VoxelDiagnosticsPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_diagnostic(Diagnostic::new(Self::CHUNKS_GENERATED_PER_SECOND))
+            .register_diagnostic(Diagnostic::new(Self::CHUNKS_MESHED_PER_SECOND))
+            .register_diagnostic(Diagnostic::new(Self::GENERATION_TIME_MEAN).with_suffix("ms"))
+            .register_diagnostic(Diagnostic::new(Self::GENERATION_TIME_P99).with_suffix("ms"))
+            .register_diagnostic(Diagnostic::new(Self::MESHING_TIME_MEAN).with_suffix("ms"))
+            .register_diagnostic(Diagnostic::new(Self::MESHING_TIME_P99).with_suffix("ms"))
+            .register_diagnostic(Diagnostic::new(Self::REMESH_QUEUE).with_smoothing_factor(0.0))
+            .register_diagnostic(Diagnostic::new(Self::VISIBILITY_QUEUE).with_smoothing_factor(0.0))
+            .register_diagnostic(Diagnostic::new(Self::LOADED_CHUNKS).with_smoothing_factor(0.0))
+            .register_diagnostic(Diagnostic::new(Self::VERTICES).with_smoothing_factor(0.0))
+            .register_diagnostic(
+                Diagnostic::new(Self::VOXEL_MEMORY)
+                    .with_suffix("KiB")
+                    .with_smoothing_factor(0.0),
+            )
+            .init_resource::<ChunkTimingHistory>();
+    }
+}
+
+// 최근 TIMING_WINDOW 개 청크의 생성 / 메쉬 생성 시간 (ms)
+#[derive(Resource, Default)]
+pub struct ChunkTimingHistory {
+    generation: VecDeque<f64>,
+    meshing: VecDeque<f64>,
+}
+
+impl ChunkTimingHistory {
+    fn push(samples: &mut VecDeque<f64>, durations: &[Duration]) {
+        for duration in durations {
+            if samples.len() == VoxelDiagnosticsPlugin::TIMING_WINDOW {
+                samples.pop_front();
+            }
+            samples.push_back(duration.as_secs_f64() * 1000.0);
+        }
+    }
+
+    pub fn generation_mean(&self) -> Option<f64> {
+        mean(&self.generation)
+    }
+
+    pub fn generation_p99(&self) -> Option<f64> {
+        percentile(&self.generation, 0.99)
+    }
+
+    pub fn meshing_mean(&self) -> Option<f64> {
+        mean(&self.meshing)
+    }
+
+    pub fn meshing_p99(&self) -> Option<f64> {
+        percentile(&self.meshing, 0.99)
+    }
+}
+
+fn mean(samples: &VecDeque<f64>) -> Option<f64> {
+    if samples.is_empty() {
+        return None;
+    }
+    Some(samples.iter().sum::<f64>() / samples.len() as f64)
+}
+
+// 가장 가까운 순위 (nearest rank) 방식
+fn percentile(samples: &VecDeque<f64>, p: f64) -> Option<f64> {
+    if samples.is_empty() {
+        return None;
+    }
+    let mut sorted: Vec<f64> = samples.iter().copied().collect();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    let rank = ((p * sorted.len() as f64).ceil() as usize).clamp(1, sorted.len());
+    Some(sorted[rank - 1])
+}
+
+// world::update 다음에 실행, 이번 프레임에 생성 / 메쉬 생성된 청크의 시간을 World 에서 가져감
+// 큐 길이는 PostUpdate 에서 엔티티에 반영되기 전의 값
+pub fn chunk_diagnostics(
+    time: Res<Time<Real>>,
+    mut voxel_world: ResMut<World>,
+    mut history: ResMut<ChunkTimingHistory>,
+    mut diagnostics: Diagnostics,
+) {
+    // 시간만 가져가는 것이므로 World 의 change detection 을 건드리지 않음
+    let timings = voxel_world.bypass_change_detection().take_chunk_timings();
+
+    let delta_seconds = time.delta_seconds_f64();
+    if delta_seconds > 0.0 {
+        diagnostics.add_measurement(&VoxelDiagnosticsPlugin::CHUNKS_GENERATED_PER_SECOND, || {
+            timings.generation.len() as f64 / delta_seconds
+        });
+        diagnostics.add_measurement(&VoxelDiagnosticsPlugin::CHUNKS_MESHED_PER_SECOND, || {
+            timings.meshing.len() as f64 / delta_seconds
+        });
+    }
+
+    // 새로 생성된 청크가 있을 때만 평균 / p99 를 다시 계산
+    if !timings.generation.is_empty() {
+        ChunkTimingHistory::push(&mut history.generation, &timings.generation);
+        if let (Some(mean), Some(p99)) = (history.generation_mean(), history.generation_p99()) {
+            diagnostics.add_measurement(&VoxelDiagnosticsPlugin::GENERATION_TIME_MEAN, || mean);
+            diagnostics.add_measurement(&VoxelDiagnosticsPlugin::GENERATION_TIME_P99, || p99);
+        }
+    }
+    if !timings.meshing.is_empty() {
+        ChunkTimingHistory::push(&mut history.meshing, &timings.meshing);
+        if let (Some(mean), Some(p99)) = (history.meshing_mean(), history.meshing_p99()) {
+            diagnostics.add_measurement(&VoxelDiagnosticsPlugin::MESHING_TIME_MEAN, || mean);
+            diagnostics.add_measurement(&VoxelDiagnosticsPlugin::MESHING_TIME_P99, || p99);
+        }
+    }
+
+    diagnostics.add_measurement(&VoxelDiagnosticsPlugin::REMESH_QUEUE, || {
+        voxel_world.remesh_queue_len() as f64
+    });
+    diagnostics.add_measurement(&VoxelDiagnosticsPlugin::VISIBILITY_QUEUE, || {
+        voxel_world.visibility_queue_len() as f64
+    });
+    diagnostics.add_measurement(&VoxelDiagnosticsPlugin::LOADED_CHUNKS, || {
+        voxel_world.chunk_count() as f64
+    });
+    diagnostics.add_measurement(&VoxelDiagnosticsPlugin::VERTICES, || {
+        voxel_world.vertex_count() as f64
+    });
+    diagnostics.add_measurement(&VoxelDiagnosticsPlugin::VOXEL_MEMORY, || {
+        voxel_world.voxel_memory_bytes() as f64 / 1024.0
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn samples(values: &[f64]) -> VecDeque<f64> {
+        values.iter().copied().collect()
+    }
+
+    #[test]
+    fn empty_history_has_no_statistics() {
+        assert_eq!(mean(&VecDeque::new()), None);
+        assert_eq!(percentile(&VecDeque::new(), 0.99), None);
+        let history = ChunkTimingHistory::default();
+        assert_eq!(history.generation_mean(), None);
+        assert_eq!(history.meshing_p99(), None);
+    }
+
+    #[test]
+    fn single_sample_is_its_own_mean_and_percentile() {
+        let single = samples(&[2.5]);
+        assert_eq!(mean(&single), Some(2.5));
+        assert_eq!(percentile(&single, 0.0), Some(2.5));
+        assert_eq!(percentile(&single, 0.99), Some(2.5));
+        assert_eq!(percentile(&single, 1.0), Some(2.5));
+    }
+
+    #[test]
+    fn p99_uses_the_nearest_rank() {
+        // 1 ~ 200 을 섞어서 넣음, 99% 순위는 198 번째
+        let values: Vec<f64> = (1..=200).map(|i| ((i * 37) % 200 + 1) as f64).collect();
+        let shuffled = samples(&values);
+        assert_eq!(mean(&shuffled), Some(100.5));
+        assert_eq!(percentile(&shuffled, 0.99), Some(198.0));
+        assert_eq!(percentile(&shuffled, 0.5), Some(100.0));
+        assert_eq!(percentile(&shuffled, 1.0), Some(200.0));
+        // 100 개면 99 번째, 100 개보다 적으면 가장 큰 값
+        let hundred: VecDeque<f64> = (1..=100).map(f64::from).collect();
+        assert_eq!(percentile(&hundred, 0.99), Some(99.0));
+        assert_eq!(percentile(&samples(&[3.0, 1.0, 2.0]), 0.99), Some(3.0));
+    }
+
+    #[test]
+    fn history_keeps_the_latest_window() {
+        let mut history = ChunkTimingHistory::default();
+        let durations: Vec<Duration> = (0..VoxelDiagnosticsPlugin::TIMING_WINDOW + 10)
+            .map(|i| Duration::from_millis(i as u64))
+            .collect();
+        ChunkTimingHistory::push(&mut history.generation, &durations);
+        assert_eq!(
+            history.generation.len(),
+            VoxelDiagnosticsPlugin::TIMING_WINDOW
+        );
+        assert_eq!(history.generation.front(), Some(&10.0));
+    }
+}
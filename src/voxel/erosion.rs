@@ -0,0 +1,438 @@
+use super::mesh::VoxelData;
+use crate::noise::hash::{hash, unit};
+use bevy::math::{IVec2, Vec2};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+
+// 물방울 기반 수력 침식 (heightmap 후처리)
+//
+// 월드를 region_size 크기의 영역으로 나누고, 영역마다 양쪽으로 margin 만큼 넓힌 heightmap 에
+// 물방울을 떨어뜨려 깎고 쌓음
+// 물방울은 월드 좌표의 칸마다 시드로 정해지므로 겹치는 부분에서는 이웃 영역과 같은 물방울이 떨어지지만,
+// 더 바깥의 물방울이 다르기 때문에 결과가 완전히 같지는 않음
+// 그래서 영역 경계 양쪽 margin / 2 안에서는 이웃 영역의 결과와 가중치 (합 = 1) 로 섞어서 높이가 끊기지 않게 함
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ErosionSettings {
+    // 영역 한 변의 길이 (블록), CHUNK_WIDTH 의 배수로 올림
+    pub region_size: i32,
+    // 영역 양쪽으로 더 시뮬레이션하는 폭 (블록)
+    pub margin: i32,
+    // 블록 하나에 떨어지는 평균 물방울 수
+    pub droplets_per_block: f32,
+    // 물방울이 움직이는 최대 칸 수
+    pub max_lifetime: u32,
+    // 0 이면 경사를 그대로 따라가고, 1 에 가까울수록 원래 방향을 유지
+    pub inertia: f32,
+    pub sediment_capacity: f32,
+    pub min_sediment_capacity: f32,
+    pub erode_speed: f32,
+    pub deposit_speed: f32,
+    pub evaporate_speed: f32,
+    pub gravity: f32,
+    // 깎을 때 주변 몇 칸까지 나눠서 깎는지
+    pub erosion_radius: i32,
+    // 메모리에 들고 있는 침식된 영역 수
+    pub max_cached_regions: usize,
+}
+
+impl Default for ErosionSettings {
+    fn default() -> Self {
+        ErosionSettings {
+            region_size: VoxelData::CHUNK_WIDTH * 6,
+            margin: 40,
+            droplets_per_block: 1.0,
+            max_lifetime: 30,
+            inertia: 0.05,
+            sediment_capacity: 4.0,
+            min_sediment_capacity: 0.01,
+            erode_speed: 0.3,
+            deposit_speed: 0.3,
+            evaporate_speed: 0.01,
+            gravity: 4.0,
+            erosion_radius: 3,
+            max_cached_regions: 64,
+        }
+    }
+}
+
+impl ErosionSettings {
+    // 청크가 한 영역 안에 들어가도록 CHUNK_WIDTH 의 배수로 맞춤
+    pub fn region_size(&self) -> i32 {
+        let width = VoxelData::CHUNK_WIDTH;
+        (self.region_size.max(1) + width - 1) / width * width
+    }
+
+    pub fn region_of(&self, x: i32, z: i32) -> IVec2 {
+        let size = self.region_size();
+        IVec2::new(x.div_euclid(size), z.div_euclid(size))
+    }
+
+    // (x, z) 에서 region 의 결과를 섞는 가중치
+    // 영역 경계에서 0.5, 경계 안쪽 margin / 2 부터 1, 바깥쪽 margin / 2 부터 0
+    // 경계를 사이에 둔 두 영역의 가중치 합은 항상 1
+    pub fn blend_weight(&self, region: IVec2, x: i32, z: i32) -> f32 {
+        let size = self.region_size();
+        let width = self.margin.max(0) as f32;
+        let axis = |cell: i32, region: i32| {
+            let min = (region * size) as f32;
+            let center = cell as f32 + 0.5;
+            let inside = (center - min).min(min + size as f32 - center);
+            if width == 0.0 {
+                return if inside > 0.0 { 1.0 } else { 0.0 };
+            }
+            let t = (0.5 + inside / width).clamp(0.0, 1.0);
+            t * t * (3.0 - 2.0 * t)
+        };
+        axis(x, region.x) * axis(z, region.y)
+    }
+}
+
+// 침식된 heightmap, margin 까지 포함
+pub struct ErodedRegion {
+    // 월드 좌표 (x, z) 의 최소 모서리
+    pub origin: IVec2,
+    pub size: i32,
+    pub heights: Vec<f32>,
+}
+
+impl ErodedRegion {
+    pub fn get(&self, x: i32, z: i32) -> Option<f32> {
+        let (lx, lz) = (x - self.origin.x, z - self.origin.y);
+        if lx < 0 || lz < 0 || lx >= self.size || lz >= self.size {
+            return None;
+        }
+        Some(self.heights[(lz * self.size + lx) as usize])
+    }
+}
+
+// region 을 margin 까지 포함해서 침식, height 는 침식 전 지표 높이
+pub fn erode_region(
+    settings: &ErosionSettings,
+    seed: u32,
+    region: IVec2,
+    height: &dyn Fn(i32, i32) -> f32,
+) -> ErodedRegion {
+    let margin = settings.margin.max(0);
+    let origin = region * settings.region_size() - IVec2::splat(margin);
+    let size = settings.region_size() + margin * 2;
+
+    let mut heights = Vec::with_capacity((size * size) as usize);
+    for z in 0..size {
+        for x in 0..size {
+            heights.push(height(origin.x + x, origin.y + z));
+        }
+    }
+
+    let mut map = Heightmap { size, heights };
+    let brush = Brush::new(settings.erosion_radius);
+
+    // 월드 좌표 순서 (z, x) 로 물방울을 떨어뜨려서 겹치는 영역끼리 순서가 최대한 같도록 함
+    let whole = settings.droplets_per_block.max(0.0).floor() as u32;
+    let fraction = settings.droplets_per_block.max(0.0).fract();
+    for z in 0..size {
+        for x in 0..size {
+            let (wx, wz) = (origin.x + x, origin.y + z);
+            let mut count = whole;
+            if unit(hash(seed, wx, wz, u32::MAX)) < fraction {
+                count += 1;
+            }
+
+            for i in 0..count {
+                let start = Vec2::new(
+                    x as f32 + unit(hash(seed, wx, wz, i * 2)),
+                    z as f32 + unit(hash(seed, wx, wz, i * 2 + 1)),
+                );
+                map.simulate_droplet(settings, &brush, start);
+            }
+        }
+    }
+
+    ErodedRegion {
+        origin,
+        size,
+        heights: map.heights,
+    }
+}
+
+// 생성기가 들고 있는 침식 결과 캐시, 먼저 들어온 영역부터 버림
+pub struct ErosionMap {
+    pub settings: ErosionSettings,
+    seed: u32,
+    cache: Mutex<RegionCache>,
+}
+
+#[derive(Default)]
+struct RegionCache {
+    regions: HashMap<IVec2, Arc<ErodedRegion>>,
+    order: VecDeque<IVec2>,
+}
+
+impl ErosionMap {
+    pub fn new(settings: &ErosionSettings, seed: u32) -> Self {
+        ErosionMap {
+            settings: settings.clone(),
+            seed,
+            cache: Mutex::new(RegionCache::default()),
+        }
+    }
+
+    pub fn region(&self, region: IVec2, height: &dyn Fn(i32, i32) -> f32) -> Arc<ErodedRegion> {
+        let mut cache = self.cache.lock().unwrap();
+        if let Some(eroded) = cache.regions.get(&region) {
+            return eroded.clone();
+        }
+
+        let eroded = Arc::new(erode_region(&self.settings, self.seed, region, height));
+        while cache.order.len() >= self.settings.max_cached_regions.max(1) {
+            if let Some(oldest) = cache.order.pop_front() {
+                cache.regions.remove(&oldest);
+            }
+        }
+        cache.order.push_back(region);
+        cache.regions.insert(region, eroded.clone());
+        eroded
+    }
+
+    // 월드 좌표 (x, z) 의 침식된 지표 높이, 경계 근처는 최대 4 개 영역을 섞음
+    pub fn height(&self, x: i32, z: i32, height: &dyn Fn(i32, i32) -> f32) -> f32 {
+        let half = (self.settings.margin.max(0) + 1) / 2;
+        let min = self.settings.region_of(x - half, z - half);
+        let max = self.settings.region_of(x + half, z + half);
+
+        let mut sum = 0.0;
+        let mut total = 0.0;
+        for region_z in min.y..=max.y {
+            for region_x in min.x..=max.x {
+                let region = IVec2::new(region_x, region_z);
+                let weight = self.settings.blend_weight(region, x, z);
+                if weight <= 0.0 {
+                    continue;
+                }
+                let eroded = self.region(region, height).get(x, z);
+                sum += weight * eroded.unwrap_or_else(|| height(x, z));
+                total += weight;
+            }
+        }
+
+        if total > 0.0 {
+            sum / total
+        } else {
+            height(x, z)
+        }
+    }
+}
+
+// 깎을 때 쓰는 주변 칸과 가중치 (중심에서 멀수록 작음, 합 = 1)
+struct Brush {
+    offsets: Vec<(IVec2, f32)>,
+}
+
+impl Brush {
+    fn new(radius: i32) -> Self {
+        let radius = radius.max(1);
+        let mut offsets = Vec::new();
+        for z in -radius..=radius {
+            for x in -radius..=radius {
+                let distance = ((x * x + z * z) as f32).sqrt();
+                if distance < radius as f32 {
+                    offsets.push((IVec2::new(x, z), 1.0 - distance / radius as f32));
+                }
+            }
+        }
+
+        let total: f32 = offsets.iter().map(|(_, weight)| weight).sum();
+        for (_, weight) in &mut offsets {
+            *weight /= total;
+        }
+        Brush { offsets }
+    }
+}
+
+struct Heightmap {
+    size: i32,
+    heights: Vec<f32>,
+}
+
+impl Heightmap {
+    fn index(&self, x: i32, z: i32) -> usize {
+        (z * self.size + x) as usize
+    }
+
+    // pos 의 쌍선형 보간 높이와 경사
+    fn height_and_gradient(&self, pos: Vec2) -> (f32, Vec2) {
+        let (x, z) = (pos.x as i32, pos.y as i32);
+        let (u, v) = (pos.x - x as f32, pos.y - z as f32);
+
+        let h00 = self.heights[self.index(x, z)];
+        let h10 = self.heights[self.index(x + 1, z)];
+        let h01 = self.heights[self.index(x, z + 1)];
+        let h11 = self.heights[self.index(x + 1, z + 1)];
+
+        let gradient = Vec2::new(
+            (h10 - h00) * (1.0 - v) + (h11 - h01) * v,
+            (h01 - h00) * (1.0 - u) + (h11 - h10) * u,
+        );
+        let height =
+            h00 * (1.0 - u) * (1.0 - v) + h10 * u * (1.0 - v) + h01 * (1.0 - u) * v + h11 * u * v;
+        (height, gradient)
+    }
+
+    fn inside(&self, pos: Vec2) -> bool {
+        let max = (self.size - 1) as f32;
+        pos.x >= 0.0 && pos.y >= 0.0 && pos.x < max && pos.y < max
+    }
+
+    fn simulate_droplet(&mut self, settings: &ErosionSettings, brush: &Brush, start: Vec2) {
+        let mut pos = start;
+        let mut dir = Vec2::ZERO;
+        let mut speed = 1.0;
+        let mut water = 1.0;
+        let mut sediment = 0.0;
+
+        for _ in 0..settings.max_lifetime {
+            if !self.inside(pos) {
+                return;
+            }
+            let cell = IVec2::new(pos.x as i32, pos.y as i32);
+            let offset = pos - cell.as_vec2();
+            let (height, gradient) = self.height_and_gradient(pos);
+
+            dir = dir * settings.inertia - gradient * (1.0 - settings.inertia);
+            let Some(normalized) = dir.try_normalize() else {
+                return;
+            };
+            dir = normalized;
+            pos += dir;
+            if !self.inside(pos) {
+                return;
+            }
+
+            let delta_height = self.height_and_gradient(pos).0 - height;
+            let capacity = (-delta_height * speed * water * settings.sediment_capacity)
+                .max(settings.min_sediment_capacity);
+
+            if sediment > capacity || delta_height > 0.0 {
+                // 오르막이면 올라갈 높이까지 채우고, 아니면 넘치는 만큼 쌓음
+                let amount = if delta_height > 0.0 {
+                    delta_height.min(sediment)
+                } else {
+                    (sediment - capacity) * settings.deposit_speed
+                };
+                sediment -= amount;
+
+                let corners = [
+                    (IVec2::new(0, 0), (1.0 - offset.x) * (1.0 - offset.y)),
+                    (IVec2::new(1, 0), offset.x * (1.0 - offset.y)),
+                    (IVec2::new(0, 1), (1.0 - offset.x) * offset.y),
+                    (IVec2::new(1, 1), offset.x * offset.y),
+                ];
+                for (corner, weight) in corners {
+                    let p = cell + corner;
+                    let index = self.index(p.x, p.y);
+                    self.heights[index] += amount * weight;
+                }
+            } else {
+                // 내려간 높이보다 많이 깎지 않음 (구덩이가 생기지 않도록)
+                let amount = ((capacity - sediment) * settings.erode_speed).min(-delta_height);
+                for (brush_offset, weight) in &brush.offsets {
+                    let p = cell + *brush_offset;
+                    if p.x < 0 || p.y < 0 || p.x >= self.size || p.y >= self.size {
+                        continue;
+                    }
+                    let index = self.index(p.x, p.y);
+                    let eroded = (amount * weight).min(self.heights[index]);
+                    self.heights[index] -= eroded;
+                    sediment += eroded;
+                }
+            }
+
+            speed = (speed * speed - delta_height * settings.gravity)
+                .max(0.0)
+                .sqrt();
+            water *= 1.0 - settings.evaporate_speed;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 언덕과 골짜기가 있는 침식 전 지표
+    fn hills(x: i32, z: i32) -> f32 {
+        let (x, z) = (x as f32, z as f32);
+        40.0 + 12.0 * ((x * 0.07).sin() + (z * 0.05).cos()) + 6.0 * ((x + z) * 0.13).sin()
+    }
+
+    #[test]
+    fn height_steps_across_region_borders_match_the_inside() {
+        const MAX_STEP_RATIO: f32 = 1.5;
+
+        let settings = ErosionSettings::default();
+        let map = ErosionMap::new(&settings, 11);
+        let size = settings.region_size();
+        let used = |x: i32, z: i32| map.height(x, z, &hills);
+
+        // 영역 (-1, -1) ~ (0, 0), 원점을 지나는 두 경계
+        let mut depth = (0.0, 0);
+        let mut border_step = (0.0, 0);
+        let mut inner_step = (0.0, 0);
+        for z in -size..size {
+            for x in -size..size {
+                let value = used(x, z);
+                depth.0 += (value - hills(x, z)).abs();
+                depth.1 += 1;
+
+                for (px, pz) in [(x - 1, z), (x, z - 1)] {
+                    if px < -size || pz < -size {
+                        continue;
+                    }
+                    let step = (value - used(px, pz)).abs();
+                    if (px < 0) != (x < 0) || (pz < 0) != (z < 0) {
+                        border_step.0 += step;
+                        border_step.1 += 1;
+                    } else {
+                        inner_step.0 += step;
+                        inner_step.1 += 1;
+                    }
+                }
+            }
+        }
+
+        let mean = |(sum, count): (f32, i32)| sum / count as f32;
+        // 침식이 실제로 일어났어야 비교에 의미가 있음
+        assert!(mean(depth) > 0.05, "mean erosion depth {}", mean(depth));
+        assert!(
+            mean(border_step) <= mean(inner_step) * MAX_STEP_RATIO,
+            "border step {} inside step {}",
+            mean(border_step),
+            mean(inner_step)
+        );
+    }
+
+    #[test]
+    fn same_seed_gives_the_same_heights() {
+        let settings = ErosionSettings {
+            region_size: VoxelData::CHUNK_WIDTH * 2,
+            margin: 10,
+            ..Default::default()
+        };
+        let size = settings.region_size();
+        let heights = |seed: u32| {
+            let map = ErosionMap::new(&settings, seed);
+            let mut heights = Vec::new();
+            for z in -size..size {
+                for x in -size..size {
+                    heights.push(map.height(x, z, &hills));
+                }
+            }
+            heights
+        };
+
+        assert_eq!(heights(5), heights(5));
+        assert_ne!(heights(5), heights(6));
+    }
+}
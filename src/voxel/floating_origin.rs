@@ -0,0 +1,222 @@
+use super::chunk::ChunkCoord;
+use super::mesh::VoxelData;
+use bevy::prelude::*;
+use bevy_flycam::prelude::*;
+
+// 렌더링 좌표의 원점을 카메라 근처 청크로 옮겨 두는 floating origin
+//
+// World 와 청크는 계속 정수 청크 좌표를 쓰고, 엔티티의 Transform 만
+// "origin 청크 기준 상대 위치" 로 둠. 카메라가 origin 에서 rebase_distance 청크 이상
+// 멀어지면 origin 을 카메라 청크로 옮기고 청크, 조명, 카메라의 Transform 을 같이 이동시킴
+// -> 월드 좌표가 커져도 렌더링에 쓰는 f32 값은 항상 작게 유지됨
+#[derive(Resource)]
+pub struct FloatingOrigin {
+    origin: ChunkCoord,
+    pub rebase_distance: i32,
+}
+
+impl Default for FloatingOrigin {
+    fn default() -> Self {
+        FloatingOrigin {
+            origin: ChunkCoord::new(0, 0),
+            rebase_distance: 32,
+        }
+    }
+}
+
+impl FloatingOrigin {
+    // 렌더링 좌표 (0, 0, 0) 이 있는 청크
+    pub fn origin(&self) -> &ChunkCoord {
+        &self.origin
+    }
+
+    // 청크 엔티티에 줄 Translation, 정수로 뺀 뒤 f32 로 바꿔 정밀도 손실이 없음
+    pub fn chunk_translation(&self, coord: &ChunkCoord) -> Vec3 {
+        Vec3::new(
+            ((coord.x - self.origin.x) * VoxelData::CHUNK_WIDTH) as f32,
+            VoxelData::CHUNK_Y_OFFSET,
+            ((coord.z - self.origin.z) * VoxelData::CHUNK_WIDTH) as f32,
+        )
+    }
+
+    // 렌더링 좌표가 속한 월드 청크 좌표
+    pub fn chunk_coord_of(&self, render_pos: Vec3) -> ChunkCoord {
+        let local = ChunkCoord::from_world_pos(render_pos);
+        ChunkCoord::new(local.x + self.origin.x, local.z + self.origin.z)
+    }
+
+    // 렌더링 좌표 -> 월드 좌표, 원점에서 아주 멀면 정밀도가 떨어지므로 표시용으로만 사용
+    pub fn world_position(&self, render_pos: Vec3) -> Vec3 {
+        render_pos + self.origin.origin().to_vec3()
+    }
+}
+
+// origin 을 옮길 때 같이 이동시킬 엔티티 (부모가 없는 청크, 조명, 카메라)
+type Rebased = (
+    Without<Parent>,
+    Or<(
+        With<ChunkCoord>,
+        With<DirectionalLight>,
+        With<PointLight>,
+        With<SpotLight>,
+        With<Camera>,
+    )>,
+);
+
+pub fn rebase_floating_origin(
+    mut floating_origin: ResMut<FloatingOrigin>,
+    mut camera: Query<&mut Transform, With<FlyCam>>,
+    mut transforms: Query<&mut Transform, (Rebased, Without<FlyCam>)>,
+) {
+    let Ok(mut camera_transform) = camera.get_single_mut() else {
+        return;
+    };
+
+    let camera_chunk = floating_origin.chunk_coord_of(camera_transform.translation);
+    let distance = (camera_chunk.x - floating_origin.origin.x)
+        .abs()
+        .max((camera_chunk.z - floating_origin.origin.z).abs());
+    if distance < floating_origin.rebase_distance {
+        return;
+    }
+
+    // 새 origin 기준으로 모든 대상이 반대 방향으로 shift 만큼 이동
+    let shift = Vec3::new(
+        ((camera_chunk.x - floating_origin.origin.x) * VoxelData::CHUNK_WIDTH) as f32,
+        0.0,
+        ((camera_chunk.z - floating_origin.origin.z) * VoxelData::CHUNK_WIDTH) as f32,
+    );
+    floating_origin.origin = camera_chunk;
+
+    camera_transform.translation -= shift;
+    for mut transform in transforms.iter_mut() {
+        transform.translation -= shift;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::generator::GeneratorConfig;
+    use super::super::plugin::VoxelWorldPlugin;
+    use super::super::world::World;
+    use super::*;
+    use std::collections::HashMap;
+
+    // 청크, 조명, 카메라 엔티티의 Translation
+    fn translations(app: &mut App) -> HashMap<Entity, Vec3> {
+        app.world
+            .query_filtered::<(Entity, &Transform), Rebased>()
+            .iter(&app.world)
+            .map(|(entity, transform)| (entity, transform.translation))
+            .collect()
+    }
+
+    fn chunk_coords(app: &mut App) -> HashMap<Entity, ChunkCoord> {
+        app.world
+            .query::<(Entity, &ChunkCoord)>()
+            .iter(&app.world)
+            .map(|(entity, coord)| (entity, coord.clone()))
+            .collect()
+    }
+
+    fn world_chunks(app: &App) -> Vec<(i32, i32)> {
+        let mut coords: Vec<(i32, i32)> = app
+            .world
+            .resource::<World>()
+            .chunks()
+            .map(|chunk| (chunk.chunk_coord.x, chunk.chunk_coord.z))
+            .collect();
+        coords.sort();
+        coords
+    }
+
+    #[test]
+    fn rebase_shifts_every_rendered_entity_but_not_the_world() {
+        let plugin = VoxelWorldPlugin::builder()
+            .generator(GeneratorConfig::Checkerboard)
+            .view_distance(2)
+            .build()
+            .unwrap();
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, AssetPlugin::default()))
+            .init_asset::<Image>()
+            .init_asset::<Mesh>()
+            .init_asset::<StandardMaterial>()
+            .add_plugins(plugin);
+        app.finish();
+        let w = VoxelData::CHUNK_WIDTH as f32;
+        let camera = app
+            .world
+            .spawn((
+                FlyCam,
+                Camera::default(),
+                Transform::from_xyz(5.0, 40.0, 5.0),
+            ))
+            .id();
+        let light = app
+            .world
+            .spawn((
+                DirectionalLight::default(),
+                Transform::from_xyz(20.0, 100.0, 0.0),
+            ))
+            .id();
+
+        // rebase 없이 카메라를 x 로 3 청크 옮겨서 청크를 다 불러둠
+        app.world.resource_mut::<FloatingOrigin>().rebase_distance = 100;
+        app.update();
+        app.world
+            .get_mut::<Transform>(camera)
+            .unwrap()
+            .translation
+            .x = 3.0 * w + 5.0;
+        app.update();
+        app.update();
+
+        let before = translations(&mut app);
+        let coords_before = chunk_coords(&mut app);
+        let world_before = world_chunks(&app);
+        let view_center = app.world.resource::<World>().view_center().cloned();
+        assert_eq!(
+            view_center.as_ref().map(|(coord, _)| coord),
+            Some(&ChunkCoord::new(3, 0))
+        );
+        assert!(coords_before.len() > 1);
+
+        app.world.resource_mut::<FloatingOrigin>().rebase_distance = 2;
+        app.update();
+
+        let floating_origin = app.world.resource::<FloatingOrigin>();
+        assert_eq!(floating_origin.origin(), &ChunkCoord::new(3, 0));
+        let camera_translation = app.world.get::<Transform>(camera).unwrap().translation;
+        assert_eq!(
+            floating_origin.chunk_coord_of(camera_translation),
+            ChunkCoord::new(3, 0)
+        );
+
+        // 청크, 조명, 카메라가 모두 같은 만큼 이동
+        let shift = Vec3::new(3.0 * w, 0.0, 0.0);
+        let after = translations(&mut app);
+        assert_eq!(after.len(), before.len());
+        for (entity, translation) in &before {
+            assert_eq!(after[entity], *translation - shift, "{:?}", entity);
+        }
+        assert_eq!(after[&camera], Vec3::new(5.0, 40.0, 5.0));
+        assert_eq!(after[&light], Vec3::new(20.0 - 3.0 * w, 100.0, 0.0));
+
+        // 월드와 청크 좌표, 뷰 중심은 그대로
+        assert_eq!(chunk_coords(&mut app), coords_before);
+        assert_eq!(world_chunks(&app), world_before);
+        assert_eq!(
+            app.world.resource::<World>().view_center().cloned(),
+            view_center
+        );
+
+        // 다음 프레임에도 카메라는 같은 월드 청크에 있으므로 뷰를 다시 계산하지 않음
+        app.update();
+        assert_eq!(
+            app.world.resource::<World>().view_center().cloned(),
+            view_center
+        );
+        assert_eq!(world_chunks(&app), world_before);
+    }
+}
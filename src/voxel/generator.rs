@@ -0,0 +1,694 @@
+use super::block::{Block, EBlockType};
+use super::chunk::{Chunk, ChunkCoord};
+use super::coord::{LocalVoxelPos, WorldVoxelPos};
+use super::erosion::ErosionMap;
+use super::mesh::VoxelData;
+use super::river::{RiverColumn, RiverMap};
+use super::terrain::{
+    DensityNoise, DensityPreset, SplineNoise, SplinePreset, TerrainNoise, TerrainPreset,
+};
+use serde::{Deserialize, Serialize};
+
+// 청크 하나의 블록을 채우는 지형 생성기
+// 시드는 생성기를 만들 때 정해지고, fill_chunk 는 같은 좌표에 항상 같은 결과를 내야 함
+pub trait TerrainGenerator: Send + Sync {
+    fn seed(&self) -> u32;
+
+    // chunk 의 voxel_map 은 공기로 초기화된 상태로 넘어옴
+    fn fill_chunk(&self, coord: &ChunkCoord, chunk: &mut Chunk);
+
+    // 블록 pos 중심의 연속적인 밀도 (양수 = 고체), SurfaceNets 메쉬에 사용
+    // None 이면 fill_chunk 로 채운 블록에서 밀도를 만듦
+    fn density(&self, _pos: WorldVoxelPos) -> Option<f32> {
+        None
+    }
+}
+
+// 월드 설정에서 생성기를 고르는 값, RON 예) Superflat("bedrock,3*dirt,grass")
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum GeneratorConfig {
+    Noise(TerrainPreset),
+    // 3D 밀도 지형 (오버행, 아치, 떠 있는 섬)
+    Density(DensityPreset),
+    // 스플라인 지형 (바다, 해안, 평야, 산맥)
+    Spline(Box<SplinePreset>),
+    // 아래층부터 쉼표로 구분, "3*dirt" 는 dirt 3층
+    Superflat(String),
+    Void,
+    Checkerboard,
+}
+
+impl Default for GeneratorConfig {
+    fn default() -> Self {
+        GeneratorConfig::Noise(TerrainPreset::default())
+    }
+}
+
+impl GeneratorConfig {
+    pub const DEFAULT_SUPERFLAT_LAYERS: &'static str = "bedrock,3*dirt,grass";
+
+    // 커맨드라인용 : noise, density, spline, void, checkerboard, superflat, superflat:<layers>
+    pub fn parse(text: &str) -> Result<GeneratorConfig, String> {
+        let (name, arg) = match text.split_once(':') {
+            Some((name, arg)) => (name, Some(arg)),
+            None => (text, None),
+        };
+
+        match (name, arg) {
+            ("noise", None) => Ok(GeneratorConfig::default()),
+            ("density", None) => Ok(GeneratorConfig::Density(DensityPreset::default())),
+            ("spline", None) => Ok(GeneratorConfig::Spline(Box::default())),
+            ("void", None) => Ok(GeneratorConfig::Void),
+            ("checkerboard", None) => Ok(GeneratorConfig::Checkerboard),
+            ("superflat", layers) => Ok(GeneratorConfig::Superflat(
+                layers.unwrap_or(Self::DEFAULT_SUPERFLAT_LAYERS).to_string(),
+            )),
+            _ => Err(format!("unknown generator: {}", text)),
+        }
+    }
+
+    // 생성기를 만들지 않고 설정만 검사 (build 가 실패하는 경우와 같음)
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            GeneratorConfig::Superflat(layers) => parse_layers(layers).map(|_| ()),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn build(&self, seed: u32) -> Result<Box<dyn TerrainGenerator>, String> {
+        Ok(match self {
+            GeneratorConfig::Noise(preset) => Box::new(NoiseGenerator::new(preset, seed)),
+            GeneratorConfig::Density(preset) => Box::new(DensityGenerator::new(preset, seed)),
+            GeneratorConfig::Spline(preset) => Box::new(SplineGenerator::new(preset, seed)),
+            GeneratorConfig::Superflat(layers) => Box::new(SuperflatGenerator {
+                seed,
+                layers: parse_layers(layers)?,
+            }),
+            GeneratorConfig::Void => Box::new(VoidGenerator { seed }),
+            GeneratorConfig::Checkerboard => Box::new(CheckerboardGenerator { seed }),
+        })
+    }
+}
+
+// 노이즈 그래프 기반의 기본 지형
+pub struct NoiseGenerator {
+    terrain: TerrainNoise,
+    erosion: Option<ErosionMap>,
+}
+
+impl NoiseGenerator {
+    // 동굴 노이즈 (-1 ~ 1) 를 블록 단위 밀도로 바꾸는 배율
+    const CAVE_DENSITY_SCALE: f32 = 4.0;
+
+    pub fn new(preset: &TerrainPreset, seed: u32) -> Self {
+        NoiseGenerator {
+            terrain: preset.build(seed),
+            erosion: preset
+                .hydraulic_erosion
+                .as_ref()
+                .map(|settings| ErosionMap::new(settings, seed)),
+        }
+    }
+
+    // 침식이 있으면 침식된 높이
+    fn terrain_height(&self, x: i32, z: i32) -> f32 {
+        let height = |x: i32, z: i32| self.terrain.terrain_height(x as f32, z as f32);
+        match &self.erosion {
+            Some(erosion) => erosion.height(x, z, &height),
+            None => height(x, z),
+        }
+    }
+
+    fn get_block_type(&self, pos: WorldVoxelPos, terrain_height: f32) -> i32 {
+        if pos.y < 1 {
+            return EBlockType::BedRock as i32;
+        }
+
+        let min_ground = VoxelData::CHUNK_HEIGHT as f32 * 0.5;
+        let y = pos.y as f32;
+
+        // 동굴 노이즈는 지표와 min_ground 아래의 블록에서만 계산
+        if y == terrain_height.floor() {
+            EBlockType::Grass as i32
+        } else if y < terrain_height.floor() {
+            if y > min_ground {
+                EBlockType::Dirt as i32
+            } else if self.terrain.cave(pos.x as f32, pos.y as f32, pos.z as f32) < 0.0 {
+                EBlockType::Air as i32
+            } else {
+                EBlockType::Stone as i32
+            }
+        } else {
+            EBlockType::Air as i32
+        }
+    }
+}
+
+impl TerrainGenerator for NoiseGenerator {
+    fn seed(&self) -> u32 {
+        self.terrain.seed
+    }
+
+    // get_block_type 과 같은 지형, 지표 높이와 동굴 노이즈를 그대로 밀도로 씀
+    fn density(&self, pos: WorldVoxelPos) -> Option<f32> {
+        let terrain_height = self.terrain_height(pos.x, pos.z);
+        let y = pos.y as f32;
+        let mut density = terrain_height - y;
+
+        // 동굴은 min_ground 아래에서만, 한 칸에 걸쳐 섞어서 경계에 계단이 생기지 않도록 함
+        let min_ground = VoxelData::CHUNK_HEIGHT as f32 * 0.5;
+        let cave_weight = (min_ground - y + 1.0).clamp(0.0, 1.0);
+        if cave_weight > 0.0 {
+            let cave =
+                self.terrain.cave(pos.x as f32, y, pos.z as f32) as f32 * Self::CAVE_DENSITY_SCALE;
+            density = density.min(density + (cave - density) * cave_weight);
+        }
+
+        // 맨 아래 (y = 0) 는 항상 고체 (기반암)
+        Some(density.max(0.5 - y))
+    }
+
+    fn fill_chunk(&self, coord: &ChunkCoord, chunk: &mut Chunk) {
+        for x in 0..VoxelData::CHUNK_WIDTH {
+            for z in 0..VoxelData::CHUNK_WIDTH {
+                // 지형 높이는 (x, z) 에만 의존하므로 컬럼마다 한 번만 계산
+                let column = coord.to_world(LocalVoxelPos::new(x, 0, z));
+                let terrain_height = self.terrain_height(column.x, column.z);
+
+                for y in 0..VoxelData::CHUNK_HEIGHT {
+                    let local = LocalVoxelPos::new(x, y, z);
+                    let block_id = self.get_block_type(coord.to_world(local), terrain_height);
+                    chunk.set_voxel(local, block_id);
+                }
+            }
+        }
+    }
+}
+
+// 3D 밀도로 고체를 정하고, 위에서부터 컬럼을 훑어 지표 아래 깊이로 블록 종류를 정함
+// 오버행 아래나 떠 있는 섬 위처럼 공기 아래에 있는 블록도 지표로 보고 잔디를 깖
+pub struct DensityGenerator {
+    terrain: DensityNoise,
+}
+
+impl DensityGenerator {
+    pub fn new(preset: &DensityPreset, seed: u32) -> Self {
+        DensityGenerator {
+            terrain: preset.build(seed),
+        }
+    }
+
+    // depth : 위쪽 공기에서부터 센 고체 블록 수 (지표 = 0)
+    fn get_block_type(&self, y: i32, depth: i32) -> i32 {
+        if y < 1 {
+            EBlockType::BedRock as i32
+        } else if depth == 0 {
+            EBlockType::Grass as i32
+        } else if depth <= self.terrain.dirt_depth {
+            EBlockType::Dirt as i32
+        } else {
+            EBlockType::Stone as i32
+        }
+    }
+}
+
+impl TerrainGenerator for DensityGenerator {
+    fn seed(&self) -> u32 {
+        self.terrain.seed
+    }
+
+    fn fill_chunk(&self, coord: &ChunkCoord, chunk: &mut Chunk) {
+        for x in 0..VoxelData::CHUNK_WIDTH {
+            for z in 0..VoxelData::CHUNK_WIDTH {
+                let column = coord.to_world(LocalVoxelPos::new(x, 0, z));
+                let (wx, wz) = (column.x as f32, column.z as f32);
+                let base_height = self.terrain.base_height(wx, wz);
+
+                // 청크 위쪽은 공기로 보고 위에서부터 내려감
+                let mut depth = 0;
+                for y in (0..VoxelData::CHUNK_HEIGHT).rev() {
+                    let solid = y < 1 || self.terrain.density(wx, y as f32, wz, base_height) > 0.0;
+                    if !solid {
+                        depth = 0;
+                        continue;
+                    }
+
+                    chunk.set_voxel(LocalVoxelPos::new(x, y, z), self.get_block_type(y, depth));
+                    depth += 1;
+                }
+            }
+        }
+    }
+
+    fn density(&self, pos: WorldVoxelPos) -> Option<f32> {
+        let (x, y, z) = (pos.x as f32, pos.y as f32, pos.z as f32);
+        let density = self
+            .terrain
+            .density(x, y, z, self.terrain.base_height(x, z));
+        // 맨 아래 (y = 0) 는 항상 고체 (기반암)
+        Some(density.max(0.5 - y))
+    }
+}
+
+// 스플라인 지형, 지표 높이만으로 블록을 정함 (동굴 없음)
+pub struct SplineGenerator {
+    terrain: SplineNoise,
+    erosion: Option<ErosionMap>,
+    rivers: Option<RiverMap>,
+}
+
+impl SplineGenerator {
+    pub fn new(preset: &SplinePreset, seed: u32) -> Self {
+        let terrain = preset.build(seed);
+        let rivers = preset
+            .rivers
+            .as_ref()
+            .map(|settings| RiverMap::new(settings, seed, terrain.sea_level));
+
+        SplineGenerator {
+            terrain,
+            erosion: preset
+                .hydraulic_erosion
+                .as_ref()
+                .map(|settings| ErosionMap::new(settings, seed)),
+            rivers,
+        }
+    }
+
+    // 침식, 강을 차례로 적용한 지표 높이
+    fn surface(&self, x: i32, z: i32) -> RiverColumn {
+        let height = |x: i32, z: i32| self.terrain.terrain_height(x as f32, z as f32);
+        let terrain_height = match &self.erosion {
+            Some(erosion) => erosion.height(x, z, &height),
+            None => height(x, z),
+        };
+
+        match &self.rivers {
+            // 강의 경로는 detail 과 침식을 뺀 높이로 찾음
+            Some(rivers) => {
+                rivers.carve(x, z, terrain_height, &|x, z| self.terrain.base_height(x, z))
+            }
+            None => RiverColumn {
+                height: terrain_height,
+                bank: false,
+            },
+        }
+    }
+
+    fn get_block_type(&self, y: i32, surface: RiverColumn) -> i32 {
+        let top = surface.height.floor() as i32;
+        let sand = surface.bank || surface.height <= self.terrain.sea_level + 1.0;
+
+        if y < 1 {
+            EBlockType::BedRock as i32
+        } else if y > top {
+            EBlockType::Air as i32
+        } else if surface.height >= self.terrain.stone_height {
+            EBlockType::Stone as i32
+        } else if y > top - self.terrain.dirt_depth && sand {
+            EBlockType::Sand as i32
+        } else if y == top {
+            EBlockType::Grass as i32
+        } else if y > top - self.terrain.dirt_depth {
+            EBlockType::Dirt as i32
+        } else {
+            EBlockType::Stone as i32
+        }
+    }
+}
+
+impl TerrainGenerator for SplineGenerator {
+    fn seed(&self) -> u32 {
+        self.terrain.seed
+    }
+
+    fn fill_chunk(&self, coord: &ChunkCoord, chunk: &mut Chunk) {
+        for x in 0..VoxelData::CHUNK_WIDTH {
+            for z in 0..VoxelData::CHUNK_WIDTH {
+                let column = coord.to_world(LocalVoxelPos::new(x, 0, z));
+                let surface = self.surface(column.x, column.z);
+
+                for y in 0..VoxelData::CHUNK_HEIGHT {
+                    let block_id = self.get_block_type(y, surface);
+                    chunk.set_voxel(LocalVoxelPos::new(x, y, z), block_id);
+                }
+            }
+        }
+    }
+
+    fn density(&self, pos: WorldVoxelPos) -> Option<f32> {
+        let terrain_height = self.surface(pos.x, pos.z).height;
+        let y = pos.y as f32;
+        // 맨 아래 (y = 0) 는 항상 고체 (기반암)
+        Some((terrain_height - y).max(0.5 - y))
+    }
+}
+
+fn parse_layers(text: &str) -> Result<Vec<i32>, String> {
+    let mut layers = Vec::new();
+
+    for entry in text
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+    {
+        let (count, name) = match entry.split_once('*') {
+            Some((count, name)) => {
+                let count: usize = count
+                    .trim()
+                    .parse()
+                    .map_err(|_| format!("invalid layer count: {}", entry))?;
+                (count, name.trim())
+            }
+            None => (1, entry),
+        };
+
+        let block = EBlockType::from_name(name).ok_or(format!("unknown block: {}", name))?;
+        layers.extend(std::iter::repeat(block as i32).take(count));
+    }
+
+    if layers.len() > VoxelData::CHUNK_HEIGHT as usize {
+        return Err(format!(
+            "superflat has {} layers, chunk height is {}",
+            layers.len(),
+            VoxelData::CHUNK_HEIGHT
+        ));
+    }
+    Ok(layers)
+}
+
+// y = 0 부터 layers 를 차례로 쌓은 평지
+pub struct SuperflatGenerator {
+    seed: u32,
+    layers: Vec<i32>,
+}
+
+impl TerrainGenerator for SuperflatGenerator {
+    fn seed(&self) -> u32 {
+        self.seed
+    }
+
+    fn fill_chunk(&self, _coord: &ChunkCoord, chunk: &mut Chunk) {
+        for (y, block_id) in self.layers.iter().enumerate() {
+            for x in 0..VoxelData::CHUNK_WIDTH {
+                for z in 0..VoxelData::CHUNK_WIDTH {
+                    chunk.set_voxel(LocalVoxelPos::new(x, y as i32, z), *block_id);
+                }
+            }
+        }
+    }
+}
+
+// 아무것도 없는 월드, .vox 모델을 찍어 보거나 할 때 사용
+pub struct VoidGenerator {
+    seed: u32,
+}
+
+impl TerrainGenerator for VoidGenerator {
+    fn seed(&self) -> u32 {
+        self.seed
+    }
+
+    fn fill_chunk(&self, _coord: &ChunkCoord, _chunk: &mut Chunk) {}
+}
+
+// 텍스처/메쉬 확인용, y = 0 에 고체 블록을 체커보드 모양으로 한 칸씩 띄워 깔아서
+// 옆면도 보이도록 함, 블록 종류는 대각선 방향으로 모든 고체 블록을 순환
+pub struct CheckerboardGenerator {
+    seed: u32,
+}
+
+impl TerrainGenerator for CheckerboardGenerator {
+    fn seed(&self) -> u32 {
+        self.seed
+    }
+
+    fn fill_chunk(&self, coord: &ChunkCoord, chunk: &mut Chunk) {
+        let block = Block::new();
+        let solid_blocks: Vec<i32> = block
+            .block_types
+            .iter()
+            .enumerate()
+            .filter(|(_, block_type)| block_type.is_solid)
+            .map(|(block_id, _)| block_id as i32)
+            .collect();
+
+        for x in 0..VoxelData::CHUNK_WIDTH {
+            for z in 0..VoxelData::CHUNK_WIDTH {
+                let local = LocalVoxelPos::new(x, 0, z);
+                let pos = coord.to_world(local);
+                if (pos.x + pos.z).rem_euclid(2) != 0 {
+                    continue;
+                }
+
+                let cell = pos.x.div_euclid(2) + pos.z.div_euclid(2);
+                let index = cell.rem_euclid(solid_blocks.len() as i32) as usize;
+                chunk.set_voxel(local, solid_blocks[index]);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GRASS: i32 = EBlockType::Grass as i32;
+    const STONE: i32 = EBlockType::Stone as i32;
+    const BEDROCK: i32 = EBlockType::BedRock as i32;
+    const AIR: i32 = EBlockType::Air as i32;
+    const SAND: i32 = EBlockType::Sand as i32;
+    const DIRT: i32 = EBlockType::Dirt as i32;
+
+    #[test]
+    fn parse_layers_with_counts() {
+        assert_eq!(
+            parse_layers("bedrock,3*dirt,grass"),
+            Ok(vec![BEDROCK, DIRT, DIRT, DIRT, GRASS])
+        );
+        // 공백, 대소문자, 끝의 쉼표, 0 개
+        assert_eq!(
+            parse_layers(" Bedrock , 2 * STONE,0*dirt, "),
+            Ok(vec![BEDROCK, STONE, STONE])
+        );
+        assert_eq!(parse_layers("air,grass"), Ok(vec![AIR, GRASS]));
+    }
+
+    #[test]
+    fn parse_layers_empty_input() {
+        assert_eq!(parse_layers(""), Ok(vec![]));
+        assert_eq!(parse_layers(" , ,"), Ok(vec![]));
+    }
+
+    #[test]
+    fn parse_layers_malformed_input() {
+        for text in [
+            "lava",
+            "3*",
+            "*dirt",
+            "-1*dirt",
+            "x*dirt",
+            "2*3*dirt",
+            "dirt grass",
+        ] {
+            assert!(parse_layers(text).is_err(), "{}", text);
+        }
+        // 청크 높이보다 많은 층
+        let too_many = format!("{}*stone", VoxelData::CHUNK_HEIGHT + 1);
+        assert!(parse_layers(&too_many).is_err());
+        let exact = format!("{}*stone", VoxelData::CHUNK_HEIGHT);
+        assert_eq!(
+            parse_layers(&exact).unwrap().len(),
+            VoxelData::CHUNK_HEIGHT as usize
+        );
+    }
+
+    #[test]
+    fn parse_generator_names() {
+        assert!(matches!(
+            GeneratorConfig::parse("void"),
+            Ok(GeneratorConfig::Void)
+        ));
+        assert!(matches!(
+            GeneratorConfig::parse("checkerboard"),
+            Ok(GeneratorConfig::Checkerboard)
+        ));
+        assert!(matches!(
+            GeneratorConfig::parse("superflat"),
+            Ok(GeneratorConfig::Superflat(layers)) if layers == GeneratorConfig::DEFAULT_SUPERFLAT_LAYERS
+        ));
+        assert!(matches!(
+            GeneratorConfig::parse("superflat:stone,grass"),
+            Ok(GeneratorConfig::Superflat(layers)) if layers == "stone,grass"
+        ));
+        assert!(GeneratorConfig::parse("void:1").is_err());
+        assert!(GeneratorConfig::parse("mountains").is_err());
+        assert!(GeneratorConfig::Superflat("lava".to_string())
+            .build(0)
+            .is_err());
+    }
+
+    fn block_at(config: &GeneratorConfig, pos: WorldVoxelPos) -> i32 {
+        let generator = config.build(1).unwrap();
+        let chunk = Chunk::generate(pos.chunk_coord(), generator.as_ref());
+        chunk.get_voxel(pos.local()).unwrap()
+    }
+
+    #[test]
+    fn superflat_stacks_layers_from_the_bottom() {
+        let config = GeneratorConfig::Superflat("bedrock,3*dirt,grass".to_string());
+        for (x, z) in [(0, 0), (-1, -1), (37, -15)] {
+            let column: Vec<i32> = (0..7)
+                .map(|y| block_at(&config, WorldVoxelPos::new(x, y, z)))
+                .collect();
+            assert_eq!(column, [BEDROCK, DIRT, DIRT, DIRT, GRASS, AIR, AIR]);
+        }
+    }
+
+    #[test]
+    fn void_is_empty() {
+        for pos in [
+            WorldVoxelPos::new(0, 0, 0),
+            WorldVoxelPos::new(-5, 3, 12),
+            WorldVoxelPos::new(9, VoxelData::CHUNK_HEIGHT - 1, -9),
+        ] {
+            assert_eq!(block_at(&GeneratorConfig::Void, pos), AIR);
+        }
+    }
+
+    #[test]
+    fn checkerboard_alternates_solid_and_air() {
+        let config = GeneratorConfig::Checkerboard;
+        // (x + z) 가 짝수인 칸에만 y = 0 에 블록이 있음
+        assert_eq!(block_at(&config, WorldVoxelPos::new(0, 0, 0)), GRASS);
+        assert_eq!(block_at(&config, WorldVoxelPos::new(1, 0, 0)), AIR);
+        assert_eq!(block_at(&config, WorldVoxelPos::new(-1, 0, 0)), AIR);
+        assert_eq!(block_at(&config, WorldVoxelPos::new(2, 0, 0)), STONE);
+        // 블록 종류는 (x / 2 + z / 2) 로 고체 블록을 순환, 음수는 내림
+        assert_eq!(block_at(&config, WorldVoxelPos::new(-1, 0, -1)), SAND);
+        assert_eq!(block_at(&config, WorldVoxelPos::new(4, 0, 4)), DIRT);
+        assert_eq!(block_at(&config, WorldVoxelPos::new(0, 1, 0)), AIR);
+    }
+
+    // 청크 여러 개의 모든 컬럼 (아래에서 위로)
+    fn columns(generator: &dyn TerrainGenerator, chunks: i32) -> Vec<Vec<i32>> {
+        let mut columns = Vec::new();
+        for cz in 0..chunks {
+            for cx in 0..chunks {
+                let chunk = Chunk::generate(ChunkCoord::new(cx, cz), generator);
+                for x in 0..VoxelData::CHUNK_WIDTH {
+                    for z in 0..VoxelData::CHUNK_WIDTH {
+                        columns.push(
+                            (0..VoxelData::CHUNK_HEIGHT)
+                                .map(|y| chunk.get_voxel(LocalVoxelPos::new(x, y, z)).unwrap())
+                                .collect(),
+                        );
+                    }
+                }
+            }
+        }
+        columns
+    }
+
+    // 위에서부터 센 고체 구간 (위, 아래) 목록
+    fn solid_runs(column: &[i32]) -> Vec<(usize, usize)> {
+        let mut runs = Vec::new();
+        let mut top = None;
+        for (y, &block_id) in column.iter().enumerate().rev() {
+            match (block_id != AIR, top) {
+                (true, None) => top = Some(y),
+                (false, Some(run_top)) => {
+                    runs.push((run_top, y + 1));
+                    top = None;
+                }
+                _ => {}
+            }
+        }
+        if let Some(run_top) = top {
+            runs.push((run_top, 0));
+        }
+        runs
+    }
+
+    fn overhang_preset() -> DensityPreset {
+        DensityPreset {
+            squash: 0.05,
+            stretch: 2.0,
+            noise_amplitude: 3.0,
+            dirt_depth: 2,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn squashed_density_terrain_has_overhangs() {
+        let generator = DensityGenerator::new(&overhang_preset(), 3);
+        let overhangs = columns(&generator, 4)
+            .iter()
+            .filter(|column| solid_runs(column).len() > 1)
+            .count();
+        assert!(overhangs > 0);
+
+        // 3D 노이즈가 없으면 지표 하나뿐
+        let flat = DensityPreset {
+            noise_amplitude: 0.0,
+            ..overhang_preset()
+        };
+        let generator = DensityGenerator::new(&flat, 3);
+        for column in columns(&generator, 2) {
+            assert_eq!(solid_runs(&column).len(), 1);
+        }
+    }
+
+    #[test]
+    fn density_terrain_has_grass_and_dirt_on_every_solid_run() {
+        let preset = overhang_preset();
+        let generator = DensityGenerator::new(&preset, 3);
+        let mut runs_under_overhangs = 0;
+        for column in columns(&generator, 4) {
+            for (i, (top, bottom)) in solid_runs(&column).into_iter().enumerate() {
+                if i > 0 {
+                    runs_under_overhangs += 1;
+                }
+                for (y, &block_id) in column.iter().enumerate().take(top + 1).skip(bottom) {
+                    let depth = (top - y) as i32;
+                    let expected = if y == 0 {
+                        BEDROCK
+                    } else if depth == 0 {
+                        GRASS
+                    } else if depth <= preset.dirt_depth {
+                        DIRT
+                    } else {
+                        STONE
+                    };
+                    assert_eq!(block_id, expected, "y {} in run {}..={}", y, bottom, top);
+                }
+            }
+        }
+        assert!(runs_under_overhangs > 0);
+    }
+
+    #[test]
+    fn spline_terrain_has_oceans_and_mountains() {
+        let preset = SplinePreset::default();
+        let generator = SplineGenerator::new(&preset, 5);
+        let (mut ocean, mut mountains) = (0, 0);
+        // 대륙 노이즈는 수백 블록 단위라서 청크를 듬성듬성 봄
+        for cz in (-400..400).step_by(32) {
+            for cx in (-400..400).step_by(32) {
+                let chunk = Chunk::generate(ChunkCoord::new(cx, cz), &generator);
+                let top = chunk.highest_solid_block(0, 0).unwrap();
+                let surface = chunk.get_voxel(LocalVoxelPos::new(0, top, 0)).unwrap();
+                if (top as f64) < preset.sea_level {
+                    assert_eq!(surface, SAND, "chunk {} {}", cx, cz);
+                    ocean += 1;
+                }
+                if top as f64 >= preset.stone_height {
+                    assert_eq!(surface, STONE, "chunk {} {}", cx, cz);
+                    mountains += 1;
+                }
+            }
+        }
+        assert!(ocean > 0 && mountains > 0, "{} {}", ocean, mountains);
+    }
+}
@@ -0,0 +1,229 @@
+use super::block::{Block, EBlockType};
+use super::chunk::ChunkCoord;
+use super::coord::WorldVoxelPos;
+use super::mesh::VoxelData;
+use super::world::World;
+use image::{ImageResult, Rgba, RgbaImage};
+use std::path::Path;
+
+// 공기(또는 생성되지 않은 영역)를 칠할 색
+const EMPTY_COLOR: Rgba<u8> = Rgba([0, 0, 0, 0]);
+
+fn block_color(block: &Block, block_id: i32) -> Rgba<u8> {
+    if block_id == EBlockType::Air as i32 {
+        return EMPTY_COLOR;
+    }
+
+    let [r, g, b] = block.block_types[block_id as usize].map_color;
+    Rgba([r, g, b, 255])
+}
+
+// 높이에 따라 어둡게 칠해서 지형의 높낮이가 보이도록 함
+fn shade(color: Rgba<u8>, height: i32) -> Rgba<u8> {
+    let factor = 0.4 + 0.6 * (height as f32 / (VoxelData::CHUNK_HEIGHT - 1) as f32);
+    let Rgba([r, g, b, a]) = color;
+
+    Rgba([
+        (r as f32 * factor) as u8,
+        (g as f32 * factor) as u8,
+        (b as f32 * factor) as u8,
+        a,
+    ])
+}
+
+// 해당 컬럼에서 가장 높은 블록 (공기 제외)의 높이와 id
+pub fn surface_block(world: &World, x: i32, z: i32) -> Option<(i32, i32)> {
+    let y = world.highest_solid_block(x, z)?;
+    let block_id = world.get_block_id(WorldVoxelPos::new(x, y, z))?;
+    Some((y, block_id))
+}
+
+// [min, max) 청크 범위를 위에서 내려다본 heightmap, 픽셀 1개 = 컬럼 1개
+pub fn render_heightmap(world: &World, min: &ChunkCoord, max: &ChunkCoord) -> RgbaImage {
+    let block = Block::new();
+    let origin_x = min.x * VoxelData::CHUNK_WIDTH;
+    let origin_z = min.z * VoxelData::CHUNK_WIDTH;
+    let width = ((max.x - min.x) * VoxelData::CHUNK_WIDTH) as u32;
+    let depth = ((max.z - min.z) * VoxelData::CHUNK_WIDTH) as u32;
+
+    RgbaImage::from_fn(width, depth, |px, pz| {
+        let x = origin_x + px as i32;
+        let z = origin_z + pz as i32;
+
+        match surface_block(world, x, z) {
+            Some((height, id)) => shade(block_color(&block, id), height),
+            None => EMPTY_COLOR,
+        }
+    })
+}
+
+// z 가 고정된 XY 단면, 이미지 위쪽이 +Y
+pub fn render_slice_x(world: &World, min: &ChunkCoord, max: &ChunkCoord, z: i32) -> RgbaImage {
+    let block = Block::new();
+    let origin_x = min.x * VoxelData::CHUNK_WIDTH;
+    let width = ((max.x - min.x) * VoxelData::CHUNK_WIDTH) as u32;
+
+    RgbaImage::from_fn(width, VoxelData::CHUNK_HEIGHT as u32, |px, py| {
+        let y = VoxelData::CHUNK_HEIGHT - 1 - py as i32;
+        match world.get_block_id(WorldVoxelPos::new(origin_x + px as i32, y, z)) {
+            Some(id) => block_color(&block, id),
+            None => EMPTY_COLOR,
+        }
+    })
+}
+
+// x 가 고정된 ZY 단면, 이미지 위쪽이 +Y
+pub fn render_slice_z(world: &World, min: &ChunkCoord, max: &ChunkCoord, x: i32) -> RgbaImage {
+    let block = Block::new();
+    let origin_z = min.z * VoxelData::CHUNK_WIDTH;
+    let depth = ((max.z - min.z) * VoxelData::CHUNK_WIDTH) as u32;
+
+    RgbaImage::from_fn(depth, VoxelData::CHUNK_HEIGHT as u32, |pz, py| {
+        let y = VoxelData::CHUNK_HEIGHT - 1 - py as i32;
+        match world.get_block_id(WorldVoxelPos::new(x, y, origin_z + pz as i32)) {
+            Some(id) => block_color(&block, id),
+            None => EMPTY_COLOR,
+        }
+    })
+}
+
+pub fn export_heightmap<P: AsRef<Path>>(
+    world: &World,
+    min: &ChunkCoord,
+    max: &ChunkCoord,
+    path: P,
+) -> ImageResult<()> {
+    render_heightmap(world, min, max).save(path)
+}
+
+pub fn export_slice_x<P: AsRef<Path>>(
+    world: &World,
+    min: &ChunkCoord,
+    max: &ChunkCoord,
+    z: i32,
+    path: P,
+) -> ImageResult<()> {
+    render_slice_x(world, min, max, z).save(path)
+}
+
+pub fn export_slice_z<P: AsRef<Path>>(
+    world: &World,
+    min: &ChunkCoord,
+    max: &ChunkCoord,
+    x: i32,
+    path: P,
+) -> ImageResult<()> {
+    render_slice_z(world, min, max, x).save(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::generator::GeneratorConfig;
+    use super::*;
+
+    const W: i32 = VoxelData::CHUNK_WIDTH;
+    const H: i32 = VoxelData::CHUNK_HEIGHT;
+
+    // 청크 (0, 0), (1, 0) 만 생성한 월드
+    fn world(generator: GeneratorConfig) -> World {
+        let mut world = World::with_generator(generator.build(0).unwrap());
+        world.generate_region(&ChunkCoord::new(0, 0), &ChunkCoord::new(2, 1));
+        world
+    }
+
+    fn superflat() -> World {
+        world(GeneratorConfig::Superflat(
+            "bedrock,3*dirt,grass".to_string(),
+        ))
+    }
+
+    fn color(block_type: EBlockType) -> Rgba<u8> {
+        block_color(&Block::new(), block_type as i32)
+    }
+
+    #[test]
+    fn heightmap_shades_the_surface_block() {
+        let image = render_heightmap(&superflat(), &ChunkCoord::new(0, 0), &ChunkCoord::new(3, 1));
+        assert_eq!(image.dimensions(), (3 * W as u32, W as u32));
+
+        let grass = shade(color(EBlockType::Grass), 4);
+        assert_eq!(*image.get_pixel(0, 0), grass);
+        assert_eq!(*image.get_pixel(2 * W as u32 - 1, W as u32 - 1), grass);
+        // 생성되지 않은 청크는 투명
+        assert_eq!(*image.get_pixel(2 * W as u32, 0), EMPTY_COLOR);
+    }
+
+    #[test]
+    fn heightmap_of_a_checkerboard_alternates() {
+        let image = render_heightmap(
+            &world(GeneratorConfig::Checkerboard),
+            &ChunkCoord::new(0, 0),
+            &ChunkCoord::new(1, 1),
+        );
+        // 2 x 2 칸마다 다음 고체 블록, 바닥 (y = 0) 한 층이라 가장 어둡게 칠함
+        for (x, z, block_type) in [
+            (0, 0, EBlockType::Grass),
+            (1, 1, EBlockType::Grass),
+            (2, 0, EBlockType::Stone),
+            (2, 2, EBlockType::BedRock),
+            (3, 5, EBlockType::Sand),
+            (4, 4, EBlockType::Dirt),
+        ] {
+            assert_eq!(
+                *image.get_pixel(x, z),
+                shade(color(block_type), 0),
+                "({}, {})",
+                x,
+                z
+            );
+        }
+        for (x, z) in [(1, 0), (0, 1), (4, 7)] {
+            assert_eq!(*image.get_pixel(x, z), EMPTY_COLOR);
+        }
+    }
+
+    #[test]
+    fn slices_put_the_bottom_layer_on_the_last_row() {
+        let world = superflat();
+        let min = ChunkCoord::new(0, 0);
+        let max = ChunkCoord::new(2, 1);
+
+        let slice_x = render_slice_x(&world, &min, &max, 3);
+        assert_eq!(slice_x.dimensions(), (2 * W as u32, H as u32));
+        let slice_z = render_slice_z(&world, &min, &max, 3);
+        assert_eq!(slice_z.dimensions(), (W as u32, H as u32));
+
+        let bottom = H as u32 - 1;
+        for slice in [&slice_x, &slice_z] {
+            assert_eq!(*slice.get_pixel(0, bottom), color(EBlockType::BedRock));
+            assert_eq!(*slice.get_pixel(1, bottom - 1), color(EBlockType::Dirt));
+            assert_eq!(*slice.get_pixel(2, bottom - 4), color(EBlockType::Grass));
+            assert_eq!(*slice.get_pixel(2, bottom - 5), EMPTY_COLOR);
+            assert_eq!(*slice.get_pixel(0, 0), EMPTY_COLOR);
+        }
+    }
+
+    #[test]
+    fn exported_png_keeps_the_rendered_pixels() {
+        let world = superflat();
+        let min = ChunkCoord::new(0, 0);
+        let max = ChunkCoord::new(2, 1);
+        let dir = std::env::temp_dir().join(format!("pcg_engine_png_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let heightmap = dir.join("heightmap.png");
+        export_heightmap(&world, &min, &max, &heightmap).unwrap();
+        let slice = dir.join("slice.png");
+        export_slice_x(&world, &min, &max, 0, &slice).unwrap();
+
+        assert_eq!(
+            image::open(&heightmap).unwrap().to_rgba8(),
+            render_heightmap(&world, &min, &max)
+        );
+        assert_eq!(
+            image::open(&slice).unwrap().to_rgba8(),
+            render_slice_x(&world, &min, &max, 0)
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
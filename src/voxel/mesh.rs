@@ -7,12 +7,18 @@ impl VoxelData {
     pub const CHUNK_HEIGHT: i32 = 20;
     pub const CHUNK_WIDTH: i32 = 10;
 
+    pub const TEXTURE_ATLAS_PATH: &'static str = "Blocks.png";
     pub const TEXTURE_ATLAS_SIZE: i32 = 4;
     pub const NORMALIZE_BLOCK_TEXTURE_SIZE: f32 = 1.0 / 4 as f32;
 
     pub const WORLD_SIZE: usize = 30;
     pub const VIEW_DISTANCE_IN_CHUNKS: i32 = 10;
 
+    pub const DEFAULT_SEED: u32 = 132;
+
+    // 청크 엔티티를 월드에 놓을 때 y 축으로 내리는 양
+    pub const CHUNK_Y_OFFSET: f32 = -10.0;
+
     // 큐브의 8개 버텍스의 상대 위치
     pub const VOXEL_VERTS: [Vec3; 8] = [
         // Front
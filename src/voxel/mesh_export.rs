@@ -0,0 +1,401 @@
+use super::chunk::ChunkCoord;
+use super::world::World;
+use bevy::prelude::*;
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+
+// 여러 청크의 메쉬 데이터를 하나로 합친 것
+pub struct MergedMesh {
+    pub vertices: Vec<Vec3>,
+    pub uvs: Vec<Vec2>,
+    pub triangles: Vec<u32>,
+}
+
+impl MergedMesh {
+    pub fn is_empty(&self) -> bool {
+        self.triangles.is_empty()
+    }
+}
+
+// [min, max) 범위에서 생성된 청크들의 메쉬를 합침
+pub fn merge_region(world: &World, min: &ChunkCoord, max: &ChunkCoord) -> MergedMesh {
+    let mut merged = MergedMesh {
+        vertices: Vec::new(),
+        uvs: Vec::new(),
+        triangles: Vec::new(),
+    };
+
+    for z in min.z..max.z {
+        for x in min.x..max.x {
+            let Some(chunk) = world.get_chunk(&ChunkCoord { x, z }) else {
+                continue;
+            };
+
+            let base_index = merged.vertices.len() as u32;
+            // 청크 메쉬는 로컬 좌표이므로 월드 위치로 옮겨서 합침
+            let offset = chunk.world_offset();
+            merged
+                .vertices
+                .extend(chunk.vertices.iter().map(|v| *v + offset));
+            // SurfaceNets 메쉬는 UV 가 없어서 (0, 0) 으로 채움 (버텍스 색은 내보내지 않음)
+            if chunk.uvs.len() == chunk.vertices.len() {
+                merged.uvs.extend_from_slice(&chunk.uvs);
+            } else {
+                merged.uvs.resize(merged.vertices.len(), Vec2::ZERO);
+            }
+            merged
+                .triangles
+                .extend(chunk.triangles.iter().map(|index| index + base_index));
+        }
+    }
+
+    merged
+}
+
+fn empty_region_error() -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, "region has no geometry")
+}
+
+// 대상이 아직 없으면 다른 파일
+fn is_same_file(a: &Path, b: &Path) -> bool {
+    match (a.canonicalize(), b.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => false,
+    }
+}
+
+// OBJ + MTL, 텍스처 아틀라스는 obj 파일 옆에 복사됨
+pub fn export_obj<P: AsRef<Path>>(
+    world: &World,
+    min: &ChunkCoord,
+    max: &ChunkCoord,
+    path: P,
+    atlas_path: &Path,
+) -> io::Result<()> {
+    let mesh = merge_region(world, min, max);
+    if mesh.is_empty() {
+        return Err(empty_region_error());
+    }
+
+    let path = path.as_ref();
+    let dir = path.parent().unwrap_or(Path::new(""));
+    let mtl_path = path.with_extension("mtl");
+    let mtl_name = mtl_path
+        .file_name()
+        .ok_or(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "invalid obj path",
+        ))?
+        .to_string_lossy()
+        .to_string();
+    let atlas_name = atlas_path
+        .file_name()
+        .ok_or(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "invalid atlas path",
+        ))?
+        .to_string_lossy()
+        .to_string();
+
+    // assets 폴더로 내보내면 원본과 대상이 같은 파일, fs::copy 는 대상을 먼저 비워서 원본이 지워짐
+    let atlas_copy = dir.join(&atlas_name);
+    if !is_same_file(atlas_path, &atlas_copy) {
+        fs::copy(atlas_path, atlas_copy)?;
+    }
+
+    let mut mtl = io::BufWriter::new(fs::File::create(&mtl_path)?);
+    writeln!(mtl, "newmtl blocks")?;
+    writeln!(mtl, "Ka 1.0 1.0 1.0")?;
+    writeln!(mtl, "Kd 1.0 1.0 1.0")?;
+    writeln!(mtl, "Ks 0.0 0.0 0.0")?;
+    writeln!(mtl, "illum 1")?;
+    writeln!(mtl, "map_Kd {}", atlas_name)?;
+    mtl.flush()?;
+
+    let mut obj = io::BufWriter::new(fs::File::create(path)?);
+    writeln!(obj, "mtllib {}", mtl_name)?;
+    writeln!(obj, "o terrain")?;
+    for v in &mesh.vertices {
+        writeln!(obj, "v {} {} {}", v.x, v.y, v.z)?;
+    }
+    // OBJ 는 텍스처 좌표의 원점이 좌하단
+    for uv in &mesh.uvs {
+        writeln!(obj, "vt {} {}", uv.x, 1.0 - uv.y)?;
+    }
+    writeln!(obj, "usemtl blocks")?;
+    for tri in mesh.triangles.chunks(3) {
+        let (a, b, c) = (tri[0] + 1, tri[1] + 1, tri[2] + 1);
+        writeln!(obj, "f {a}/{a} {b}/{b} {c}/{c}")?;
+    }
+    obj.flush()
+}
+
+fn pad_to_four(buffer: &mut Vec<u8>, pad: u8) {
+    while buffer.len() % 4 != 0 {
+        buffer.push(pad);
+    }
+}
+
+// glTF 바이너리 (.glb), 텍스처 아틀라스 png 를 buffer 안에 그대로 포함
+pub fn export_glb<P: AsRef<Path>>(
+    world: &World,
+    min: &ChunkCoord,
+    max: &ChunkCoord,
+    path: P,
+    atlas_path: &Path,
+) -> io::Result<()> {
+    let mesh = merge_region(world, min, max);
+    if mesh.is_empty() {
+        return Err(empty_region_error());
+    }
+    let atlas = fs::read(atlas_path)?;
+
+    // BIN chunk : position | uv | index | image
+    let mut bin: Vec<u8> = Vec::new();
+    let mut bound_min = Vec3::splat(f32::MAX);
+    let mut bound_max = Vec3::splat(f32::MIN);
+
+    let position_offset = bin.len();
+    for v in &mesh.vertices {
+        bound_min = bound_min.min(*v);
+        bound_max = bound_max.max(*v);
+        for c in v.to_array() {
+            bin.extend_from_slice(&c.to_le_bytes());
+        }
+    }
+    let position_length = bin.len() - position_offset;
+
+    let uv_offset = bin.len();
+    for uv in &mesh.uvs {
+        for c in uv.to_array() {
+            bin.extend_from_slice(&c.to_le_bytes());
+        }
+    }
+    let uv_length = bin.len() - uv_offset;
+
+    let index_offset = bin.len();
+    for index in &mesh.triangles {
+        bin.extend_from_slice(&index.to_le_bytes());
+    }
+    let index_length = bin.len() - index_offset;
+
+    let image_offset = bin.len();
+    bin.extend_from_slice(&atlas);
+    let image_length = bin.len() - image_offset;
+    pad_to_four(&mut bin, 0);
+
+    let json = format!(
+        concat!(
+            "{{",
+            "\"asset\":{{\"version\":\"2.0\",\"generator\":\"pcg_engine\"}},",
+            "\"scene\":0,",
+            "\"scenes\":[{{\"nodes\":[0]}}],",
+            "\"nodes\":[{{\"name\":\"terrain\",\"mesh\":0}}],",
+            "\"meshes\":[{{\"primitives\":[{{\"attributes\":{{\"POSITION\":0,\"TEXCOORD_0\":1}},\"indices\":2,\"material\":0}}]}}],",
+            "\"materials\":[{{\"name\":\"blocks\",\"pbrMetallicRoughness\":{{\"baseColorTexture\":{{\"index\":0}},\"metallicFactor\":0.0,\"roughnessFactor\":1.0}}}}],",
+            "\"textures\":[{{\"sampler\":0,\"source\":0}}],",
+            // 픽셀 아트 아틀라스이므로 NEAREST 필터
+            "\"samplers\":[{{\"magFilter\":9728,\"minFilter\":9728}}],",
+            "\"images\":[{{\"bufferView\":3,\"mimeType\":\"image/png\"}}],",
+            "\"buffers\":[{{\"byteLength\":{buffer_length}}}],",
+            "\"bufferViews\":[",
+            "{{\"buffer\":0,\"byteOffset\":{position_offset},\"byteLength\":{position_length},\"target\":34962}},",
+            "{{\"buffer\":0,\"byteOffset\":{uv_offset},\"byteLength\":{uv_length},\"target\":34962}},",
+            "{{\"buffer\":0,\"byteOffset\":{index_offset},\"byteLength\":{index_length},\"target\":34963}},",
+            "{{\"buffer\":0,\"byteOffset\":{image_offset},\"byteLength\":{image_length}}}",
+            "],",
+            "\"accessors\":[",
+            "{{\"bufferView\":0,\"componentType\":5126,\"count\":{vertex_count},\"type\":\"VEC3\",\"min\":[{min_x},{min_y},{min_z}],\"max\":[{max_x},{max_y},{max_z}]}},",
+            "{{\"bufferView\":1,\"componentType\":5126,\"count\":{vertex_count},\"type\":\"VEC2\"}},",
+            "{{\"bufferView\":2,\"componentType\":5125,\"count\":{index_count},\"type\":\"SCALAR\"}}",
+            "]",
+            "}}"
+        ),
+        buffer_length = bin.len(),
+        position_offset = position_offset,
+        position_length = position_length,
+        uv_offset = uv_offset,
+        uv_length = uv_length,
+        index_offset = index_offset,
+        index_length = index_length,
+        image_offset = image_offset,
+        image_length = image_length,
+        vertex_count = mesh.vertices.len(),
+        index_count = mesh.triangles.len(),
+        min_x = bound_min.x,
+        min_y = bound_min.y,
+        min_z = bound_min.z,
+        max_x = bound_max.x,
+        max_y = bound_max.y,
+        max_z = bound_max.z,
+    );
+    let mut json = json.into_bytes();
+    pad_to_four(&mut json, b' ');
+
+    let total_length = 12 + 8 + json.len() + 8 + bin.len();
+
+    let mut file = io::BufWriter::new(fs::File::create(path)?);
+    // header : magic "glTF", version 2, 전체 길이
+    file.write_all(&0x4654_6C67u32.to_le_bytes())?;
+    file.write_all(&2u32.to_le_bytes())?;
+    file.write_all(&(total_length as u32).to_le_bytes())?;
+    // JSON chunk
+    file.write_all(&(json.len() as u32).to_le_bytes())?;
+    file.write_all(&0x4E4F_534Au32.to_le_bytes())?;
+    file.write_all(&json)?;
+    // BIN chunk
+    file.write_all(&(bin.len() as u32).to_le_bytes())?;
+    file.write_all(&0x004E_4942u32.to_le_bytes())?;
+    file.write_all(&bin)?;
+    file.flush()
+}
+
+// 확장자 (.obj / .glb) 로 포맷을 고름
+pub fn export_mesh<P: AsRef<Path>>(
+    world: &World,
+    min: &ChunkCoord,
+    max: &ChunkCoord,
+    path: P,
+    atlas_path: &Path,
+) -> io::Result<()> {
+    let path = path.as_ref();
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("obj") => export_obj(world, min, max, path, atlas_path),
+        Some("glb") => export_glb(world, min, max, path, atlas_path),
+        _ => Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "mesh export path must end with .obj or .glb",
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::voxel::generator::GeneratorConfig;
+    use crate::voxel::mesh::VoxelData;
+    use crate::voxel::world::WorldConfig;
+    use std::path::PathBuf;
+
+    fn test_world() -> World {
+        let config = WorldConfig {
+            generator: GeneratorConfig::Checkerboard,
+            ..Default::default()
+        };
+        let mut world = World::from_config(&config).unwrap();
+        world.generate_region(&ChunkCoord::new(0, 0), &ChunkCoord::new(2, 2));
+        world
+    }
+
+    fn output_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("pcg_engine_{}_{}", name, std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn atlas_path() -> PathBuf {
+        Path::new("assets").join(VoxelData::TEXTURE_ATLAS_PATH)
+    }
+
+    fn read_u32(bytes: &[u8], offset: usize) -> usize {
+        u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap()) as usize
+    }
+
+    #[test]
+    fn glb_lengths_are_consistent_and_aligned() {
+        let world = test_world();
+        let path = output_dir("glb").join("terrain.glb");
+        export_glb(
+            &world,
+            &ChunkCoord::new(0, 0),
+            &ChunkCoord::new(2, 2),
+            &path,
+            &atlas_path(),
+        )
+        .unwrap();
+
+        let bytes = fs::read(&path).unwrap();
+        assert_eq!(&bytes[0..4], b"glTF");
+        assert_eq!(read_u32(&bytes, 4), 2);
+        assert_eq!(read_u32(&bytes, 8), bytes.len());
+
+        let json_length = read_u32(&bytes, 12);
+        assert_eq!(&bytes[16..20], b"JSON");
+        assert_eq!(json_length % 4, 0);
+
+        let bin_header = 20 + json_length;
+        let bin_length = read_u32(&bytes, bin_header);
+        assert_eq!(&bytes[bin_header + 4..bin_header + 8], b"BIN\0");
+        assert_eq!(bin_length % 4, 0);
+        assert_eq!(bin_header + 8 + bin_length, bytes.len());
+
+        let json = std::str::from_utf8(&bytes[20..bin_header]).unwrap();
+        assert!(json.contains(&format!("\"byteLength\":{}}}]", bin_length)));
+    }
+
+    #[test]
+    fn obj_faces_reference_existing_vertices() {
+        let world = test_world();
+        let path = output_dir("obj").join("terrain.obj");
+        export_obj(
+            &world,
+            &ChunkCoord::new(0, 0),
+            &ChunkCoord::new(2, 2),
+            &path,
+            &atlas_path(),
+        )
+        .unwrap();
+
+        let text = fs::read_to_string(&path).unwrap();
+        let vertex_count = text.lines().filter(|line| line.starts_with("v ")).count();
+        let uv_count = text.lines().filter(|line| line.starts_with("vt ")).count();
+        assert!(vertex_count > 0);
+        assert_eq!(vertex_count, uv_count);
+
+        let mut face_count = 0;
+        for line in text.lines().filter(|line| line.starts_with("f ")) {
+            face_count += 1;
+            for corner in line.split_whitespace().skip(1) {
+                for index in corner.split('/') {
+                    let index: usize = index.parse().unwrap();
+                    assert!((1..=vertex_count).contains(&index), "{}", line);
+                }
+            }
+        }
+        assert!(face_count > 0);
+        assert!(path.with_extension("mtl").exists());
+    }
+
+    #[test]
+    fn exporting_next_to_the_atlas_keeps_it() {
+        let dir = output_dir("obj_atlas");
+        let atlas = dir.join("Blocks.png");
+        fs::copy(atlas_path(), &atlas).unwrap();
+        let original = fs::read(&atlas).unwrap();
+
+        // 같은 파일을 다른 경로로 가리켜도 복사하지 않음
+        export_obj(
+            &test_world(),
+            &ChunkCoord::new(0, 0),
+            &ChunkCoord::new(1, 1),
+            dir.join("terrain.obj"),
+            &dir.join(".").join("Blocks.png"),
+        )
+        .unwrap();
+        assert_eq!(fs::read(&atlas).unwrap(), original);
+    }
+
+    #[test]
+    fn obj_path_without_file_name_is_an_error() {
+        let world = test_world();
+        let result = export_obj(
+            &world,
+            &ChunkCoord::new(0, 0),
+            &ChunkCoord::new(2, 2),
+            "..",
+            &atlas_path(),
+        );
+        assert_eq!(result.unwrap_err().kind(), io::ErrorKind::InvalidInput);
+    }
+}
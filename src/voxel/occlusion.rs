@@ -0,0 +1,352 @@
+use super::block::Block;
+use super::chunk::{Chunk, ChunkCoord};
+use super::coord::LocalVoxelPos;
+use super::mesh::VoxelData;
+use std::collections::{HashSet, VecDeque};
+
+// 청크 면 인덱스, VoxelData::FACE_CHECKS 와 같은 순서
+// 0 : -Z, 1 : +Z, 2 : +Y, 3 : -Y, 4 : -X, 5 : +X
+pub const FACE_BACK: usize = 0;
+pub const FACE_FRONT: usize = 1;
+pub const FACE_TOP: usize = 2;
+pub const FACE_BOTTOM: usize = 3;
+pub const FACE_LEFT: usize = 4;
+pub const FACE_RIGHT: usize = 5;
+
+const HORIZONTAL_FACES: [usize; 4] = [FACE_BACK, FACE_FRONT, FACE_LEFT, FACE_RIGHT];
+
+fn opposite_face(face: usize) -> usize {
+    face ^ 1
+}
+
+fn face_offset(face: usize) -> (i32, i32) {
+    match face {
+        FACE_BACK => (0, -1),
+        FACE_FRONT => (0, 1),
+        FACE_LEFT => (-1, 0),
+        FACE_RIGHT => (1, 0),
+        _ => (0, 0),
+    }
+}
+
+// 청크 안의 빈 공간을 통해 어느 면에서 어느 면으로 볼 수 있는지 (6 x 6 비트)
+// 메쉬를 만들 때 빈 블록들을 flood fill 해서 계산
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct VisibilityGraph(u64);
+
+impl VisibilityGraph {
+    pub fn compute(chunk: &Chunk) -> Self {
+        let block = Block::new();
+        let width = VoxelData::CHUNK_WIDTH;
+        let height = VoxelData::CHUNK_HEIGHT;
+        let index = |p: LocalVoxelPos| ((p.x * height + p.y) * width + p.z) as usize;
+
+        let mut visited = vec![false; (width * height * width) as usize];
+        let mut graph = VisibilityGraph(0);
+        let mut stack = Vec::new();
+
+        for x in 0..width {
+            for y in 0..height {
+                for z in 0..width {
+                    let start = LocalVoxelPos::new(x, y, z);
+                    if visited[index(start)] || is_solid(&block, chunk, start) {
+                        continue;
+                    }
+
+                    // 연결된 빈 공간 하나가 닿는 면들
+                    let mut faces = 0u8;
+                    visited[index(start)] = true;
+                    stack.push(start);
+
+                    while let Some(pos) = stack.pop() {
+                        faces |= touched_faces(pos);
+
+                        for face in 0..6 {
+                            let next = pos + VoxelData::FACE_CHECKS[face].as_ivec3();
+                            if !next.is_in_chunk()
+                                || visited[index(next)]
+                                || is_solid(&block, chunk, next)
+                            {
+                                continue;
+                            }
+                            visited[index(next)] = true;
+                            stack.push(next);
+                        }
+                    }
+
+                    graph.connect_all(faces);
+                }
+            }
+        }
+
+        graph
+    }
+
+    // 막힌 곳 없이 모든 면이 서로 보이는 그래프
+    pub fn open() -> Self {
+        let mut graph = VisibilityGraph(0);
+        graph.connect_all(0b11_1111);
+        graph
+    }
+
+    pub fn is_connected(&self, from: usize, to: usize) -> bool {
+        self.0 & (1 << (from * 6 + to)) != 0
+    }
+
+    fn connect_all(&mut self, faces: u8) {
+        for from in 0..6 {
+            if faces & (1 << from) == 0 {
+                continue;
+            }
+            for to in 0..6 {
+                if faces & (1 << to) != 0 {
+                    self.0 |= 1 << (from * 6 + to);
+                }
+            }
+        }
+    }
+}
+
+fn is_solid(block: &Block, chunk: &Chunk, pos: LocalVoxelPos) -> bool {
+    match chunk.get_voxel(pos) {
+        Some(block_id) => block.block_types[block_id as usize].is_solid,
+        None => false,
+    }
+}
+
+fn touched_faces(pos: LocalVoxelPos) -> u8 {
+    let mut faces = 0u8;
+    if pos.z == 0 {
+        faces |= 1 << FACE_BACK;
+    }
+    if pos.z == VoxelData::CHUNK_WIDTH - 1 {
+        faces |= 1 << FACE_FRONT;
+    }
+    if pos.y == VoxelData::CHUNK_HEIGHT - 1 {
+        faces |= 1 << FACE_TOP;
+    }
+    if pos.y == 0 {
+        faces |= 1 << FACE_BOTTOM;
+    }
+    if pos.x == 0 {
+        faces |= 1 << FACE_LEFT;
+    }
+    if pos.x == VoxelData::CHUNK_WIDTH - 1 {
+        faces |= 1 << FACE_RIGHT;
+    }
+    faces
+}
+
+// 카메라 청크에서 시작해 빈 공간으로 이어진 청크만 찾는 BFS
+// 청크는 세로로 한 칸뿐이므로 위쪽 (하늘) 은 하나의 공간으로 봄
+// 카메라가 하늘과 이어져 있으면 윗면이 열린 모든 청크를 위에서 들어온 것으로 시작함
+//
+// 한 번 간 방향의 반대 방향으로는 다시 가지 않음 (카메라 쪽으로 되돌아오는 경로 제외)
+// 같은 면으로 들어와도 지금까지 간 방향이 다르면 갈 수 있는 곳이 달라서 따로 방문함
+pub fn find_visible_chunks(
+    camera_chunk: &ChunkCoord,
+    camera_in_sky: bool,
+    candidates: &HashSet<ChunkCoord>,
+    get_graph: impl Fn(&ChunkCoord) -> Option<VisibilityGraph>,
+) -> HashSet<ChunkCoord> {
+    // (청크, 들어온 면, 지금까지 간 방향 비트)
+    let mut queue: VecDeque<(ChunkCoord, Option<usize>, u8)> = VecDeque::new();
+    let mut visited: HashSet<(ChunkCoord, Option<usize>, u8)> = HashSet::new();
+    let mut visible = HashSet::new();
+
+    let mut sky_reachable = camera_in_sky;
+    if let Some(graph) = get_graph(camera_chunk) {
+        visible.insert(camera_chunk.clone());
+        queue.push_back((camera_chunk.clone(), None, 0));
+        visited.insert((camera_chunk.clone(), None, 0));
+
+        sky_reachable |= (0..6).any(|face| graph.is_connected(face, FACE_TOP));
+    }
+
+    if sky_reachable {
+        for coord in candidates {
+            let Some(graph) = get_graph(coord) else {
+                continue;
+            };
+            let start = (coord.clone(), Some(FACE_TOP), 1 << FACE_BOTTOM);
+            if graph.is_connected(FACE_TOP, FACE_TOP) && visited.insert(start.clone()) {
+                visible.insert(coord.clone());
+                queue.push_back(start);
+            }
+        }
+    }
+
+    while let Some((coord, entered, directions)) = queue.pop_front() {
+        let Some(graph) = get_graph(&coord) else {
+            continue;
+        };
+
+        for face in HORIZONTAL_FACES {
+            if directions & (1 << opposite_face(face)) != 0 {
+                continue;
+            }
+            if let Some(entered) = entered {
+                if !graph.is_connected(entered, face) {
+                    continue;
+                }
+            }
+
+            let (dx, dz) = face_offset(face);
+            let next = ChunkCoord::new(coord.x + dx, coord.z + dz);
+            if !candidates.contains(&next) {
+                continue;
+            }
+
+            let step = (
+                next.clone(),
+                Some(opposite_face(face)),
+                directions | (1 << face),
+            );
+            if !visited.insert(step.clone()) {
+                continue;
+            }
+            visible.insert(next);
+            queue.push_back(step);
+        }
+    }
+
+    visible
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::block::EBlockType;
+    use super::*;
+    use std::collections::HashMap;
+
+    // 돌로 가득 찬 청크에서 carve 가 true 인 블록만 공기
+    fn stone_chunk(carve: impl Fn(i32, i32, i32) -> bool) -> Chunk {
+        let mut chunk = Chunk::empty(ChunkCoord::new(0, 0), 0);
+        for x in 0..VoxelData::CHUNK_WIDTH {
+            for y in 0..VoxelData::CHUNK_HEIGHT {
+                for z in 0..VoxelData::CHUNK_WIDTH {
+                    if !carve(x, y, z) {
+                        chunk.set_voxel(LocalVoxelPos::new(x, y, z), EBlockType::Stone as i32);
+                    }
+                }
+            }
+        }
+        chunk
+    }
+
+    fn graph(faces: &[usize]) -> VisibilityGraph {
+        let mut graph = VisibilityGraph::default();
+        graph.connect_all(faces.iter().fold(0, |bits, face| bits | 1 << face));
+        graph
+    }
+
+    fn connected_pairs(graph: VisibilityGraph) -> Vec<(usize, usize)> {
+        (0..6)
+            .flat_map(|from| (0..6).map(move |to| (from, to)))
+            .filter(|&(from, to)| graph.is_connected(from, to))
+            .collect()
+    }
+
+    fn visible(
+        camera: (i32, i32),
+        camera_in_sky: bool,
+        graphs: &HashMap<(i32, i32), VisibilityGraph>,
+    ) -> Vec<(i32, i32)> {
+        let candidates = graphs.keys().map(|&(x, z)| ChunkCoord::new(x, z)).collect();
+        let found = find_visible_chunks(
+            &ChunkCoord::new(camera.0, camera.1),
+            camera_in_sky,
+            &candidates,
+            |coord| graphs.get(&(coord.x, coord.z)).copied(),
+        );
+        let mut found: Vec<_> = found.iter().map(|coord| (coord.x, coord.z)).collect();
+        found.sort();
+        found
+    }
+
+    #[test]
+    fn solid_chunk_connects_no_faces() {
+        let chunk = stone_chunk(|_, _, _| false);
+        assert_eq!(VisibilityGraph::compute(&chunk), VisibilityGraph::default());
+        assert_eq!(
+            VisibilityGraph::compute(&Chunk::empty(ChunkCoord::new(0, 0), 0)),
+            VisibilityGraph::open()
+        );
+    }
+
+    #[test]
+    fn straight_tunnel_connects_only_its_two_faces() {
+        // x 방향으로 뚫린 1 x 1 터널
+        let chunk = stone_chunk(|_, y, z| y == 8 && z == 4);
+        let mut pairs = connected_pairs(VisibilityGraph::compute(&chunk));
+        pairs.sort();
+        assert_eq!(
+            pairs,
+            vec![
+                (FACE_LEFT, FACE_LEFT),
+                (FACE_LEFT, FACE_RIGHT),
+                (FACE_RIGHT, FACE_LEFT),
+                (FACE_RIGHT, FACE_RIGHT),
+            ]
+        );
+
+        // 터널과 이어지지 않은 빈 방은 아무 면에도 닿지 않음
+        let chunk = stone_chunk(|x, y, z| (y == 8 && z == 4) || (x == 5 && y == 3 && z == 7));
+        assert_eq!(connected_pairs(VisibilityGraph::compute(&chunk)).len(), 4);
+    }
+
+    #[test]
+    fn chunks_behind_sealed_stone_are_not_visible() {
+        let tunnel = graph(&[FACE_LEFT, FACE_RIGHT]);
+        let mut graphs = HashMap::new();
+        for x in -2..=3 {
+            for z in -1..=1 {
+                graphs.insert((x, z), tunnel);
+            }
+        }
+        graphs.insert((1, 0), VisibilityGraph::default());
+
+        // 카메라 이웃은 항상 보이고, 막힌 (1, 0) 너머와 옆 줄 터널은 보이지 않음
+        assert_eq!(
+            visible((0, 0), false, &graphs),
+            vec![(-2, 0), (-1, 0), (0, -1), (0, 0), (0, 1), (1, 0)]
+        );
+    }
+
+    #[test]
+    fn camera_in_sky_sees_every_chunk_open_to_the_sky() {
+        let mut graphs = HashMap::new();
+        graphs.insert((0, 0), VisibilityGraph::default());
+        graphs.insert((5, 5), graph(&[FACE_TOP]));
+        graphs.insert((6, 5), graph(&[FACE_LEFT, FACE_RIGHT]));
+        graphs.insert((-5, 2), VisibilityGraph::default());
+
+        // 하늘에서 (5, 5) 로 내려와서 옆으로 (6, 5) 는 이어지지 않음
+        assert_eq!(visible((0, 0), true, &graphs), vec![(0, 0), (5, 5)]);
+        // 카메라가 막힌 청크 안이면 하늘도 보이지 않음
+        assert_eq!(visible((0, 0), false, &graphs), vec![(0, 0)]);
+        // 카메라 청크가 하늘과 이어져 있으면 camera_in_sky 가 아니어도 하늘로 감
+        graphs.insert((0, 0), VisibilityGraph::open());
+        assert_eq!(visible((0, 0), false, &graphs), vec![(0, 0), (5, 5)]);
+    }
+
+    #[test]
+    fn same_entry_face_with_other_directions_is_still_explored() {
+        // 카메라 (1, 2) 에서 -Z, -X 로 (0, 0) 에 먼저 들어오면 +Z 로는 못 감
+        // 하늘에서 (3, 0) 으로 내려와 -X 로만 (0, 0) 에 들어온 경로는 +Z 의 (0, 1) 까지 이어짐
+        let solid = VisibilityGraph::default();
+        let mut graphs = HashMap::new();
+        for x in -1..=3 {
+            for z in -1..=3 {
+                graphs.insert((x, z), solid);
+            }
+        }
+        graphs.insert((1, 1), graph(&[FACE_FRONT, FACE_BACK]));
+        graphs.insert((1, 0), graph(&[FACE_FRONT, FACE_RIGHT, FACE_LEFT]));
+        graphs.insert((0, 0), graph(&[FACE_RIGHT, FACE_FRONT]));
+        graphs.insert((3, 0), graph(&[FACE_TOP, FACE_LEFT]));
+        graphs.insert((2, 0), graph(&[FACE_LEFT, FACE_RIGHT]));
+
+        assert!(visible((1, 2), true, &graphs).contains(&(0, 1)));
+    }
+}
@@ -0,0 +1,434 @@
+use super::chunk_render::{
+    apply_chunk_meshes, fade_in_chunks, update_chunk_asset_stats, BlockMaterials,
+    ChunkAssetStats,
+};
+use super::chunk::ChunkMesher;
+use super::diagnostics::{chunk_diagnostics, VoxelDiagnosticsPlugin};
+use super::floating_origin::{rebase_floating_origin, FloatingOrigin};
+use super::generator::GeneratorConfig;
+use super::mesh::VoxelData;
+use super::world::{
+    self, ChunkActivated, ChunkDeactivated, ChunkEntities, ChunkLoaded, ChunkUnloaded, WorldConfig,
+};
+use crate::day_night::TimeOfDay;
+use bevy::app::AppExit;
+use bevy::prelude::*;
+use bevy::transform::TransformSystem;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+// 복셀 월드를 앱에 붙이는 플러그인
+//
+// App::new()
+//     .add_plugins(DefaultPlugins)
+//     .add_plugins(VoxelWorldPlugin::builder().seed(7).view_distance(8).build()?)
+//
+// 청크 스트리밍 / 이벤트 / 오클루전 컬링은 항상 동작하고, 청크 엔티티 스폰과 메쉬 갱신은
+// 메쉬 / 머티리얼 에셋이 먼저 등록된 경우 (PbrPlugin 등) 에만 등록됨 -> MinimalPlugins 로 헤드리스 테스트 가능
+// 뷰 거리는 FlyCam 카메라 기준
+// 청크 크기는 컴파일 시간 상수 (VoxelData::CHUNK_WIDTH / CHUNK_HEIGHT) 라서 빌더로 바꿀 수 없음
+// 미리 생성해 둔 월드는 builder().world(..) 로 넘김, 시드 / 생성기 / 메쉬 방식이 WorldConfig 와 다르면 build 에서 Err
+// 앱에 이미 World 리소스가 있으면 경고하고 빌더의 월드로 바꿈
+// 청크 생성 / 메쉬 생성 진단 (VoxelDiagnosticsPlugin) 도 같이 추가됨
+pub struct VoxelWorldPlugin {
+    config: WorldConfig,
+    // 빌더로 넘긴 미리 생성한 월드, Plugin::build 에서 앱으로 옮김
+    world: Mutex<Option<world::World>>,
+    view_distance: i32,
+    unload_distance: Option<i32>,
+    texture_atlas_path: String,
+    save_path: Option<PathBuf>,
+}
+
+impl VoxelWorldPlugin {
+    pub fn builder() -> VoxelWorldPluginBuilder {
+        VoxelWorldPluginBuilder::default()
+    }
+}
+
+impl Default for VoxelWorldPlugin {
+    fn default() -> Self {
+        VoxelWorldPluginBuilder::default()
+            .build()
+            .expect("default voxel world config is valid")
+    }
+}
+
+impl Plugin for VoxelWorldPlugin {
+    fn build(&self, app: &mut App) {
+        let voxel_world = self.world.lock().unwrap().take();
+        let mut voxel_world = match voxel_world {
+            Some(voxel_world) => voxel_world,
+            None => world::World::from_config(&self.config)
+                .expect("generator config was validated by the builder"),
+        };
+        voxel_world.set_view_distance(self.view_distance);
+        voxel_world.set_unload_distance(self.unload_distance);
+        if app.world.contains_resource::<world::World>() {
+            warn!("replacing the existing World resource, pass it with VoxelWorldPluginBuilder::world");
+        }
+        app.insert_resource(voxel_world);
+
+        // 월드 설정과 저장된 시간, DayNightPlugin 보다 먼저 추가하면 저장된 시간에서 시작
+        app.insert_resource(self.config.clone());
+        if !app.world.contains_resource::<TimeOfDay>() {
+            app.insert_resource(self.config.time_of_day.clone());
+        }
+        if let Some(path) = &self.save_path {
+            app.insert_resource(WorldSavePath(path.clone()))
+                .add_systems(Last, save_world_config_on_exit);
+        }
+
+        if !app.is_plugin_added::<VoxelDiagnosticsPlugin>() {
+            app.add_plugins(VoxelDiagnosticsPlugin);
+        }
+
+        app.init_resource::<FloatingOrigin>()
+            .init_resource::<ChunkEntities>()
+            .init_resource::<ChunkAssetStats>()
+            .add_event::<ChunkLoaded>()
+            .add_event::<ChunkActivated>()
+            .add_event::<ChunkDeactivated>()
+            .add_event::<ChunkUnloaded>()
+            .add_systems(Update, (world::update, chunk_diagnostics).chain())
+            .add_systems(
+                PostUpdate,
+                (world::update_chunk_in_view_range, apply_chunk_meshes)
+                    .run_if(resource_changed::<world::World>),
+            )
+            .add_systems(
+                PostUpdate,
+                rebase_floating_origin.before(TransformSystem::TransformPropagate),
+            );
+
+        if has_render_assets(app) {
+            app.add_systems(Startup, world::setup)
+                .add_systems(
+                    Update,
+                    (world::spawn_loaded_chunks, world::despawn_unloaded_chunks)
+                        .after(world::update),
+                )
+                .add_systems(Update, fade_in_chunks)
+                .add_systems(Last, update_chunk_asset_stats);
+        }
+    }
+
+    // 에셋 서버는 모든 플러그인이 추가된 뒤에 사용
+    fn finish(&self, app: &mut App) {
+        if has_render_assets(app) {
+            let block_materials = BlockMaterials::load(&mut app.world, &self.texture_atlas_path);
+            app.insert_resource(block_materials);
+        }
+    }
+}
+
+// 청크 엔티티와 메쉬 에셋을 만들 수 있는지, PbrPlugin 이 없어도 에셋만 등록하면 됨 (테스트)
+fn has_render_assets(app: &App) -> bool {
+    app.world.contains_resource::<Assets<Mesh>>()
+        && app.world.contains_resource::<Assets<StandardMaterial>>()
+}
+
+#[derive(Resource)]
+struct WorldSavePath(PathBuf);
+
+fn save_world_config_on_exit(
+    mut exit_events: EventReader<AppExit>,
+    save_path: Res<WorldSavePath>,
+    config: Res<WorldConfig>,
+    time_of_day: Option<Res<TimeOfDay>>,
+) {
+    if exit_events.is_empty() {
+        return;
+    }
+    exit_events.clear();
+
+    let mut config = config.clone();
+    if let Some(time_of_day) = time_of_day {
+        config.time_of_day = time_of_day.clone();
+    }
+    match config.save(&save_path.0) {
+        Ok(()) => info!("saved {}", save_path.0.display()),
+        Err(e) => error!("{}: {}", save_path.0.display(), e),
+    }
+}
+
+pub struct VoxelWorldPluginBuilder {
+    config: WorldConfig,
+    world: Option<world::World>,
+    view_distance: i32,
+    unload_distance: Option<i32>,
+    texture_atlas_path: String,
+    save_path: Option<PathBuf>,
+}
+
+impl Default for VoxelWorldPluginBuilder {
+    fn default() -> Self {
+        VoxelWorldPluginBuilder {
+            config: WorldConfig::default(),
+            world: None,
+            view_distance: VoxelData::VIEW_DISTANCE_IN_CHUNKS,
+            unload_distance: None,
+            texture_atlas_path: VoxelData::TEXTURE_ATLAS_PATH.to_string(),
+            save_path: None,
+        }
+    }
+}
+
+impl VoxelWorldPluginBuilder {
+    pub fn seed(mut self, seed: u32) -> Self {
+        self.config.seed = seed;
+        self
+    }
+
+    pub fn generator(mut self, generator: GeneratorConfig) -> Self {
+        self.config.generator = generator;
+        self
+    }
+
+    // 청크 메쉬 방식 (블록 / SurfaceNets)
+    pub fn mesher(mut self, mesher: ChunkMesher) -> Self {
+        self.config.mesher = mesher;
+        self
+    }
+
+    // 시드와 생성기를 한 번에 (WorldConfig::load 로 읽은 설정 등)
+    pub fn world_config(mut self, config: WorldConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    // 카메라 주변으로 활성화할 청크 범위 (청크 단위)
+    pub fn view_distance(mut self, view_distance: i32) -> Self {
+        self.view_distance = view_distance;
+        self
+    }
+
+    // 카메라에서 이보다 먼 청크는 월드에서 제거 (기본값은 제거하지 않음)
+    pub fn unload_distance(mut self, unload_distance: i32) -> Self {
+        self.unload_distance = Some(unload_distance);
+        self
+    }
+
+    // 미리 생성해 둔 월드 (WorldConfig 로 World::from_config 한 것), 뷰 거리 / 제거 거리는 빌더 값으로 바뀜
+    pub fn world(mut self, world: world::World) -> Self {
+        self.world = Some(world);
+        self
+    }
+
+    // assets 폴더 기준 블록 텍스처 아틀라스 경로
+    pub fn texture_atlas(mut self, path: impl Into<String>) -> Self {
+        self.texture_atlas_path = path.into();
+        self
+    }
+
+    // 앱이 끝날 때 WorldConfig 를 (현재 TimeOfDay 와 함께) 저장할 파일
+    pub fn save_to(mut self, path: impl Into<PathBuf>) -> Self {
+        self.save_path = Some(path.into());
+        self
+    }
+
+    // 생성기 설정 (superflat 레이어 등) 이나 뷰 거리가 잘못됐거나 미리 생성한 월드가 WorldConfig 와 다르면 Err
+    pub fn build(self) -> Result<VoxelWorldPlugin, String> {
+        self.config.generator.validate()?;
+        if self.view_distance < 1 {
+            return Err(format!("invalid view distance: {}", self.view_distance));
+        }
+        // 저장되는 WorldConfig 와 실제 월드가 어긋나지 않도록
+        if let Some(voxel_world) = &self.world {
+            if voxel_world.seed() != self.config.seed || voxel_world.mesher() != self.config.mesher
+            {
+                return Err(format!(
+                    "world (seed {}, {:?}) does not match the WorldConfig (seed {}, {:?})",
+                    voxel_world.seed(),
+                    voxel_world.mesher(),
+                    self.config.seed,
+                    self.config.mesher
+                ));
+            }
+            if voxel_world.generator_config() != Some(&self.config.generator) {
+                return Err(format!(
+                    "world generator {:?} does not match the WorldConfig generator {:?}",
+                    voxel_world.generator_config(),
+                    self.config.generator
+                ));
+            }
+        }
+
+        Ok(VoxelWorldPlugin {
+            config: self.config,
+            world: Mutex::new(self.world),
+            view_distance: self.view_distance,
+            unload_distance: self.unload_distance,
+            texture_atlas_path: self.texture_atlas_path,
+            save_path: self.save_path,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::chunk_render::FADE_STEPS;
+    use super::super::coord::ChunkCoord;
+    use super::*;
+    use bevy_flycam::prelude::FlyCam;
+
+    fn headless_app(plugin: VoxelWorldPlugin) -> App {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins).add_plugins(plugin);
+        app
+    }
+
+    #[test]
+    fn runs_headless_with_minimal_plugins() {
+        let plugin = VoxelWorldPlugin::builder()
+            .seed(7)
+            .generator(GeneratorConfig::Checkerboard)
+            .view_distance(2)
+            .build()
+            .unwrap();
+        let mut app = headless_app(plugin);
+        app.world
+            .spawn((FlyCam, Transform::from_xyz(5.0, 40.0, 5.0)));
+        app.update();
+
+        // 카메라 청크 주변 (2 * 2) x (2 * 2) 청크
+        let loaded = app.world.resource::<Events<ChunkLoaded>>();
+        assert_eq!(loaded.len(), 16);
+
+        let voxel_world = app.world.resource::<world::World>();
+        assert_eq!(voxel_world.seed(), 7);
+        assert_eq!(voxel_world.view_distance(), 2);
+        assert!(voxel_world.get_chunk(&ChunkCoord::new(0, 0)).is_some());
+        assert_eq!(app.world.resource::<WorldConfig>().seed, 7);
+    }
+
+    #[test]
+    fn asset_counts_stay_constant_while_chunks_stream() {
+        let plugin = VoxelWorldPlugin::builder()
+            .generator(GeneratorConfig::Checkerboard)
+            .view_distance(2)
+            .unload_distance(3)
+            .build()
+            .unwrap();
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, AssetPlugin::default()))
+            .init_asset::<Image>()
+            .init_asset::<Mesh>()
+            .init_asset::<StandardMaterial>()
+            .add_plugins(plugin);
+        app.finish();
+        let camera = app
+            .world
+            .spawn((FlyCam, Transform::from_xyz(5.0, 40.0, 5.0)))
+            .id();
+
+        let w = VoxelData::CHUNK_WIDTH as f32;
+        let stream_to = |app: &mut App, chunk_x: i32| {
+            let mut transform = app.world.get_mut::<Transform>(camera).unwrap();
+            transform.translation.x = chunk_x as f32 * w + 5.0;
+            // 드롭된 핸들은 다음 프레임에 에셋에서 빠짐
+            app.update();
+            app.update();
+            app.world.resource::<ChunkAssetStats>().clone()
+        };
+
+        // 제거 거리까지 청크가 쌓이도록 양쪽 방향으로 한 번씩 지나감
+        let mut max_meshes = 0;
+        for chunk_x in (0..8).chain((0..8).rev()) {
+            let stats = stream_to(&mut app, chunk_x);
+            assert_eq!(stats.materials, 1 + FADE_STEPS);
+            max_meshes = max_meshes.max(stats.meshes);
+        }
+
+        for chunk_x in (0..24).chain((-24..24).rev()) {
+            let stats = stream_to(&mut app, chunk_x);
+            assert_eq!(stats.materials, 1 + FADE_STEPS, "chunk x {}", chunk_x);
+            assert!(
+                stats.meshes <= max_meshes,
+                "chunk x {}: {:?}",
+                chunk_x,
+                stats
+            );
+            assert_eq!(stats.meshes, stats.chunk_entities, "chunk x {}", chunk_x);
+        }
+    }
+
+    #[test]
+    fn pregenerated_world_takes_the_builder_distances() {
+        let config = WorldConfig {
+            seed: 3,
+            generator: GeneratorConfig::Void,
+            ..Default::default()
+        };
+        let mut pregenerated = world::World::from_config(&config).unwrap();
+        pregenerated.generate_world(2);
+        let plugin = VoxelWorldPlugin::builder()
+            .world_config(config.clone())
+            .world(pregenerated)
+            .view_distance(3)
+            .unload_distance(6)
+            .build()
+            .unwrap();
+
+        let app = headless_app(plugin);
+        let voxel_world = app.world.resource::<world::World>();
+        assert_eq!(voxel_world.chunk_count(), 4);
+        assert_eq!(voxel_world.view_distance(), 3);
+        assert_eq!(voxel_world.unload_distance(), Some(6));
+    }
+
+    #[test]
+    fn pregenerated_world_must_match_the_config() {
+        let config = WorldConfig {
+            seed: 3,
+            generator: GeneratorConfig::Void,
+            ..Default::default()
+        };
+        let pregenerated = || world::World::from_config(&config).unwrap();
+        let builder = || VoxelWorldPlugin::builder().world_config(config.clone());
+
+        assert!(builder().world(pregenerated()).build().is_ok());
+        assert!(builder().seed(4).world(pregenerated()).build().is_err());
+        assert!(builder()
+            .mesher(ChunkMesher::SurfaceNets)
+            .world(pregenerated())
+            .build()
+            .is_err());
+        assert!(builder()
+            .generator(GeneratorConfig::Checkerboard)
+            .world(pregenerated())
+            .build()
+            .is_err());
+        // 생성기 설정을 알 수 없는 월드
+        assert!(builder().world(world::World::with_seed(3)).build().is_err());
+    }
+
+    #[test]
+    fn existing_world_resource_is_replaced() {
+        let plugin = VoxelWorldPlugin::builder()
+            .seed(4)
+            .generator(GeneratorConfig::Void)
+            .build()
+            .unwrap();
+
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .insert_resource(world::World::with_seed(3))
+            .add_plugins(plugin);
+
+        let voxel_world = app.world.resource::<world::World>();
+        assert_eq!(voxel_world.seed(), 4);
+        assert_eq!(voxel_world.generator_config(), Some(&GeneratorConfig::Void));
+    }
+
+    #[test]
+    fn build_rejects_invalid_settings() {
+        let builder = VoxelWorldPlugin::builder;
+        assert!(builder().build().is_ok());
+        assert!(builder()
+            .generator(GeneratorConfig::Superflat("3*nothing".to_string()))
+            .build()
+            .is_err());
+        assert!(builder().view_distance(0).build().is_err());
+    }
+}
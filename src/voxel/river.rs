@@ -0,0 +1,502 @@
+use super::mesh::VoxelData;
+use crate::noise::hash::{hash, unit};
+use bevy::math::{IVec2, Vec2};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::f32::consts::TAU;
+use std::sync::{Arc, Mutex};
+
+// 강 생성
+//
+// source_spacing 격자 칸마다 시드로 정한 위치에서 (높은 곳이면) 강이 시작해서, 지형을 따라
+// 내려가다가 sea_level 에 닿으면 끝남, max_steps 안에 바다에 닿지 못하면 강을 만들지 않음
+// 강의 경로는 (침식 전) 지표 높이와 월드 좌표만으로 정해지므로 어느 청크 / 영역에서 계산해도 같고,
+// 영역마다 그 영역에 닿는 구간만 모아서 캐시함
+// 물 블록이 없어서 강바닥과 강가는 모래로만 표시함
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RiverSettings {
+    // 강이 시작할 수 있는 격자 한 칸 (블록)
+    pub source_spacing: i32,
+    // 격자 한 칸에서 강이 시작할 확률
+    pub source_chance: f32,
+    // 시작점이 sea_level 보다 이만큼 높아야 함
+    pub source_min_height: f32,
+    // 경로 한 걸음 (블록)
+    pub step: f32,
+    pub max_steps: u32,
+    // 클수록 방향을 덜 바꿈 (블록 단위 높이 차이로 환산)
+    pub inertia: f32,
+    // 지금까지의 강바닥보다 이만큼 높은 곳을 만나면 (분지에 갇힌 강) 버림
+    pub max_climb: f32,
+    // 시작점에서의 강 폭 절반, 한 걸음마다 width_growth 씩 넓어지고 max_width 에서 멈춤
+    pub width: f32,
+    pub width_growth: f32,
+    pub max_width: f32,
+    // 강 가운데의 깊이 (강바닥 높이 기준)
+    pub depth: f32,
+    // 강 양쪽으로 모래가 깔리는 폭
+    pub bank_width: f32,
+    // 강가에서 멀어질 때 한 블록마다 올라가는 높이 (V 자 골짜기)
+    pub bank_slope: f32,
+    // 골짜기가 강가에서 이어지는 최대 거리
+    pub valley_width: f32,
+    // 구간을 모아 두는 영역 한 변 (블록)
+    pub region_size: i32,
+    pub max_cached_regions: usize,
+}
+
+impl Default for RiverSettings {
+    fn default() -> Self {
+        RiverSettings {
+            source_spacing: 64,
+            source_chance: 0.6,
+            source_min_height: 2.5,
+            step: 4.0,
+            max_steps: 200,
+            inertia: 0.5,
+            max_climb: 4.0,
+            width: 1.5,
+            width_growth: 0.03,
+            max_width: 5.0,
+            depth: 1.5,
+            bank_width: 2.0,
+            bank_slope: 0.5,
+            valley_width: 12.0,
+            region_size: VoxelData::CHUNK_WIDTH * 6,
+            max_cached_regions: 64,
+        }
+    }
+}
+
+impl RiverSettings {
+    // 강 가운데에서 지형이 바뀔 수 있는 최대 거리
+    fn reach(&self) -> f32 {
+        self.max_width.max(self.width) + self.valley_width.max(self.bank_width)
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct RiverPoint {
+    pub pos: Vec2,
+    // 강바닥 높이, 하류로 갈수록 낮아지기만 함
+    pub level: f32,
+    pub width: f32,
+}
+
+pub struct RiverPath {
+    pub points: Vec<RiverPoint>,
+    pub min: Vec2,
+    pub max: Vec2,
+}
+
+// 경로 두 점 사이 구간, min / max 는 reach 까지 포함한 범위
+#[derive(Clone, Copy)]
+struct Segment {
+    a: RiverPoint,
+    b: RiverPoint,
+    min: Vec2,
+    max: Vec2,
+}
+
+impl Segment {
+    fn new(a: RiverPoint, b: RiverPoint, reach: f32) -> Self {
+        Segment {
+            a,
+            b,
+            min: a.pos.min(b.pos) - Vec2::splat(reach),
+            max: a.pos.max(b.pos) + Vec2::splat(reach),
+        }
+    }
+
+    fn contains(&self, p: Vec2) -> bool {
+        p.x >= self.min.x && p.y >= self.min.y && p.x <= self.max.x && p.y <= self.max.y
+    }
+}
+
+// 한 컬럼에 강이 준 영향
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RiverColumn {
+    pub height: f32,
+    // 강바닥이나 강가 (모래)
+    pub bank: bool,
+}
+
+pub struct RiverMap {
+    pub settings: RiverSettings,
+    // 침식 물방울과 다른 난수가 나오도록 월드 시드에 SEED_OFFSET 을 더한 값
+    seed: u32,
+    sea_level: f32,
+    // 캐시된 영역에 닿을 수 있는 source 칸의 경로만 남김 (영역을 버릴 때 같이 정리)
+    paths: Mutex<HashMap<IVec2, Option<Arc<RiverPath>>>>,
+    regions: Mutex<RegionCache>,
+}
+
+#[derive(Default)]
+struct RegionCache {
+    segments: HashMap<IVec2, Arc<Vec<Segment>>>,
+    order: VecDeque<IVec2>,
+}
+
+impl RiverMap {
+    const SEED_OFFSET: u32 = 0x68e3_1da4;
+
+    pub fn new(settings: &RiverSettings, seed: u32, sea_level: f32) -> Self {
+        RiverMap {
+            settings: settings.clone(),
+            seed: seed.wrapping_add(Self::SEED_OFFSET),
+            sea_level,
+            paths: Mutex::new(HashMap::new()),
+            regions: Mutex::new(RegionCache::default()),
+        }
+    }
+
+    fn region_size(&self) -> i32 {
+        self.settings.region_size.max(1)
+    }
+
+    // source 격자 칸 cell 에서 시작하는 강, 시작하지 않으면 None
+    // height 는 경로를 찾을 때 쓰는 높이 (작은 굴곡이 없는 쪽이 좋음)
+    fn path(&self, cell: IVec2, height: &dyn Fn(f32, f32) -> f32) -> Option<Arc<RiverPath>> {
+        if let Some(path) = self.paths.lock().unwrap().get(&cell) {
+            return path.clone();
+        }
+
+        let path = self.trace(cell, height).map(Arc::new);
+        self.paths.lock().unwrap().insert(cell, path.clone());
+        path
+    }
+
+    fn trace(&self, cell: IVec2, height: &dyn Fn(f32, f32) -> f32) -> Option<RiverPath> {
+        let settings = &self.settings;
+        if unit(hash(self.seed, cell.x, cell.y, 0)) >= settings.source_chance {
+            return None;
+        }
+
+        let spacing = settings.source_spacing.max(1) as f32;
+        let start = (cell.as_vec2()
+            + Vec2::new(
+                unit(hash(self.seed, cell.x, cell.y, 1)),
+                unit(hash(self.seed, cell.x, cell.y, 2)),
+            ))
+            * spacing;
+        let mut level = height(start.x, start.y);
+        if level < self.sea_level + settings.source_min_height {
+            return None;
+        }
+
+        let mut points = vec![RiverPoint {
+            pos: start,
+            level,
+            width: settings.width,
+        }];
+        let mut pos = start;
+        let mut dir = Vec2::ZERO;
+
+        for i in 0..settings.max_steps {
+            if level <= self.sea_level {
+                break;
+            }
+
+            // 8 방향 중 가장 낮은 곳으로, 걸음마다 방향을 조금씩 돌려서 격자 모양이 나지 않도록 함
+            let rotation = unit(hash(self.seed, cell.x, cell.y, 3 + i)) * TAU / 8.0;
+            let mut best: Option<(f32, Vec2, f32)> = None;
+            for k in 0..8 {
+                let candidate = Vec2::from_angle(rotation + k as f32 * TAU / 8.0);
+                // 되돌아가지 않음
+                if candidate.dot(dir) < 0.0 {
+                    continue;
+                }
+                let next = pos + candidate * settings.step;
+                let next_height = height(next.x, next.y);
+                let cost = next_height - candidate.dot(dir) * settings.inertia;
+                if best.is_none_or(|(best_cost, _, _)| cost < best_cost) {
+                    best = Some((cost, candidate, next_height));
+                }
+            }
+
+            let Some((_, next_dir, next_height)) = best else {
+                break;
+            };
+            if next_height > level + settings.max_climb {
+                break;
+            }
+
+            dir = next_dir;
+            pos += dir * settings.step;
+            level = level.min(next_height);
+            points.push(RiverPoint {
+                pos,
+                level,
+                width: (settings.width + (i + 1) as f32 * settings.width_growth)
+                    .min(settings.max_width),
+            });
+        }
+
+        // 바다까지 가지 못한 강 (분지에 갇혔거나 너무 긴 강) 은 만들지 않음
+        if points.len() < 2 || level > self.sea_level {
+            return None;
+        }
+
+        let (min, max) = points.iter().fold(
+            (Vec2::splat(f32::MAX), Vec2::splat(f32::MIN)),
+            |(min, max), point| (min.min(point.pos), max.max(point.pos)),
+        );
+        Some(RiverPath { points, min, max })
+    }
+
+    // region 에 닿을 수 있는 강이 시작하는 source 칸 범위 (양 끝 포함)
+    fn source_cells(&self, region: IVec2) -> (IVec2, IVec2) {
+        let settings = &self.settings;
+        let size = self.region_size() as f32;
+        let rect_min = region.as_vec2() * size;
+        let rect_max = (region + IVec2::ONE).as_vec2() * size;
+
+        // 강은 시작점에서 max_steps * step 보다 멀리 가지 않음
+        let spacing = settings.source_spacing.max(1) as f32;
+        let length = settings.max_steps as f32 * settings.step + settings.reach();
+        let cell_min = ((rect_min - Vec2::splat(length)) / spacing)
+            .floor()
+            .as_ivec2();
+        let cell_max = ((rect_max + Vec2::splat(length)) / spacing)
+            .floor()
+            .as_ivec2();
+        (cell_min, cell_max)
+    }
+
+    // 버린 영역의 source 칸 중 남은 영역 (kept) 어디에도 닿지 않는 칸의 경로를 지움
+    fn drop_paths(&self, evicted: IVec2, kept: impl Iterator<Item = IVec2>) {
+        let kept: Vec<_> = kept.map(|region| self.source_cells(region)).collect();
+        let (cell_min, cell_max) = self.source_cells(evicted);
+        let mut paths = self.paths.lock().unwrap();
+        for cell_z in cell_min.y..=cell_max.y {
+            for cell_x in cell_min.x..=cell_max.x {
+                let cell = IVec2::new(cell_x, cell_z);
+                if !kept
+                    .iter()
+                    .any(|(min, max)| cell.cmpge(*min).all() && cell.cmple(*max).all())
+                {
+                    paths.remove(&cell);
+                }
+            }
+        }
+    }
+
+    // region 에 닿는 모든 강의 구간
+    fn segments(&self, region: IVec2, height: &dyn Fn(f32, f32) -> f32) -> Arc<Vec<Segment>> {
+        if let Some(segments) = self.regions.lock().unwrap().segments.get(&region) {
+            return segments.clone();
+        }
+
+        let settings = &self.settings;
+        let reach = settings.reach();
+        let size = self.region_size() as f32;
+        let rect_min = region.as_vec2() * size;
+        let rect_max = (region + IVec2::ONE).as_vec2() * size;
+        let (cell_min, cell_max) = self.source_cells(region);
+
+        let mut segments = Vec::new();
+        for cell_z in cell_min.y..=cell_max.y {
+            for cell_x in cell_min.x..=cell_max.x {
+                let Some(path) = self.path(IVec2::new(cell_x, cell_z), height) else {
+                    continue;
+                };
+                if path.max.x + reach < rect_min.x
+                    || path.max.y + reach < rect_min.y
+                    || path.min.x - reach > rect_max.x
+                    || path.min.y - reach > rect_max.y
+                {
+                    continue;
+                }
+
+                for pair in path.points.windows(2) {
+                    let segment = Segment::new(pair[0], pair[1], reach);
+                    if segment.max.x >= rect_min.x
+                        && segment.max.y >= rect_min.y
+                        && segment.min.x <= rect_max.x
+                        && segment.min.y <= rect_max.y
+                    {
+                        segments.push(segment);
+                    }
+                }
+            }
+        }
+
+        let segments = Arc::new(segments);
+        let mut cache = self.regions.lock().unwrap();
+        while cache.order.len() >= settings.max_cached_regions.max(1) {
+            if let Some(oldest) = cache.order.pop_front() {
+                cache.segments.remove(&oldest);
+                self.drop_paths(oldest, cache.order.iter().copied().chain([region]));
+            }
+        }
+        cache.order.push_back(region);
+        cache.segments.insert(region, segments.clone());
+        segments
+    }
+
+    // (x, z) 의 지표 높이 terrain_height 를 강으로 깎은 결과, height 는 path 와 같음
+    pub fn carve(
+        &self,
+        x: i32,
+        z: i32,
+        terrain_height: f32,
+        height: &dyn Fn(f32, f32) -> f32,
+    ) -> RiverColumn {
+        let size = self.region_size();
+        let region = IVec2::new(x.div_euclid(size), z.div_euclid(size));
+        let segments = self.segments(region, height);
+
+        let settings = &self.settings;
+        let p = Vec2::new(x as f32, z as f32);
+        let mut column = RiverColumn {
+            height: terrain_height,
+            bank: false,
+        };
+
+        for segment in segments.iter() {
+            if !segment.contains(p) {
+                continue;
+            }
+
+            let ab = segment.b.pos - segment.a.pos;
+            let t = ((p - segment.a.pos).dot(ab) / ab.length_squared().max(f32::EPSILON))
+                .clamp(0.0, 1.0);
+            let distance = p.distance(segment.a.pos + ab * t);
+            let level = segment.a.level + (segment.b.level - segment.a.level) * t;
+            let width = segment.a.width + (segment.b.width - segment.a.width) * t;
+            if distance >= width + settings.valley_width.max(settings.bank_width) {
+                continue;
+            }
+
+            // 강 안은 가운데가 가장 깊은 포물선, 밖은 강가에서 bank_slope 로 올라가는 골짜기
+            let carved = if distance < width {
+                let r = distance / width;
+                level - settings.depth * (1.0 - r * r)
+            } else {
+                level + (distance - width) * settings.bank_slope
+            };
+            column.height = column.height.min(carved);
+            if distance < width + settings.bank_width {
+                column.bank = true;
+            }
+        }
+
+        column
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SEA_LEVEL: f32 = 10.0;
+
+    // -x 쪽으로 내려가서 바다에 닿는 지형, z 방향으로 골짜기가 있음
+    fn slope(x: f32, z: f32) -> f32 {
+        SEA_LEVEL + 0.05 * x + 4.0 * (z * 0.03).sin() + 0.5 * (x * 0.05).sin()
+    }
+
+    fn carve(map: &RiverMap, x: i32, z: i32) -> RiverColumn {
+        map.carve(x, z, slope(x as f32, z as f32), &slope)
+    }
+
+    #[test]
+    fn carving_matches_on_both_sides_of_region_borders() {
+        let settings = RiverSettings::default();
+        let size = settings.region_size;
+        let map = RiverMap::new(&settings, 21, SEA_LEVEL);
+        // 영역 하나가 측정 범위를 모두 덮어서 영역 경계가 없는 기준
+        let reference = RiverMap::new(
+            &RiverSettings {
+                region_size: size * 10,
+                ..settings.clone()
+            },
+            21,
+            SEA_LEVEL,
+        );
+
+        let mut carved = 0;
+        let mut banks = 0;
+        for along in 0..size * 10 {
+            for border in (1..10).map(|i| i * size) {
+                // 경계 양쪽 두 칸씩, x 경계와 z 경계 모두
+                for offset in -2..2 {
+                    for (x, z) in [(border + offset, along), (along, border + offset)] {
+                        let column = carve(&map, x, z);
+                        let expected = carve(&reference, x, z);
+                        assert_eq!(column.height, expected.height, "({}, {})", x, z);
+                        assert_eq!(column.bank, expected.bank, "({}, {})", x, z);
+
+                        if column.height < slope(x as f32, z as f32) {
+                            carved += 1;
+                        }
+                        if column.bank {
+                            banks += 1;
+                        }
+                    }
+                }
+            }
+        }
+
+        // 강이 실제로 경계를 지나야 비교에 의미가 있음
+        assert!(carved > 0 && banks > 0, "carved {} banks {}", carved, banks);
+    }
+
+    #[test]
+    fn same_seed_carves_the_same_rivers() {
+        let settings = RiverSettings::default();
+        let columns = |seed: u32| {
+            let map = RiverMap::new(&settings, seed, SEA_LEVEL);
+            let mut columns = Vec::new();
+            for z in (0..600).step_by(3) {
+                for x in (0..600).step_by(3) {
+                    let column = carve(&map, x, z);
+                    columns.push((column.height, column.bank));
+                }
+            }
+            columns
+        };
+
+        let first = columns(4);
+        assert!(first.iter().any(|(_, bank)| *bank));
+        assert_eq!(first, columns(4));
+        assert_ne!(first, columns(5));
+    }
+
+    #[test]
+    fn cached_paths_are_bounded_by_the_cached_regions() {
+        let settings = RiverSettings {
+            max_cached_regions: 4,
+            ..Default::default()
+        };
+        let size = settings.region_size;
+        let map = RiverMap::new(&settings, 21, SEA_LEVEL);
+
+        // 영역을 지나가는 동안 한 번이라도 경로를 찾은 칸
+        let mut traced = std::collections::HashSet::new();
+        for region_x in 0..30 {
+            carve(&map, region_x * size, 0);
+            let paths = map.paths.lock().unwrap();
+            traced.extend(paths.keys().copied());
+
+            // 남은 경로는 모두 캐시된 영역 중 하나에 닿을 수 있는 칸
+            let regions = map.regions.lock().unwrap();
+            assert!(regions.order.len() <= settings.max_cached_regions);
+            for cell in paths.keys() {
+                assert!(regions.order.iter().any(|region| {
+                    let (min, max) = map.source_cells(*region);
+                    cell.cmpge(min).all() && cell.cmple(max).all()
+                }));
+            }
+        }
+        assert!(map.paths.lock().unwrap().len() < traced.len());
+
+        // 지운 경로는 다시 찾아도 같은 결과
+        let reference = RiverMap::new(&settings, 21, SEA_LEVEL);
+        for x in (0..size * 2).step_by(7) {
+            assert_eq!(carve(&map, x, 5), carve(&reference, x, 5));
+        }
+    }
+}
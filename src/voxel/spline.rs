@@ -0,0 +1,267 @@
+use serde::{Deserialize, Serialize};
+
+// 지형 파라미터 (continentalness, erosion, peaks & valleys) 를 높이 같은 값으로 바꾸는 스플라인
+// 제어점의 값이 다른 파라미터에 대한 스플라인일 수 있어서 (중첩) 여러 파라미터를 함께 반영함
+//
+// 예) 내륙에서는 침식이 낮을수록 높고, 그 중에서도 봉우리 쪽이 더 높음
+// (
+//     coordinate: Continentalness,
+//     points: [
+//         (location: -0.5, value: Constant(4.0)),
+//         (location: 0.3, value: Spline((
+//             coordinate: Erosion,
+//             points: [
+//                 (location: -0.5, value: Spline((
+//                     coordinate: PeaksValleys,
+//                     points: [(location: -1.0, value: Constant(11.0)), (location: 1.0, value: Constant(18.0))],
+//                 ))),
+//                 (location: 0.5, value: Constant(10.0)),
+//             ],
+//         ))),
+//     ],
+// )
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TerrainParam {
+    // 낮으면 바다, 높으면 내륙
+    Continentalness,
+    // 낮으면 험한 산지, 높으면 평평하게 깎인 지형
+    Erosion,
+    // -1 = 골짜기, 1 = 봉우리 (weirdness 노이즈를 접어서 만듦)
+    PeaksValleys,
+}
+
+// (x, z) 한 컬럼의 지형 파라미터
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct TerrainParams {
+    pub continentalness: f64,
+    pub erosion: f64,
+    pub peaks_valleys: f64,
+}
+
+impl TerrainParams {
+    pub fn get(&self, param: TerrainParam) -> f64 {
+        match param {
+            TerrainParam::Continentalness => self.continentalness,
+            TerrainParam::Erosion => self.erosion,
+            TerrainParam::PeaksValleys => self.peaks_valleys,
+        }
+    }
+}
+
+// weirdness (-1 ~ 1) 를 접어서 0 근처와 ±1 근처는 골짜기, ±2/3 근처는 봉우리가 되도록 함
+pub fn peaks_valleys(weirdness: f64) -> f64 {
+    1.0 - (3.0 * weirdness.abs() - 2.0).abs()
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum SplineValue {
+    Constant(f64),
+    Spline(Box<Spline>),
+}
+
+impl SplineValue {
+    pub fn evaluate(&self, params: &TerrainParams) -> f64 {
+        match self {
+            SplineValue::Constant(value) => *value,
+            SplineValue::Spline(spline) => spline.evaluate(params),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SplinePoint {
+    pub location: f64,
+    pub value: SplineValue,
+    // 이 점에서의 기울기, 0 이면 점에서 평평하게 이어짐
+    #[serde(default)]
+    pub derivative: f64,
+}
+
+// 제어점 사이를 3차 에르미트 보간, 범위 밖은 양 끝 점에서 기울기를 따라 직선으로 이어짐
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Spline {
+    pub coordinate: TerrainParam,
+    pub points: Vec<SplinePoint>,
+}
+
+impl Spline {
+    pub fn evaluate(&self, params: &TerrainParams) -> f64 {
+        let (Some(first), Some(last)) = (self.points.first(), self.points.last()) else {
+            return 0.0;
+        };
+
+        let x = params.get(self.coordinate);
+        // NaN 은 어느 구간에도 들지 않아서 첫 점, 무한대는 기울기 직선 대신 끝 점의 값
+        if x.is_nan() || x == f64::NEG_INFINITY {
+            return first.value.evaluate(params);
+        }
+        if x == f64::INFINITY {
+            return last.value.evaluate(params);
+        }
+        if x <= first.location {
+            return first.value.evaluate(params) + first.derivative * (x - first.location);
+        }
+        if x >= last.location {
+            return last.value.evaluate(params) + last.derivative * (x - last.location);
+        }
+
+        // 제어점은 location 순서로 있다고 가정 (SplinePreset 을 만들 때 정렬함)
+        let index = self.points.partition_point(|point| point.location <= x);
+        let (p0, p1) = (&self.points[index - 1], &self.points[index]);
+        let width = p1.location - p0.location;
+        if width <= 0.0 {
+            return p1.value.evaluate(params);
+        }
+
+        let t = (x - p0.location) / width;
+        let (v0, v1) = (p0.value.evaluate(params), p1.value.evaluate(params));
+        let (m0, m1) = (p0.derivative * width, p1.derivative * width);
+
+        let t2 = t * t;
+        let t3 = t2 * t;
+        (2.0 * t3 - 3.0 * t2 + 1.0) * v0
+            + (t3 - 2.0 * t2 + t) * m0
+            + (-2.0 * t3 + 3.0 * t2) * v1
+            + (t3 - t2) * m1
+    }
+
+    // 중첩된 스플라인까지 제어점을 location 순서로 정렬
+    pub fn sort(&mut self) {
+        self.points
+            .sort_by(|a, b| a.location.total_cmp(&b.location));
+        for point in &mut self.points {
+            if let SplineValue::Spline(spline) = &mut point.value {
+                spline.sort();
+            }
+        }
+    }
+
+    // 상수 제어점만 있는 스플라인
+    pub fn constant_points(coordinate: TerrainParam, points: &[(f64, f64)]) -> Spline {
+        Spline {
+            coordinate,
+            points: points
+                .iter()
+                .map(|&(location, value)| SplinePoint {
+                    location,
+                    value: SplineValue::Constant(value),
+                    derivative: 0.0,
+                })
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params(continentalness: f64, erosion: f64) -> TerrainParams {
+        TerrainParams {
+            continentalness,
+            erosion,
+            peaks_valleys: 0.0,
+        }
+    }
+
+    fn point(location: f64, value: f64, derivative: f64) -> SplinePoint {
+        SplinePoint {
+            location,
+            value: SplineValue::Constant(value),
+            derivative,
+        }
+    }
+
+    fn spline(points: Vec<SplinePoint>) -> Spline {
+        Spline {
+            coordinate: TerrainParam::Continentalness,
+            points,
+        }
+    }
+
+    #[test]
+    fn control_points_keep_their_values() {
+        let spline = spline(vec![
+            point(-1.0, 2.0, 0.5),
+            point(0.0, 5.0, -3.0),
+            point(0.5, 4.0, 0.0),
+        ]);
+        for (location, value) in [(-1.0, 2.0), (0.0, 5.0), (0.5, 4.0)] {
+            assert!((spline.evaluate(&params(location, 0.0)) - value).abs() < 1e-9);
+        }
+        // 기울기 0 인 두 점 사이는 가운데에서 평균
+        let flat = Spline::constant_points(TerrainParam::Erosion, &[(0.0, 1.0), (2.0, 3.0)]);
+        assert!((flat.evaluate(&params(0.0, 1.0)) - 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn flat_control_points_give_monotonic_segments() {
+        let spline = Spline::constant_points(
+            TerrainParam::Continentalness,
+            &[(-1.0, 0.0), (0.0, 4.0), (0.3, 4.5), (1.0, 10.0)],
+        );
+        let mut previous = f64::MIN;
+        for i in 0..=200 {
+            let value = spline.evaluate(&params(-1.0 + i as f64 * 0.01, 0.0));
+            assert!(value >= previous, "{} < {} at step {}", value, previous, i);
+            previous = value;
+        }
+    }
+
+    #[test]
+    fn nested_splines_use_their_own_coordinate() {
+        let by_erosion = |low, high| {
+            SplineValue::Spline(Box::new(Spline::constant_points(
+                TerrainParam::Erosion,
+                &[(0.0, low), (1.0, high)],
+            )))
+        };
+        let spline = spline(vec![
+            SplinePoint {
+                location: 0.0,
+                value: by_erosion(0.0, 10.0),
+                derivative: 0.0,
+            },
+            SplinePoint {
+                location: 1.0,
+                value: by_erosion(20.0, 40.0),
+                derivative: 0.0,
+            },
+        ]);
+
+        assert!((spline.evaluate(&params(0.0, 0.0)) - 0.0).abs() < 1e-9);
+        assert!((spline.evaluate(&params(0.0, 1.0)) - 10.0).abs() < 1e-9);
+        assert!((spline.evaluate(&params(1.0, 0.5)) - 30.0).abs() < 1e-9);
+        // 가운데는 두 안쪽 스플라인 값의 평균 (5 와 30)
+        assert!((spline.evaluate(&params(0.5, 0.5)) - 17.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn out_of_range_inputs_follow_the_end_points() {
+        let ends = spline(vec![point(-1.0, 2.0, 0.0), point(1.0, 6.0, 2.0)]);
+        // 기울기 0 이면 끝 점 값으로 고정, 아니면 기울기를 따라 직선
+        assert_eq!(ends.evaluate(&params(-5.0, 0.0)), 2.0);
+        assert_eq!(ends.evaluate(&params(2.0, 0.0)), 8.0);
+
+        assert_eq!(ends.evaluate(&params(f64::NAN, 0.0)), 2.0);
+        assert_eq!(ends.evaluate(&params(f64::NEG_INFINITY, 0.0)), 2.0);
+        assert_eq!(ends.evaluate(&params(f64::INFINITY, 0.0)), 6.0);
+
+        let single = spline(vec![point(0.0, 3.0, 0.0)]);
+        assert_eq!(single.evaluate(&params(f64::NAN, 0.0)), 3.0);
+        assert_eq!(single.evaluate(&params(0.7, 0.0)), 3.0);
+        assert_eq!(spline(vec![]).evaluate(&params(0.0, 0.0)), 0.0);
+    }
+
+    #[test]
+    fn peaks_valleys_folds_weirdness() {
+        for (weirdness, folded) in [(0.0, -1.0), (2.0 / 3.0, 1.0), (-2.0 / 3.0, 1.0), (1.0, 0.0)] {
+            assert!(
+                (peaks_valleys(weirdness) - folded).abs() < 1e-9,
+                "{}",
+                weirdness
+            );
+        }
+    }
+}
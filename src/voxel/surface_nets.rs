@@ -0,0 +1,234 @@
+use super::block::Block;
+use super::chunk::Chunk;
+use super::coord::LocalVoxelPos;
+use super::generator::TerrainGenerator;
+use super::mesh::VoxelData;
+use bevy::prelude::*;
+
+// 부드러운 지형 메쉬 (Surface Nets)
+//
+// 블록 대신 밀도 (양수 = 고체) 샘플을 격자로 보고, 부호가 바뀌는 칸마다 버텍스 하나를
+// 칸 안의 교차점 평균 위치에 놓은 뒤 이웃한 칸의 버텍스를 사각형으로 이음
+// 샘플 (x, y, z) 는 블록 (x, y, z) 의 중심이라서 블록 메쉬와 같은 위치에 지형이 생김
+//
+// 밀도 격자는 청크 양쪽으로 한 칸씩 더 있어서 ([-1, W] x [-1, H] x [-1, W])
+// 생성기의 밀도가 좌표에만 의존하면 이웃 청크와 경계가 이어짐
+
+const SIZE_X: i32 = VoxelData::CHUNK_WIDTH + 2;
+const SIZE_Y: i32 = VoxelData::CHUNK_HEIGHT + 2;
+const SIZE_Z: i32 = VoxelData::CHUNK_WIDTH + 2;
+
+// 블록으로 밀도를 만들 때의 값 (생성기에 밀도가 없거나 블록을 고쳤을 때)
+pub const SOLID_DENSITY: f32 = 0.5;
+pub const AIR_DENSITY: f32 = -0.5;
+
+pub fn density_len() -> usize {
+    (SIZE_X * SIZE_Y * SIZE_Z) as usize
+}
+
+// 청크 로컬 좌표 (-1 ~ W, -1 ~ H) -> 밀도 배열 인덱스, 범위 밖이면 None
+pub fn density_index(x: i32, y: i32, z: i32) -> Option<usize> {
+    let (x, y, z) = (x + 1, y + 1, z + 1);
+    if !(0..SIZE_X).contains(&x) || !(0..SIZE_Y).contains(&y) || !(0..SIZE_Z).contains(&z) {
+        return None;
+    }
+    Some(((x * SIZE_Y + y) * SIZE_Z + z) as usize)
+}
+
+// 생성기의 밀도로 격자를 채움, 생성기가 없거나 밀도가 없는 생성기면 블록에서 만듦 (청크 밖은 공기)
+pub fn sample_density(chunk: &Chunk, generator: Option<&dyn TerrainGenerator>) -> Vec<f32> {
+    let block = Block::new();
+    let mut density = vec![AIR_DENSITY; density_len()];
+
+    for x in -1..=VoxelData::CHUNK_WIDTH {
+        for y in -1..=VoxelData::CHUNK_HEIGHT {
+            for z in -1..=VoxelData::CHUNK_WIDTH {
+                let local = LocalVoxelPos::new(x, y, z);
+                let value =
+                    match generator.and_then(|g| g.density(chunk.chunk_coord.to_world(local))) {
+                        Some(value) => value,
+                        None => match chunk.get_voxel(local) {
+                            Some(block_id) if block.block_types[block_id as usize].is_solid => {
+                                SOLID_DENSITY
+                            }
+                            _ => AIR_DENSITY,
+                        },
+                    };
+                density[density_index(x, y, z).unwrap()] = value;
+            }
+        }
+    }
+
+    density
+}
+
+pub struct SurfaceMesh {
+    pub vertices: Vec<Vec3>,
+    pub normals: Vec<Vec3>,
+    pub colors: Vec<[f32; 4]>,
+    pub triangles: Vec<u32>,
+}
+
+// 칸 (cell) 의 최소 모서리 샘플 기준 8개 꼭짓점
+const CORNERS: [IVec3; 8] = [
+    IVec3::new(0, 0, 0),
+    IVec3::new(1, 0, 0),
+    IVec3::new(0, 1, 0),
+    IVec3::new(1, 1, 0),
+    IVec3::new(0, 0, 1),
+    IVec3::new(1, 0, 1),
+    IVec3::new(0, 1, 1),
+    IVec3::new(1, 1, 1),
+];
+
+// 칸의 12개 모서리 (CORNERS 인덱스 쌍)
+const EDGES: [(usize, usize); 12] = [
+    (0, 1),
+    (2, 3),
+    (4, 5),
+    (6, 7),
+    (0, 2),
+    (1, 3),
+    (4, 6),
+    (5, 7),
+    (0, 4),
+    (1, 5),
+    (2, 6),
+    (3, 7),
+];
+
+pub fn build(chunk: &Chunk, density: &[f32]) -> SurfaceMesh {
+    let block = Block::new();
+    let sample = |p: IVec3| density[density_index(p.x, p.y, p.z).unwrap()];
+
+    let mut mesh = SurfaceMesh {
+        vertices: Vec::new(),
+        normals: Vec::new(),
+        colors: Vec::new(),
+        triangles: Vec::new(),
+    };
+
+    // 칸 [-1, W) x [-1, H) x [-1, W) 의 버텍스 인덱스
+    let cell_size = IVec3::new(SIZE_X - 1, SIZE_Y - 1, SIZE_Z - 1);
+    let cell_index = |c: IVec3| {
+        let c = c + IVec3::ONE;
+        ((c.x * cell_size.y + c.y) * cell_size.z + c.z) as usize
+    };
+    let mut cell_vertices = vec![u32::MAX; (cell_size.x * cell_size.y * cell_size.z) as usize];
+
+    for x in -1..VoxelData::CHUNK_WIDTH {
+        for y in -1..VoxelData::CHUNK_HEIGHT {
+            for z in -1..VoxelData::CHUNK_WIDTH {
+                let cell = IVec3::new(x, y, z);
+                let values = CORNERS.map(|corner| sample(cell + corner));
+
+                let mut sum = Vec3::ZERO;
+                let mut crossings = 0;
+                for (a, b) in EDGES {
+                    let (da, db) = (values[a], values[b]);
+                    if (da > 0.0) == (db > 0.0) {
+                        continue;
+                    }
+                    let t = da / (da - db);
+                    sum += CORNERS[a].as_vec3().lerp(CORNERS[b].as_vec3(), t);
+                    crossings += 1;
+                }
+                if crossings == 0 {
+                    continue;
+                }
+
+                // 밀도가 커지는 방향의 반대가 바깥쪽
+                let mut gradient = Vec3::ZERO;
+                for (i, corner) in CORNERS.iter().enumerate() {
+                    let sign = corner.as_vec3() * 2.0 - Vec3::ONE;
+                    gradient += sign * values[i];
+                }
+
+                cell_vertices[cell_index(cell)] = mesh.vertices.len() as u32;
+                mesh.vertices
+                    .push(cell.as_vec3() + sum / crossings as f32 + Vec3::splat(0.5));
+                mesh.normals
+                    .push((-gradient).try_normalize().unwrap_or(Vec3::Y));
+                mesh.colors
+                    .push(blend_material(chunk, &block, cell, &values));
+            }
+        }
+    }
+
+    // 모서리 (p, p + axis) 는 p 가 청크 안에 있을 때만 이 청크가 그림 -> 이웃 청크와 겹치지 않음
+    // axis 에 수직인 두 축 (u, v) 은 u x v = axis 가 되는 순서
+    let axes = [
+        (IVec3::X, IVec3::Y, IVec3::Z),
+        (IVec3::Y, IVec3::Z, IVec3::X),
+        (IVec3::Z, IVec3::X, IVec3::Y),
+    ];
+    for x in 0..VoxelData::CHUNK_WIDTH {
+        for y in 0..VoxelData::CHUNK_HEIGHT {
+            for z in 0..VoxelData::CHUNK_WIDTH {
+                let p = IVec3::new(x, y, z);
+                let d0 = sample(p);
+
+                for (axis, u, v) in axes {
+                    let d1 = sample(p + axis);
+                    if (d0 > 0.0) == (d1 > 0.0) {
+                        continue;
+                    }
+
+                    let quad =
+                        [p - u - v, p - v, p, p - u].map(|cell| cell_vertices[cell_index(cell)]);
+                    if quad.contains(&u32::MAX) {
+                        continue;
+                    }
+
+                    // p 쪽이 고체면 면이 +axis 를 봄, 반시계 방향이 앞면
+                    let [a, b, c, d] = quad;
+                    if d0 > 0.0 {
+                        mesh.triangles.extend_from_slice(&[a, b, c, a, c, d]);
+                    } else {
+                        mesh.triangles.extend_from_slice(&[a, c, b, a, d, c]);
+                    }
+                }
+            }
+        }
+    }
+
+    mesh
+}
+
+// 칸의 고체 꼭짓점에 있는 블록 색을 고르게 섞음, 청크 밖 꼭짓점은 가장 가까운 청크 안 블록을 씀
+fn blend_material(chunk: &Chunk, block: &Block, cell: IVec3, values: &[f32; 8]) -> [f32; 4] {
+    let mut sum = Vec3::ZERO;
+    let mut count = 0;
+
+    for (i, corner) in CORNERS.iter().enumerate() {
+        if values[i] <= 0.0 {
+            continue;
+        }
+        let p = (cell + *corner).clamp(
+            IVec3::ZERO,
+            IVec3::new(
+                VoxelData::CHUNK_WIDTH - 1,
+                VoxelData::CHUNK_HEIGHT - 1,
+                VoxelData::CHUNK_WIDTH - 1,
+            ),
+        );
+        let Some(block_id) = chunk.get_voxel(LocalVoxelPos::new(p.x, p.y, p.z)) else {
+            continue;
+        };
+        let block_type = &block.block_types[block_id as usize];
+        if !block_type.is_solid {
+            continue;
+        }
+
+        let [r, g, b] = block_type.map_color;
+        let color = Color::rgb_u8(r, g, b).as_linear_rgba_f32();
+        sum += Vec3::new(color[0], color[1], color[2]);
+        count += 1;
+    }
+
+    if count == 0 {
+        return Color::GRAY.as_linear_rgba_f32();
+    }
+    let color = sum / count as f32;
+    [color.x, color.y, color.z, 1.0]
+}
@@ -0,0 +1,359 @@
+use super::erosion::ErosionSettings;
+use super::mesh::VoxelData;
+use super::river::RiverSettings;
+use super::spline::{peaks_valleys, Spline, SplinePoint, SplineValue, TerrainParam, TerrainParams};
+use crate::noise::graph::{NoiseGraph, NoiseNode};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+// 지형 모양을 정하는 노이즈 그래프 묶음, assets/terrain/*.ron 으로 저장/교체 가능
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TerrainPreset {
+    // (x, z) 에서의 지표 높이 (블록 단위)
+    pub height: NoiseNode,
+    // min_ground 아래에서 0 보다 작으면 동굴 (공기)
+    pub cave: NoiseNode,
+    // 있으면 height 에 수력 침식을 적용
+    #[serde(default)]
+    pub hydraulic_erosion: Option<ErosionSettings>,
+}
+
+impl Default for TerrainPreset {
+    // 기존 Chunk::get_block_type 에 하드코딩되어 있던 지형
+    fn default() -> Self {
+        let octaves = (0..6)
+            .map(|i| NoiseNode::Perlin2d {
+                seed_offset: 0,
+                frequency: 0.001 * 2f64.powi(i),
+            })
+            .collect();
+
+        TerrainPreset {
+            height: NoiseNode::Clamp {
+                source: Box::new(NoiseNode::ScaleBias {
+                    source: Box::new(NoiseNode::Add(octaves)),
+                    scale: (VoxelData::CHUNK_HEIGHT - 1) as f64,
+                    bias: 0.0,
+                }),
+                min: VoxelData::CHUNK_HEIGHT as f64 * 0.5,
+                max: (VoxelData::CHUNK_HEIGHT - 1) as f64,
+            },
+            cave: NoiseNode::Simplex3d {
+                seed_offset: 0,
+                frequency: 0.1,
+            },
+            hydraulic_erosion: None,
+        }
+    }
+}
+
+impl TerrainPreset {
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<TerrainPreset> {
+        let text = fs::read_to_string(path)?;
+        ron::from_str(&text).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let text = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        fs::write(path, text)
+    }
+
+    pub fn build(&self, seed: u32) -> TerrainNoise {
+        TerrainNoise {
+            seed,
+            height: self.height.build(seed),
+            cave: self.cave.build(seed),
+        }
+    }
+}
+
+// 시드가 적용된 TerrainPreset, World 가 하나 들고 모든 청크 생성에 재사용
+pub struct TerrainNoise {
+    pub seed: u32,
+    pub height: NoiseGraph,
+    pub cave: NoiseGraph,
+}
+
+impl TerrainNoise {
+    pub fn terrain_height(&self, x: f32, z: f32) -> f32 {
+        self.height.get([x as f64, 0.0, z as f64]) as f32
+    }
+
+    pub fn cave(&self, x: f32, y: f32, z: f32) -> f64 {
+        self.cave.get([x as f64, y as f64, z as f64])
+    }
+}
+
+// 3D 밀도 지형 (DensityGenerator), 높이 기울기에 3D 노이즈를 더해서
+// 절벽, 오버행, 아치, 떠 있는 섬이 생길 수 있음
+//   density = (base_height(x, z) - y) * squash + noise(x, y / stretch, z) * noise_amplitude
+// 양수면 고체
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DensityPreset {
+    // (x, z) 에서 3D 노이즈가 없을 때의 지표 높이 (블록 단위)
+    pub base_height: NoiseNode,
+    pub noise: NoiseNode,
+    // 지표에서 한 블록 멀어질 때 바뀌는 밀도, 작을수록 3D 노이즈가 세게 작용함
+    pub squash: f64,
+    // 3D 노이즈를 y 방향으로 늘리는 배율, 1 보다 크면 세로로 긴 절벽과 기둥이 생김
+    pub stretch: f64,
+    pub noise_amplitude: f64,
+    // 잔디 아래 흙 층 두께, 더 아래는 돌
+    pub dirt_depth: i32,
+}
+
+impl Default for DensityPreset {
+    fn default() -> Self {
+        DensityPreset {
+            base_height: NoiseNode::ScaleBias {
+                source: Box::new(NoiseNode::Fbm2d {
+                    seed_offset: 0,
+                    frequency: 0.02,
+                    octaves: 4,
+                    persistence: 0.5,
+                    lacunarity: 2.0,
+                }),
+                scale: 3.0,
+                bias: VoxelData::CHUNK_HEIGHT as f64 * 0.55,
+            },
+            noise: NoiseNode::Add(vec![
+                NoiseNode::Perlin3d {
+                    seed_offset: 1,
+                    frequency: 0.06,
+                },
+                NoiseNode::ScaleBias {
+                    source: Box::new(NoiseNode::Perlin3d {
+                        seed_offset: 2,
+                        frequency: 0.15,
+                    }),
+                    scale: 0.4,
+                    bias: 0.0,
+                },
+            ]),
+            squash: 0.15,
+            stretch: 1.0,
+            noise_amplitude: 2.0,
+            dirt_depth: 3,
+        }
+    }
+}
+
+impl DensityPreset {
+    pub fn build(&self, seed: u32) -> DensityNoise {
+        DensityNoise {
+            seed,
+            base_height: self.base_height.build(seed),
+            noise: self.noise.build(seed),
+            squash: self.squash,
+            stretch: self.stretch.max(f64::EPSILON),
+            noise_amplitude: self.noise_amplitude,
+            dirt_depth: self.dirt_depth,
+        }
+    }
+}
+
+// 시드가 적용된 DensityPreset
+pub struct DensityNoise {
+    pub seed: u32,
+    pub base_height: NoiseGraph,
+    pub noise: NoiseGraph,
+    pub squash: f64,
+    pub stretch: f64,
+    pub noise_amplitude: f64,
+    pub dirt_depth: i32,
+}
+
+impl DensityNoise {
+    pub fn base_height(&self, x: f32, z: f32) -> f64 {
+        self.base_height.get([x as f64, 0.0, z as f64])
+    }
+
+    // base_height 는 컬럼마다 한 번만 계산해서 넘김
+    pub fn density(&self, x: f32, y: f32, z: f32, base_height: f64) -> f32 {
+        let gradient = (base_height - y as f64) * self.squash;
+        let noise = self
+            .noise
+            .get([x as f64, y as f64 / self.stretch, z as f64]);
+        (gradient + noise * self.noise_amplitude) as f32
+    }
+}
+
+// 스플라인 지형 (SplineGenerator)
+// 넓은 범위의 2D 노이즈 세 개 (continentalness, erosion, weirdness) 를 스플라인으로 높이와 거칠기로 바꿈
+//   terrain_height = height(params) + detail(x, z) * roughness(params)
+// 바다 (sea_level 아래), 해안, 평야, 산맥이 수천 블록 단위로 나뉨
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SplinePreset {
+    pub continentalness: NoiseNode,
+    pub erosion: NoiseNode,
+    // peaks_valleys 로 접기 전의 값
+    pub weirdness: NoiseNode,
+    // 지표 높이 (블록 단위)
+    pub height: Spline,
+    // detail 노이즈에 곱하는 높이 (블록 단위)
+    pub roughness: Spline,
+    pub detail: NoiseNode,
+    // 지표가 sea_level + 1 이하면 모래 (해안, 바다 바닥)
+    pub sea_level: f64,
+    // 지표가 이 높이 이상이면 흙 없이 돌
+    pub stone_height: f64,
+    pub dirt_depth: i32,
+    // 있으면 지표 높이에 수력 침식을 적용
+    #[serde(default)]
+    pub hydraulic_erosion: Option<ErosionSettings>,
+    // 있으면 높은 곳에서 sea_level 까지 강을 깎음 (침식 다음)
+    #[serde(default)]
+    pub rivers: Option<RiverSettings>,
+}
+
+impl Default for SplinePreset {
+    fn default() -> Self {
+        let fbm = |seed_offset, frequency| NoiseNode::Fbm2d {
+            seed_offset,
+            frequency,
+            octaves: 5,
+            persistence: 0.5,
+            lacunarity: 2.0,
+        };
+        let point = |location, value| SplinePoint {
+            location,
+            value,
+            derivative: 0.0,
+        };
+        let peaks = |points: &[(f64, f64)]| {
+            SplineValue::Spline(Box::new(Spline::constant_points(
+                TerrainParam::PeaksValleys,
+                points,
+            )))
+        };
+
+        // 내륙 : 침식이 낮을수록 봉우리와 골짜기의 높이 차이가 커짐
+        let inland = |mountains: SplineValue, hills: SplineValue, plains: f64| {
+            SplineValue::Spline(Box::new(Spline {
+                coordinate: TerrainParam::Erosion,
+                points: vec![
+                    point(-0.3, mountains),
+                    point(0.0, hills),
+                    point(0.3, SplineValue::Constant(plains)),
+                ],
+            }))
+        };
+
+        let height = Spline {
+            coordinate: TerrainParam::Continentalness,
+            points: vec![
+                // 깊은 바다 ~ 대륙붕
+                point(-0.6, SplineValue::Constant(3.0)),
+                point(-0.35, SplineValue::Constant(4.0)),
+                point(-0.18, SplineValue::Constant(6.5)),
+                // 해안
+                point(-0.08, SplineValue::Constant(8.5)),
+                point(
+                    0.0,
+                    inland(
+                        peaks(&[(-1.0, 9.5), (0.0, 11.0), (1.0, 13.0)]),
+                        peaks(&[(-1.0, 9.5), (1.0, 11.0)]),
+                        9.5,
+                    ),
+                ),
+                point(
+                    0.25,
+                    inland(
+                        peaks(&[(-1.0, 11.0), (0.0, 14.0), (1.0, 19.0)]),
+                        peaks(&[(-1.0, 10.0), (1.0, 13.5)]),
+                        10.5,
+                    ),
+                ),
+            ],
+        };
+
+        SplinePreset {
+            continentalness: fbm(10, 0.0015),
+            erosion: fbm(11, 0.003),
+            // fbm 은 대부분 ±0.4 안이라서 접었을 때 봉우리 (|w| = 2/3) 까지 닿도록 키움
+            weirdness: NoiseNode::ScaleBias {
+                source: Box::new(fbm(12, 0.006)),
+                scale: 1.6,
+                bias: 0.0,
+            },
+            height,
+            roughness: Spline::constant_points(
+                TerrainParam::Erosion,
+                &[(-0.3, 2.0), (0.0, 1.0), (0.3, 0.4)],
+            ),
+            detail: NoiseNode::Perlin2d {
+                seed_offset: 13,
+                frequency: 0.08,
+            },
+            sea_level: VoxelData::CHUNK_HEIGHT as f64 * 0.4,
+            stone_height: VoxelData::CHUNK_HEIGHT as f64 * 0.75,
+            dirt_depth: 3,
+            hydraulic_erosion: None,
+            rivers: None,
+        }
+    }
+}
+
+impl SplinePreset {
+    pub fn build(&self, seed: u32) -> SplineNoise {
+        let mut height = self.height.clone();
+        let mut roughness = self.roughness.clone();
+        height.sort();
+        roughness.sort();
+
+        SplineNoise {
+            seed,
+            continentalness: self.continentalness.build(seed),
+            erosion: self.erosion.build(seed),
+            weirdness: self.weirdness.build(seed),
+            height,
+            roughness,
+            detail: self.detail.build(seed),
+            sea_level: self.sea_level as f32,
+            stone_height: self.stone_height as f32,
+            dirt_depth: self.dirt_depth,
+        }
+    }
+}
+
+// 시드가 적용된 SplinePreset
+pub struct SplineNoise {
+    pub seed: u32,
+    pub continentalness: NoiseGraph,
+    pub erosion: NoiseGraph,
+    pub weirdness: NoiseGraph,
+    pub height: Spline,
+    pub roughness: Spline,
+    pub detail: NoiseGraph,
+    pub sea_level: f32,
+    pub stone_height: f32,
+    pub dirt_depth: i32,
+}
+
+impl SplineNoise {
+    pub fn params(&self, x: f32, z: f32) -> TerrainParams {
+        let pos = [x as f64, 0.0, z as f64];
+        TerrainParams {
+            continentalness: self.continentalness.get(pos),
+            erosion: self.erosion.get(pos),
+            peaks_valleys: peaks_valleys(self.weirdness.get(pos)),
+        }
+    }
+
+    // 기반암 위, 청크 높이 안으로 제한
+    pub fn terrain_height(&self, x: f32, z: f32) -> f32 {
+        let params = self.params(x, z);
+        let detail = self.detail.get([x as f64, 0.0, z as f64]);
+        let height = self.height.evaluate(&params) + detail * self.roughness.evaluate(&params);
+        (height as f32).clamp(1.0, (VoxelData::CHUNK_HEIGHT - 1) as f32)
+    }
+
+    // detail 노이즈를 뺀 큰 지형의 높이, 강 경로를 찾을 때 작은 웅덩이에 갇히지 않도록 씀
+    pub fn base_height(&self, x: f32, z: f32) -> f32 {
+        let height = self.height.evaluate(&self.params(x, z));
+        (height as f32).clamp(1.0, (VoxelData::CHUNK_HEIGHT - 1) as f32)
+    }
+}
@@ -0,0 +1,576 @@
+use super::block::{Block, EBlockType};
+use super::chunk::ChunkCoord;
+use super::coord::WorldVoxelPos;
+use super::world::World;
+use bevy::prelude::*;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+// MagicaVoxel .vox 파일 읽기/쓰기
+// https://github.com/ephtracy/voxel-model/blob/master/MagicaVoxel-file-format-vox.txt
+//
+// MagicaVoxel 은 Z 가 위쪽인 오른손 좌표계라서 x 축으로 돌려서 월드 (Y 가 위쪽) 에 맞춤
+// vox (x, y, z) -> 월드 (x, z, size_y - 1 - y), 축만 바꾸면 모델이 거울상이 됨
+
+const VOX_VERSION: u32 = 150;
+const VOX_MAX_SIZE: i32 = 256;
+
+// 파일에 들어 있는 그대로의 모델, voxel 은 [x, y, z, color_index] (vox 축 기준)
+pub struct VoxModel {
+    pub size: [u32; 3],
+    pub voxels: Vec<[u8; 4]>,
+}
+
+pub struct VoxScene {
+    pub models: Vec<VoxModel>,
+    // RGBA 청크가 없으면 None (MagicaVoxel 기본 팔레트)
+    // palette[i] 는 color_index i + 1 의 색
+    pub palette: Option<[[u8; 4]; 256]>,
+}
+
+// 월드 축 기준으로 변환된 블록 모델, vox 에서 읽은 모델은 공기 블록을 포함하지 않음
+// (wfc 구조물처럼 공기 블록이 있으면 찍을 때 그 자리를 비움, write_vox 는 빈 칸으로 저장)
+pub struct BlockModel {
+    pub size: IVec3,
+    pub blocks: Vec<(IVec3, i32)>,
+}
+
+// vox 팔레트 인덱스 -> 블록 id 매핑
+// table 에 없는 인덱스는 팔레트 색과 가장 가까운 BlockType::map_color 의 블록으로,
+// 팔레트 색도 모르면 default_block 으로 매핑
+// 블록 id 는 넣을 때 검사하므로 매핑 결과는 항상 Block::block_types 안에 있음
+pub struct PaletteMapping {
+    table: HashMap<u8, i32>,
+    default_block: i32,
+}
+
+impl Default for PaletteMapping {
+    fn default() -> Self {
+        PaletteMapping {
+            table: HashMap::new(),
+            default_block: EBlockType::Stone as i32,
+        }
+    }
+}
+
+impl PaletteMapping {
+    pub fn with_entry(mut self, color_index: u8, block_id: i32) -> io::Result<Self> {
+        self.table.insert(color_index, check_block_id(block_id)?);
+        Ok(self)
+    }
+
+    pub fn with_default_block(mut self, block_id: i32) -> io::Result<Self> {
+        self.default_block = check_block_id(block_id)?;
+        Ok(self)
+    }
+
+    fn resolve(&self, block: &Block, palette: Option<&[[u8; 4]; 256]>, color_index: u8) -> i32 {
+        if let Some(block_id) = self.table.get(&color_index) {
+            return *block_id;
+        }
+
+        let Some(palette) = palette else {
+            return self.default_block;
+        };
+        let [r, g, b, _] = palette[(color_index as usize + 255) % 256];
+
+        let mut best = self.default_block;
+        let mut best_distance = i32::MAX;
+        for (block_id, block_type) in block.block_types.iter().enumerate() {
+            if !block_type.is_solid {
+                continue;
+            }
+
+            let [br, bg, bb] = block_type.map_color;
+            let dr = r as i32 - br as i32;
+            let dg = g as i32 - bg as i32;
+            let db = b as i32 - bb as i32;
+            let distance = dr * dr + dg * dg + db * db;
+            if distance < best_distance {
+                best_distance = distance;
+                best = block_id as i32;
+            }
+        }
+        best
+    }
+}
+
+fn check_block_id(block_id: i32) -> io::Result<i32> {
+    let count = Block::new().block_types.len();
+    if !(0..count as i32).contains(&block_id) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "block id {} is not one of the {} block types",
+                block_id, count
+            ),
+        ));
+    }
+    Ok(block_id)
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> io::Result<u32> {
+    let slice = bytes
+        .get(offset..offset + 4)
+        .ok_or(invalid_data("unexpected end of .vox data"))?;
+    Ok(u32::from_le_bytes([slice[0], slice[1], slice[2], slice[3]]))
+}
+
+pub fn read_vox(bytes: &[u8]) -> io::Result<VoxScene> {
+    if bytes.get(0..4) != Some(b"VOX ") {
+        return Err(invalid_data("not a .vox file"));
+    }
+    if bytes.get(8..12) != Some(b"MAIN") {
+        return Err(invalid_data("missing MAIN chunk"));
+    }
+
+    let main_content = read_u32(bytes, 12)? as usize;
+    let main_children = read_u32(bytes, 16)? as usize;
+    let mut offset = 20 + main_content;
+    let end = offset + main_children;
+    if end > bytes.len() {
+        return Err(invalid_data("unexpected end of .vox data"));
+    }
+
+    let mut scene = VoxScene {
+        models: Vec::new(),
+        palette: None,
+    };
+    let mut size = None;
+
+    // 알 수 없는 청크 (nTRN, MATL 등) 는 건너뜀
+    while offset < end {
+        let id = bytes
+            .get(offset..offset + 4)
+            .ok_or(invalid_data("unexpected end of .vox data"))?;
+        let content_size = read_u32(bytes, offset + 4)? as usize;
+        let children_size = read_u32(bytes, offset + 8)? as usize;
+        let content = offset + 12;
+        if content + content_size + children_size > end {
+            return Err(invalid_data("chunk exceeds MAIN"));
+        }
+
+        match id {
+            b"SIZE" => {
+                if content_size < 12 {
+                    return Err(invalid_data("SIZE chunk too small"));
+                }
+                size = Some([
+                    read_u32(bytes, content)?,
+                    read_u32(bytes, content + 4)?,
+                    read_u32(bytes, content + 8)?,
+                ]);
+            }
+            b"XYZI" => {
+                let size = size.take().ok_or(invalid_data("XYZI chunk without SIZE"))?;
+                let count = read_u32(bytes, content)? as usize;
+                // 개수가 청크 크기를 넘으면 다음 청크까지 읽게 됨
+                if content_size < 4 || count > (content_size - 4) / 4 {
+                    return Err(invalid_data("XYZI voxel count exceeds the chunk"));
+                }
+                let data = bytes
+                    .get(content + 4..content + 4 + count * 4)
+                    .ok_or(invalid_data("unexpected end of XYZI chunk"))?;
+
+                let voxels: Vec<[u8; 4]> =
+                    data.chunks(4).map(|v| [v[0], v[1], v[2], v[3]]).collect();
+                // 축을 돌릴 때 size 안에 있어야 함
+                if voxels
+                    .iter()
+                    .any(|v| (0..3).any(|axis| v[axis] as u32 >= size[axis]))
+                {
+                    return Err(invalid_data("voxel outside the model size"));
+                }
+                scene.models.push(VoxModel { size, voxels });
+            }
+            b"RGBA" => {
+                if content_size < 256 * 4 {
+                    return Err(invalid_data("RGBA chunk too small"));
+                }
+                let mut palette = [[0u8; 4]; 256];
+                for (i, color) in palette.iter_mut().enumerate() {
+                    let at = content + i * 4;
+                    let data = bytes
+                        .get(at..at + 4)
+                        .ok_or(invalid_data("unexpected end of RGBA chunk"))?;
+                    color.copy_from_slice(data);
+                }
+                scene.palette = Some(palette);
+            }
+            _ => {}
+        }
+
+        offset = content + content_size + children_size;
+    }
+
+    Ok(scene)
+}
+
+pub fn load_vox<P: AsRef<Path>>(path: P) -> io::Result<VoxScene> {
+    read_vox(&fs::read(path)?)
+}
+
+impl VoxScene {
+    pub fn to_block_model(
+        &self,
+        model_index: usize,
+        mapping: &PaletteMapping,
+    ) -> Option<BlockModel> {
+        let model = self.models.get(model_index)?;
+        let block = Block::new();
+
+        let blocks = model
+            .voxels
+            .iter()
+            .map(|[x, y, z, color_index]| {
+                let pos = IVec3::new(*x as i32, *z as i32, model.size[1] as i32 - 1 - *y as i32);
+                let block_id = mapping.resolve(&block, self.palette.as_ref(), *color_index);
+                (pos, block_id)
+            })
+            .collect();
+
+        Some(BlockModel {
+            size: IVec3::new(
+                model.size[0] as i32,
+                model.size[2] as i32,
+                model.size[1] as i32,
+            ),
+            blocks,
+        })
+    }
+}
+
+// 모델을 origin 위치에 찍고, 바뀐 청크의 메쉬를 다시 만듦
+// 생성되지 않은 청크나 높이 범위 밖의 블록은 무시, 실제로 놓인 블록 수를 반환
+pub fn stamp_model(world: &mut World, model: &BlockModel, origin: IVec3) -> usize {
+    let mut placed = 0;
+    let mut dirty_chunks: HashSet<ChunkCoord> = HashSet::new();
+
+    for (pos, block_id) in &model.blocks {
+        let pos = origin + *pos;
+        let changed = world.set_block_id(pos.into(), *block_id);
+        if !changed.is_empty() {
+            dirty_chunks.extend(changed);
+            placed += 1;
+        }
+    }
+
+    for coord in dirty_chunks {
+        world.rebuild_chunk_mesh(&coord);
+    }
+
+    placed
+}
+
+// 월드의 [min, max) 영역을 블록 모델로 복사 (공기 제외)
+pub fn region_to_model(world: &World, min: IVec3, max: IVec3) -> BlockModel {
+    let mut blocks = Vec::new();
+
+    for y in min.y..max.y {
+        for z in min.z..max.z {
+            for x in min.x..max.x {
+                match world.get_block_id(WorldVoxelPos::new(x, y, z)) {
+                    Some(id) if id != EBlockType::Air as i32 => {
+                        blocks.push((IVec3::new(x, y, z) - min, id));
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    BlockModel {
+        size: max - min,
+        blocks,
+    }
+}
+
+fn write_chunk(out: &mut Vec<u8>, id: &[u8; 4], content: &[u8], children: &[u8]) {
+    out.extend_from_slice(id);
+    out.extend_from_slice(&(content.len() as u32).to_le_bytes());
+    out.extend_from_slice(&(children.len() as u32).to_le_bytes());
+    out.extend_from_slice(content);
+    out.extend_from_slice(children);
+}
+
+// 블록 id 가 i 인 블록은 color_index i + 1 로 저장하고, 팔레트에는 map_color 를 씀
+// 기본 PaletteMapping 으로 다시 읽으면 같은 블록이 나옴
+pub fn write_vox(model: &BlockModel) -> io::Result<Vec<u8>> {
+    if model.size.min_element() <= 0 || model.size.max_element() > VOX_MAX_SIZE {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            ".vox models must be 1..=256 voxels on each axis",
+        ));
+    }
+
+    let block = Block::new();
+
+    let mut size = Vec::new();
+    for v in [model.size.x, model.size.z, model.size.y] {
+        size.extend_from_slice(&(v as u32).to_le_bytes());
+    }
+
+    let mut voxels = Vec::new();
+    for (pos, block_id) in &model.blocks {
+        // u8 로 바꾸면서 값이 돌아가지 않도록 먼저 범위를 확인
+        if pos.cmplt(IVec3::ZERO).any() || pos.cmpge(model.size).any() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("block {} is outside the model size {}", pos, model.size),
+            ));
+        }
+        if !(0..255).contains(block_id) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("block id {} has no .vox color index", block_id),
+            ));
+        }
+        // vox 에는 공기 블록이 없으므로 빈 칸으로 둠 (팔레트 색으로 다른 블록이 되지 않도록)
+        if *block_id == EBlockType::Air as i32 {
+            continue;
+        }
+        // to_block_model 의 회전을 되돌림
+        let vox_y = model.size.z - 1 - pos.z;
+        voxels.push([pos.x as u8, vox_y as u8, pos.y as u8, (*block_id + 1) as u8]);
+    }
+
+    let mut xyzi = Vec::with_capacity(4 + voxels.len() * 4);
+    xyzi.extend_from_slice(&(voxels.len() as u32).to_le_bytes());
+    for voxel in &voxels {
+        xyzi.extend_from_slice(voxel);
+    }
+
+    let mut rgba = vec![0u8; 256 * 4];
+    for (block_id, block_type) in block.block_types.iter().enumerate() {
+        let [r, g, b] = block_type.map_color;
+        rgba[block_id * 4..block_id * 4 + 4].copy_from_slice(&[r, g, b, 255]);
+    }
+
+    let mut children = Vec::new();
+    write_chunk(&mut children, b"SIZE", &size, &[]);
+    write_chunk(&mut children, b"XYZI", &xyzi, &[]);
+    write_chunk(&mut children, b"RGBA", &rgba, &[]);
+
+    let mut out = Vec::new();
+    out.extend_from_slice(b"VOX ");
+    out.extend_from_slice(&VOX_VERSION.to_le_bytes());
+    write_chunk(&mut out, b"MAIN", &[], &children);
+    Ok(out)
+}
+
+pub fn save_vox<P: AsRef<Path>>(model: &BlockModel, path: P) -> io::Result<()> {
+    fs::write(path, write_vox(model)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::generator::GeneratorConfig;
+    use super::*;
+
+    fn test_model() -> BlockModel {
+        BlockModel {
+            size: IVec3::new(4, 3, 2),
+            blocks: vec![
+                (IVec3::new(0, 0, 0), EBlockType::Stone as i32),
+                (IVec3::new(3, 0, 1), EBlockType::Grass as i32),
+                (IVec3::new(1, 2, 0), EBlockType::Sand as i32),
+                (IVec3::new(2, 1, 1), EBlockType::BedRock as i32),
+                (IVec3::new(3, 2, 1), EBlockType::Dirt as i32),
+            ],
+        }
+    }
+
+    #[test]
+    fn write_then_read_keeps_blocks_and_palette() {
+        let model = test_model();
+        let bytes = write_vox(&model).unwrap();
+        let scene = read_vox(&bytes).unwrap();
+
+        assert_eq!(scene.models.len(), 1);
+        assert_eq!(scene.models[0].size, [4, 2, 3]);
+
+        let read = scene.to_block_model(0, &PaletteMapping::default()).unwrap();
+        assert_eq!(read.size, model.size);
+        assert_eq!(read.blocks, model.blocks);
+
+        let palette = scene.palette.unwrap();
+        for (block_id, block_type) in Block::new().block_types.iter().enumerate() {
+            let [r, g, b] = block_type.map_color;
+            assert_eq!(palette[block_id], [r, g, b, 255]);
+        }
+    }
+
+    #[test]
+    fn air_blocks_are_written_as_empty_voxels() {
+        let mut model = test_model();
+        model
+            .blocks
+            .push((IVec3::new(1, 1, 1), EBlockType::Air as i32));
+        model
+            .blocks
+            .push((IVec3::new(0, 2, 1), EBlockType::Air as i32));
+
+        let scene = read_vox(&write_vox(&model).unwrap()).unwrap();
+        assert_eq!(scene.models[0].voxels.len(), 5);
+
+        let read = scene.to_block_model(0, &PaletteMapping::default()).unwrap();
+        assert_eq!(read.blocks, test_model().blocks);
+    }
+
+    #[test]
+    fn truncated_input_is_invalid_data() {
+        let bytes = write_vox(&test_model()).unwrap();
+        for length in 0..bytes.len() {
+            let error = read_vox(&bytes[..length]).err().unwrap();
+            assert_eq!(
+                error.kind(),
+                io::ErrorKind::InvalidData,
+                "length {}",
+                length
+            );
+        }
+    }
+
+    #[test]
+    fn voxel_count_larger_than_chunk_is_invalid_data() {
+        let mut bytes = write_vox(&test_model()).unwrap();
+        // MAIN (20) + SIZE (12 + 12) 다음이 XYZI 청크, 내용의 첫 4 바이트가 개수
+        let count = 20 + 24 + 12;
+        assert_eq!(&bytes[count - 12..count - 8], b"XYZI");
+        bytes[count..count + 4].copy_from_slice(&100u32.to_le_bytes());
+        let error = read_vox(&bytes).err().unwrap();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn blocks_outside_the_model_are_rejected() {
+        let mut model = test_model();
+        model
+            .blocks
+            .push((IVec3::new(4, 0, 0), EBlockType::Stone as i32));
+        assert_eq!(
+            write_vox(&model).unwrap_err().kind(),
+            io::ErrorKind::InvalidInput
+        );
+
+        let mut model = test_model();
+        model.size = IVec3::new(300, 3, 2);
+        assert_eq!(
+            write_vox(&model).unwrap_err().kind(),
+            io::ErrorKind::InvalidInput
+        );
+
+        let mut model = test_model();
+        model
+            .blocks
+            .push((IVec3::new(-1, 0, 0), EBlockType::Stone as i32));
+        assert_eq!(
+            write_vox(&model).unwrap_err().kind(),
+            io::ErrorKind::InvalidInput
+        );
+    }
+
+    #[test]
+    fn import_rotates_instead_of_mirroring() {
+        // 원점과 vox 의 +x, +y, +z 로 한 칸씩
+        let scene = VoxScene {
+            models: vec![VoxModel {
+                size: [3, 4, 5],
+                voxels: vec![[0, 0, 0, 1], [1, 0, 0, 1], [0, 1, 0, 1], [0, 0, 1, 1]],
+            }],
+            palette: None,
+        };
+        let model = scene.to_block_model(0, &PaletteMapping::default()).unwrap();
+        assert_eq!(model.size, IVec3::new(3, 5, 4));
+
+        let origin = model.blocks[0].0;
+        let [dx, dy, dz] = [1, 2, 3].map(|i| model.blocks[i].0 - origin);
+        assert_eq!(origin, IVec3::new(0, 0, 3));
+        assert_eq!(dz, IVec3::Y);
+        // 오른손 좌표계끼리의 회전이면 x x y = z 가 그대로 유지됨
+        assert_eq!(dx.cross(dy), dz);
+    }
+
+    #[test]
+    fn voxels_outside_the_size_are_invalid_data() {
+        let scene = VoxScene {
+            models: vec![],
+            palette: None,
+        };
+        assert!(scene
+            .to_block_model(0, &PaletteMapping::default())
+            .is_none());
+
+        let mut bytes = write_vox(&test_model()).unwrap();
+        // 첫 voxel 의 y 를 SIZE 의 y (2) 로
+        let first_voxel = 20 + 24 + 12 + 4;
+        bytes[first_voxel + 1] = 2;
+        let error = read_vox(&bytes).err().unwrap();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn palette_mapping_rejects_unknown_block_ids() {
+        let count = Block::new().block_types.len() as i32;
+        let mapping = PaletteMapping::default()
+            .with_entry(1, EBlockType::Dirt as i32)
+            .unwrap();
+        assert!(mapping.with_entry(2, count).is_err());
+        assert!(PaletteMapping::default().with_entry(2, -1).is_err());
+        assert!(PaletteMapping::default().with_default_block(count).is_err());
+        assert!(PaletteMapping::default()
+            .with_default_block(count - 1)
+            .is_ok());
+    }
+
+    #[test]
+    fn world_region_round_trips_through_vox() {
+        let generator = GeneratorConfig::Superflat("bedrock,2*stone,dirt,grass".to_string());
+        let mut source = World::with_generator(generator.build(0).unwrap());
+        source.generate_region(&ChunkCoord::new(-1, -1), &ChunkCoord::new(1, 1));
+        // 회전이 틀리면 달라지도록 좌우 / 앞뒤가 다른 모양을 얹음
+        for (x, y, z, block) in [
+            (-2, 5, -3, EBlockType::Sand),
+            (-1, 5, -3, EBlockType::Stone),
+            (-1, 6, -3, EBlockType::BedRock),
+            (4, 5, 2, EBlockType::Dirt),
+            (4, 5, 3, EBlockType::Sand),
+            (0, 3, 0, EBlockType::Air),
+        ] {
+            source.set_block_id(WorldVoxelPos::new(x, y, z), block as i32);
+        }
+
+        let (min, max) = (IVec3::new(-3, 0, -4), IVec3::new(6, 8, 5));
+        let bytes = write_vox(&region_to_model(&source, min, max)).unwrap();
+        let model = read_vox(&bytes)
+            .unwrap()
+            .to_block_model(0, &PaletteMapping::default())
+            .unwrap();
+        assert_eq!(model.size, max - min);
+
+        let mut target = World::with_generator(GeneratorConfig::Void.build(0).unwrap());
+        target.generate_region(&ChunkCoord::new(2, -2), &ChunkCoord::new(3, 0));
+        let origin = IVec3::new(20, 3, -15);
+        stamp_model(&mut target, &model, origin);
+
+        for y in min.y..max.y {
+            for z in min.z..max.z {
+                for x in min.x..max.x {
+                    let pos = IVec3::new(x, y, z);
+                    assert_eq!(
+                        target.get_block_id((origin + pos - min).into()),
+                        source.get_block_id(pos.into()),
+                        "{}",
+                        pos
+                    );
+                }
+            }
+        }
+    }
+}
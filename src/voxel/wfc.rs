@@ -0,0 +1,794 @@
+use super::block::EBlockType;
+use super::vox::{self, BlockModel};
+use super::world::World;
+use bevy::prelude::*;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+// Wave Function Collapse 구조물 생성기
+//
+// 타일은 tile_size 크기의 작은 블록 템플릿 + 여섯 면의 소켓, 소켓이 맞는 타일끼리만 이웃할 수 있음
+// 격자의 모든 칸에 가능한 타일 (회전 포함) 을 두고, 엔트로피가 가장 작은 칸부터 가중치로 하나씩 고르고
+// 이웃 칸으로 제약을 전파함, 막히면 마지막 선택을 되돌리고 (백트래킹) 다른 타일을 고름
+// 같은 타일 세트, 크기, 시드면 항상 같은 결과
+//
+// 소켓 규칙
+// - 가로 소켓 (x, z 면) 은 면 밖에서 바라본 단면의 이름, 같은 이름끼리 이어짐
+//   좌우 대칭이 아닌 단면은 끝에 '>' 나 '<' 를 붙이고, 'a>' 는 'a<' 와만 이어짐 (마주 보면 좌우가 바뀜)
+// - 세로 소켓 (y 면) 은 같은 이름끼리 이어짐, 끝에 '^' 가 있으면 타일의 회전까지 같아야 이어짐
+//
+// 예) assets/structures/dungeon.ron
+
+// 타일의 회전 대칭, 회전한 타일을 몇 개 만들지 정함 (y 축 기준 90도씩)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Symmetry {
+    // 회전하지 않음 (모든 방향이 같은 타일이거나 방향을 고정할 타일)
+    #[default]
+    None,
+    // 0, 90도 (180도 돌리면 같은 타일)
+    Rotate2,
+    // 0, 90, 180, 270도
+    Rotate4,
+}
+
+impl Symmetry {
+    fn rotations(&self) -> u32 {
+        match self {
+            Symmetry::None => 1,
+            Symmetry::Rotate2 => 2,
+            Symmetry::Rotate4 => 4,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Sockets {
+    pub pos_x: String,
+    pub neg_x: String,
+    pub pos_y: String,
+    pub neg_y: String,
+    pub pos_z: String,
+    pub neg_z: String,
+}
+
+impl Sockets {
+    // DIRECTIONS 순서
+    fn to_array(&self) -> [String; 6] {
+        [
+            self.pos_x.clone(),
+            self.neg_x.clone(),
+            self.pos_y.clone(),
+            self.neg_y.clone(),
+            self.pos_z.clone(),
+            self.neg_z.clone(),
+        ]
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TileDef {
+    pub name: String,
+    #[serde(default = "default_weight")]
+    pub weight: f32,
+    #[serde(default)]
+    pub symmetry: Symmetry,
+    // 아래층 (y = 0) 부터, 층마다 z 순서의 행, 행은 x 순서의 palette 문자
+    pub layers: Vec<Vec<String>>,
+    pub sockets: Sockets,
+}
+
+fn default_weight() -> f32 {
+    1.0
+}
+
+fn default_max_backtracks() -> u32 {
+    1000
+}
+
+// 격자 바깥의 소켓, None 이면 제약 없음
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct BoundarySockets {
+    #[serde(default)]
+    pub horizontal: Option<String>,
+    #[serde(default)]
+    pub top: Option<String>,
+    #[serde(default)]
+    pub bottom: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TileSet {
+    // (x, y, z) 블록, x 와 z 는 같아야 함 (회전)
+    pub tile_size: (i32, i32, i32),
+    // 템플릿 문자 -> 블록 이름 (EBlockType::from_name), '-' 는 월드를 그대로 둠
+    pub palette: HashMap<char, String>,
+    #[serde(default)]
+    pub boundary: BoundarySockets,
+    // 구조물 바닥을 지표보다 얼마나 위에 둘지 (음수면 땅속), 찍는 쪽에서 사용
+    #[serde(default)]
+    pub ground_offset: i32,
+    #[serde(default = "default_max_backtracks")]
+    pub max_backtracks: u32,
+    pub tiles: Vec<TileDef>,
+}
+
+// 생성 결과, blocks 는 x 가 가장 빠른 순서 (x, z, y), None 은 월드를 그대로 둠
+pub struct Structure {
+    pub size: IVec3,
+    pub blocks: Vec<Option<i32>>,
+    // 칸마다 고른 타일 이름, (x, z, y) 순서
+    pub tiles: Vec<String>,
+}
+
+impl Structure {
+    // pos 의 블록, 월드를 그대로 두는 칸이거나 구조물 밖이면 None
+    pub fn get(&self, pos: IVec3) -> Option<i32> {
+        if pos.cmplt(IVec3::ZERO).any() || pos.cmpge(self.size).any() {
+            return None;
+        }
+        let index = (pos.y * self.size.z + pos.z) * self.size.x + pos.x;
+        self.blocks[index as usize]
+    }
+
+    // 공기 블록도 포함, 찍으면 그 자리의 지형을 비움
+    pub fn to_block_model(&self) -> BlockModel {
+        let mut blocks = Vec::new();
+        for y in 0..self.size.y {
+            for z in 0..self.size.z {
+                for x in 0..self.size.x {
+                    let pos = IVec3::new(x, y, z);
+                    if let Some(block_id) = self.get(pos) {
+                        blocks.push((pos, block_id));
+                    }
+                }
+            }
+        }
+        BlockModel {
+            size: self.size,
+            blocks,
+        }
+    }
+
+    // origin 에 찍고 바뀐 청크의 메쉬를 다시 만듦, 실제로 놓인 블록 수를 반환
+    pub fn stamp(&self, world: &mut World, origin: IVec3) -> usize {
+        vox::stamp_model(world, &self.to_block_model(), origin)
+    }
+}
+
+// +x, -x, +y, -y, +z, -z, 반대 방향은 d ^ 1
+const DIRECTIONS: [IVec3; 6] = [
+    IVec3::X,
+    IVec3::NEG_X,
+    IVec3::Y,
+    IVec3::NEG_Y,
+    IVec3::Z,
+    IVec3::NEG_Z,
+];
+
+fn is_vertical(direction: usize) -> bool {
+    direction == 2 || direction == 3
+}
+
+// 마주 보는 면에서 이어지는 소켓 이름
+fn mirror(socket: &str) -> String {
+    if let Some(name) = socket.strip_suffix('>') {
+        format!("{}<", name)
+    } else if let Some(name) = socket.strip_suffix('<') {
+        format!("{}>", name)
+    } else {
+        socket.to_string()
+    }
+}
+
+// 회전까지 고려한 타일
+struct Variant {
+    tile: usize,
+    rotation: u32,
+    weight: f32,
+    blocks: Vec<Option<i32>>,
+    sockets: [String; 6],
+}
+
+impl Variant {
+    // a 의 direction 면과 b 의 반대 면이 이어지는지
+    fn connects(a: &Variant, b: &Variant, direction: usize) -> bool {
+        let (sa, sb) = (&a.sockets[direction], &b.sockets[direction ^ 1]);
+        if is_vertical(direction) {
+            sa == sb && (!sa.ends_with('^') || a.rotation == b.rotation)
+        } else {
+            *sb == mirror(sa)
+        }
+    }
+
+    fn connects_boundary(&self, direction: usize, boundary: &str) -> bool {
+        if is_vertical(direction) {
+            self.sockets[direction] == boundary
+        } else {
+            mirror(&self.sockets[direction]) == boundary
+        }
+    }
+}
+
+impl TileSet {
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<TileSet> {
+        let text = fs::read_to_string(path)?;
+        ron::from_str(&text).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    fn size(&self) -> IVec3 {
+        IVec3::new(self.tile_size.0, self.tile_size.1, self.tile_size.2)
+    }
+
+    // 템플릿을 읽고 대칭에 따라 회전한 타일을 만듦
+    fn variants(&self) -> Result<Vec<Variant>, String> {
+        let size = self.size();
+        if size.x != size.z || size.min_element() <= 0 {
+            return Err(format!(
+                "tile_size must be positive with x == z: {:?}",
+                self.tile_size
+            ));
+        }
+
+        let mut palette = HashMap::new();
+        for (symbol, name) in &self.palette {
+            let block =
+                EBlockType::from_name(name).ok_or(format!("unknown block in palette: {}", name))?;
+            palette.insert(*symbol, block as i32);
+        }
+
+        let mut variants = Vec::new();
+        for (tile_index, tile) in self.tiles.iter().enumerate() {
+            if tile.layers.len() != size.y as usize
+                || tile.layers.iter().any(|layer| {
+                    layer.len() != size.z as usize
+                        || layer
+                            .iter()
+                            .any(|row| row.chars().count() != size.x as usize)
+                })
+            {
+                return Err(format!(
+                    "tile {} does not match tile_size {:?}",
+                    tile.name, self.tile_size
+                ));
+            }
+
+            let mut blocks = Vec::with_capacity(volume(size));
+            for layer in &tile.layers {
+                for row in layer {
+                    for symbol in row.chars() {
+                        if symbol == '-' {
+                            blocks.push(None);
+                        } else {
+                            let block = palette.get(&symbol).ok_or(format!(
+                                "tile {} uses a symbol missing from the palette: {}",
+                                tile.name, symbol
+                            ))?;
+                            blocks.push(Some(*block));
+                        }
+                    }
+                }
+            }
+
+            let rotations = tile.symmetry.rotations();
+            let mut sockets = tile.sockets.to_array();
+            for rotation in 0..rotations {
+                variants.push(Variant {
+                    tile: tile_index,
+                    rotation,
+                    weight: tile.weight.max(0.0) / rotations as f32,
+                    blocks: blocks.clone(),
+                    sockets: sockets.clone(),
+                });
+                blocks = rotate_blocks(&blocks, size);
+                sockets = rotate_sockets(&sockets);
+            }
+        }
+
+        if variants.is_empty() {
+            return Err("tile set has no tiles".to_string());
+        }
+        Ok(variants)
+    }
+
+    // size 는 타일 단위 격자 크기
+    pub fn solve(&self, size: IVec3, seed: u32) -> Result<Structure, String> {
+        if size.min_element() <= 0 {
+            return Err(format!("structure size must be positive: {}", size));
+        }
+
+        let variants = self.variants()?;
+        let mut solver = Solver::new(&variants, size);
+        solver.apply_boundary(&self.boundary)?;
+
+        let mut rng = StdRng::seed_from_u64(seed as u64);
+        let cells = solver.run(&mut rng, self.max_backtracks)?;
+
+        let tile_size = self.size();
+        let block_size = size * tile_size;
+        let mut blocks = vec![None; volume(block_size)];
+        let mut tiles = Vec::with_capacity(cells.len());
+
+        for (cell, variant) in cells.iter().enumerate() {
+            let variant = &variants[*variant];
+            tiles.push(self.tiles[variant.tile].name.clone());
+
+            let cell_pos = solver.position(cell) * tile_size;
+            for (i, block) in variant.blocks.iter().enumerate() {
+                let local = index_to_pos(i, tile_size);
+                let pos = cell_pos + local;
+                let index = (pos.y * block_size.z + pos.z) * block_size.x + pos.x;
+                blocks[index as usize] = *block;
+            }
+        }
+
+        Ok(Structure {
+            size: block_size,
+            blocks,
+            tiles,
+        })
+    }
+}
+
+fn volume(size: IVec3) -> usize {
+    (size.x * size.y * size.z) as usize
+}
+
+// blocks 는 (x, z, y) 순서, x 가 가장 빠름
+fn index_to_pos(index: usize, size: IVec3) -> IVec3 {
+    let index = index as i32;
+    IVec3::new(
+        index % size.x,
+        index / (size.x * size.z),
+        (index / size.x) % size.z,
+    )
+}
+
+// y 축 기준 90도 : (x, z) -> (W - 1 - z, x), +x 면이 +z 면으로 감
+fn rotate_blocks(blocks: &[Option<i32>], size: IVec3) -> Vec<Option<i32>> {
+    let mut rotated = vec![None; blocks.len()];
+    for (i, block) in blocks.iter().enumerate() {
+        let pos = index_to_pos(i, size);
+        let (x, z) = (size.x - 1 - pos.z, pos.x);
+        rotated[((pos.y * size.z + z) * size.x + x) as usize] = *block;
+    }
+    rotated
+}
+
+fn rotate_sockets(sockets: &[String; 6]) -> [String; 6] {
+    let [pos_x, neg_x, pos_y, neg_y, pos_z, neg_z] = sockets.clone();
+    // rotate_blocks 와 같은 방향 : +x -> +z -> -x -> -z -> +x
+    [neg_z, pos_z, pos_y, neg_y, pos_x, neg_x]
+}
+
+// 칸마다 가능한 타일을 비트셋으로 들고 있음
+struct Solver<'a> {
+    variants: &'a [Variant],
+    size: IVec3,
+    words: usize,
+    domains: Vec<u64>,
+    // domains 에서 지운 비트 (domains 인덱스, 지운 비트), 백트래킹할 때 거꾸로 되돌림
+    trail: Vec<(usize, u64)>,
+    // compatible[direction][variant] : direction 쪽 이웃에 올 수 있는 타일
+    compatible: [Vec<Vec<u64>>; 6],
+}
+
+impl<'a> Solver<'a> {
+    fn new(variants: &'a [Variant], size: IVec3) -> Self {
+        let words = variants.len().div_ceil(64);
+        let cell_count = volume(size);
+
+        let mut all = vec![0u64; words];
+        for variant in 0..variants.len() {
+            all[variant / 64] |= 1 << (variant % 64);
+        }
+        let mut domains = Vec::with_capacity(cell_count * words);
+        for _ in 0..cell_count {
+            domains.extend_from_slice(&all);
+        }
+
+        let compatible = std::array::from_fn(|direction| {
+            variants
+                .iter()
+                .map(|a| {
+                    let mut set = vec![0u64; words];
+                    for (index, b) in variants.iter().enumerate() {
+                        if Variant::connects(a, b, direction) {
+                            set[index / 64] |= 1 << (index % 64);
+                        }
+                    }
+                    set
+                })
+                .collect()
+        });
+
+        Solver {
+            variants,
+            size,
+            words,
+            domains,
+            trail: Vec::new(),
+            compatible,
+        }
+    }
+
+    fn position(&self, cell: usize) -> IVec3 {
+        index_to_pos(cell, self.size)
+    }
+
+    fn cell(&self, pos: IVec3) -> Option<usize> {
+        if pos.cmplt(IVec3::ZERO).any() || pos.cmpge(self.size).any() {
+            return None;
+        }
+        Some(((pos.y * self.size.z + pos.z) * self.size.x + pos.x) as usize)
+    }
+
+    fn domain(&self, cell: usize) -> &[u64] {
+        &self.domains[cell * self.words..(cell + 1) * self.words]
+    }
+
+    fn options(&self, cell: usize) -> impl Iterator<Item = usize> + '_ {
+        self.domain(cell)
+            .iter()
+            .enumerate()
+            .flat_map(|(word_index, word)| {
+                (0..64)
+                    .filter(move |bit| word & (1 << bit) != 0)
+                    .map(move |bit| word_index * 64 + bit)
+            })
+    }
+
+    fn count(&self, cell: usize) -> u32 {
+        self.domain(cell).iter().map(|word| word.count_ones()).sum()
+    }
+
+    // domains[index] 를 next 로 줄이고 지운 비트를 trail 에 남김, 바뀌었으면 true
+    fn narrow(&mut self, index: usize, next: u64) -> bool {
+        let removed = self.domains[index] & !next;
+        if removed == 0 {
+            return false;
+        }
+        self.trail.push((index, removed));
+        self.domains[index] &= next;
+        true
+    }
+
+    // trail 을 len 까지 되돌림
+    fn undo(&mut self, len: usize) {
+        for (index, removed) in self.trail.drain(len..).rev() {
+            self.domains[index] |= removed;
+        }
+    }
+
+    // 가장자리 칸에서 바깥 소켓과 맞지 않는 타일을 지움
+    fn apply_boundary(&mut self, boundary: &BoundarySockets) -> Result<(), String> {
+        let mut changed = Vec::new();
+        for cell in 0..volume(self.size) {
+            let pos = self.position(cell);
+            for (direction, offset) in DIRECTIONS.iter().enumerate() {
+                if self.cell(pos + *offset).is_some() {
+                    continue;
+                }
+                let socket = match direction {
+                    2 => &boundary.top,
+                    3 => &boundary.bottom,
+                    _ => &boundary.horizontal,
+                };
+                let Some(socket) = socket else {
+                    continue;
+                };
+
+                for variant in 0..self.variants.len() {
+                    if !self.variants[variant].connects_boundary(direction, socket) {
+                        self.domains[cell * self.words + variant / 64] &= !(1 << (variant % 64));
+                    }
+                }
+                changed.push(cell);
+            }
+        }
+
+        if (0..volume(self.size)).any(|cell| self.count(cell) == 0) {
+            return Err("no tile fits the boundary sockets".to_string());
+        }
+        if !self.propagate(changed) {
+            return Err("no tile fits the boundary sockets".to_string());
+        }
+        Ok(())
+    }
+
+    // 바뀐 칸에서 이웃으로 제약을 전파, 가능한 타일이 없는 칸이 생기면 false
+    fn propagate(&mut self, changed: Vec<usize>) -> bool {
+        let mut queue: VecDeque<usize> = changed.into();
+        let mut allowed = vec![0u64; self.words];
+
+        while let Some(cell) = queue.pop_front() {
+            let pos = self.position(cell);
+            for (direction, offset) in DIRECTIONS.iter().enumerate() {
+                let Some(neighbor) = self.cell(pos + *offset) else {
+                    continue;
+                };
+
+                allowed.fill(0);
+                for variant in self.options(cell).collect::<Vec<_>>() {
+                    for (word, bits) in allowed.iter_mut().zip(&self.compatible[direction][variant])
+                    {
+                        *word |= bits;
+                    }
+                }
+
+                let mut modified = false;
+                let mut empty = true;
+                for (i, bits) in allowed.iter().enumerate() {
+                    let index = neighbor * self.words + i;
+                    modified |= self.narrow(index, *bits);
+                    empty &= self.domains[index] == 0;
+                }
+                if empty {
+                    return false;
+                }
+                if modified {
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+        true
+    }
+
+    // 가능한 타일이 둘 이상인 칸 중 가중치 엔트로피가 가장 작은 칸, 모두 정해졌으면 None
+    fn lowest_entropy(&self, rng: &mut StdRng) -> Option<usize> {
+        let mut best: Option<(f32, usize)> = None;
+        for cell in 0..volume(self.size) {
+            if self.count(cell) <= 1 {
+                continue;
+            }
+
+            let (mut sum, mut sum_log) = (0.0f32, 0.0f32);
+            for variant in self.options(cell) {
+                let weight = self.variants[variant].weight.max(f32::EPSILON);
+                sum += weight;
+                sum_log += weight * weight.ln();
+            }
+            // 같은 엔트로피끼리는 시드에 따라 고름
+            let entropy = sum.ln() - sum_log / sum + rng.gen::<f32>() * 1e-4;
+            if best.is_none_or(|(best_entropy, _)| entropy < best_entropy) {
+                best = Some((entropy, cell));
+            }
+        }
+        best.map(|(_, cell)| cell)
+    }
+
+    fn choose(&self, cell: usize, rng: &mut StdRng) -> usize {
+        let options: Vec<usize> = self.options(cell).collect();
+        let total: f32 = options
+            .iter()
+            .map(|variant| self.variants[*variant].weight)
+            .sum();
+        if total <= 0.0 {
+            return options[rng.gen_range(0..options.len())];
+        }
+
+        let mut pick = rng.gen::<f32>() * total;
+        for variant in &options {
+            pick -= self.variants[*variant].weight;
+            if pick <= 0.0 {
+                return *variant;
+            }
+        }
+        *options.last().unwrap()
+    }
+
+    fn collapse(&mut self, cell: usize, variant: usize) {
+        for i in 0..self.words {
+            let keep = if i == variant / 64 {
+                1 << (variant % 64)
+            } else {
+                0
+            };
+            self.narrow(cell * self.words + i, keep);
+        }
+    }
+
+    fn remove(&mut self, cell: usize, variant: usize) {
+        self.narrow(cell * self.words + variant / 64, !(1 << (variant % 64)));
+    }
+
+    // 칸마다 고른 타일, 백트래킹을 max_backtracks 번 넘게 하면 실패
+    fn run(&mut self, rng: &mut StdRng, max_backtracks: u32) -> Result<Vec<usize>, String> {
+        // (고르기 전 trail 길이, 칸, 고른 타일)
+        // 상태를 통째로 복사하지 않고 trail 로 되돌리므로 메모리는 칸 수 x 타일 수에 비례
+        let mut stack: Vec<(usize, usize, usize)> = Vec::new();
+        let mut backtracks = 0;
+        self.trail.clear();
+
+        while let Some(cell) = self.lowest_entropy(rng) {
+            let variant = self.choose(cell, rng);
+            stack.push((self.trail.len(), cell, variant));
+            self.collapse(cell, variant);
+            if self.propagate(vec![cell]) {
+                continue;
+            }
+
+            // 막히면 고른 타일을 지운 상태로 되돌림, 그래도 막히면 더 이전 선택으로
+            loop {
+                backtracks += 1;
+                if backtracks > max_backtracks {
+                    return Err(format!(
+                        "no solution found within {} backtracks",
+                        max_backtracks
+                    ));
+                }
+                let Some((trail_len, cell, variant)) = stack.pop() else {
+                    return Err("tile set has no solution for this size".to_string());
+                };
+                self.undo(trail_len);
+                self.remove(cell, variant);
+                if self.count(cell) > 0 && self.propagate(vec![cell]) {
+                    break;
+                }
+            }
+        }
+
+        Ok((0..volume(self.size))
+            .map(|cell| self.options(cell).next().unwrap())
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn load_dungeon() -> TileSet {
+        TileSet::load("assets/structures/dungeon.ron").unwrap()
+    }
+
+    // 1x1x1 돌 타일, 가로 소켓은 좌우 대칭인 이름만 사용
+    fn tile(name: &str, pos_x: &str, neg_x: &str, pos_z: &str, neg_z: &str) -> TileDef {
+        TileDef {
+            name: name.to_string(),
+            weight: 1.0,
+            symmetry: Symmetry::None,
+            layers: vec![vec!["#".to_string()]],
+            sockets: Sockets {
+                pos_x: pos_x.to_string(),
+                neg_x: neg_x.to_string(),
+                pos_y: "y".to_string(),
+                neg_y: "y".to_string(),
+                pos_z: pos_z.to_string(),
+                neg_z: neg_z.to_string(),
+            },
+        }
+    }
+
+    // +x 이웃은 s(t), +z 이웃은 r(t) 로 정해지는 세 타일 (s = (0 1), r = (1 2))
+    // 2x1x2 격자에서 대각선 칸이 s(r(t)) 와 r(s(t)) 둘 다여야 하는데 두 값은 항상 달라서 해가 없음
+    // 이웃 칸끼리는 항상 맞는 타일이 있어서 전파만으로는 알 수 없고, 고른 뒤에 막혀서 되돌아감
+    fn unsolvable_set(max_backtracks: u32) -> TileSet {
+        TileSet {
+            tile_size: (1, 1, 1),
+            palette: HashMap::from([('#', "stone".to_string())]),
+            boundary: BoundarySockets::default(),
+            ground_offset: 0,
+            max_backtracks,
+            tiles: vec![
+                tile("t0", "x0", "x1", "z0", "z0"),
+                tile("t1", "x1", "x0", "z1", "z2"),
+                tile("t2", "x2", "x2", "z2", "z1"),
+            ],
+        }
+    }
+
+    #[test]
+    fn same_seed_gives_the_same_structure() {
+        let tile_set = load_dungeon();
+        let size = IVec3::new(4, 2, 4);
+        let first = tile_set.solve(size, 7).unwrap();
+        let second = tile_set.solve(size, 7).unwrap();
+
+        assert_eq!(first.tiles, second.tiles);
+        assert_eq!(first.blocks, second.blocks);
+        assert_eq!(first.tiles.len(), volume(size));
+
+        // 시드가 다르면 다른 배치가 나와야 함
+        assert!((8..16).any(|seed| tile_set.solve(size, seed).unwrap().tiles != first.tiles));
+    }
+
+    #[test]
+    fn get_outside_the_structure_is_none() {
+        let structure = load_dungeon().solve(IVec3::new(2, 1, 2), 3).unwrap();
+        let size = structure.size;
+        assert_eq!(size, IVec3::new(6, 4, 6));
+
+        // 바닥 모서리는 던전의 바닥 (돌)
+        assert_eq!(structure.get(IVec3::ZERO), Some(EBlockType::Stone as i32));
+        assert_eq!(
+            structure.get(size - IVec3::ONE),
+            Some(EBlockType::Stone as i32)
+        );
+        for pos in [
+            IVec3::new(-1, 0, 0),
+            IVec3::new(0, -1, 0),
+            IVec3::new(0, 0, -1),
+            IVec3::new(size.x, 0, 0),
+            IVec3::new(0, size.y, 0),
+            IVec3::new(0, 0, size.z),
+            size,
+        ] {
+            assert_eq!(structure.get(pos), None, "{}", pos);
+        }
+    }
+
+    #[test]
+    fn unsolvable_set_backtracks_until_it_gives_up() {
+        let size = IVec3::new(2, 1, 2);
+
+        // 한 줄이면 순환이 없어서 풀림
+        for tiles in unsolvable_set(0)
+            .solve(IVec3::new(2, 1, 1), 0)
+            .unwrap()
+            .tiles
+            .chunks(2)
+        {
+            let expected = match tiles[0].as_str() {
+                "t0" => "t1",
+                "t1" => "t0",
+                _ => "t2",
+            };
+            assert_eq!(tiles[1], expected);
+        }
+
+        for seed in 0..4 {
+            // 첫 선택을 되돌려도 전파에서 막히고, 두 번째로 되돌아갈 때 남은 선택이 없음
+            assert_eq!(
+                unsolvable_set(2).solve(size, seed).err().unwrap(),
+                "tile set has no solution for this size"
+            );
+            assert_eq!(
+                unsolvable_set(1).solve(size, seed).err().unwrap(),
+                "no solution found within 1 backtracks"
+            );
+            assert_eq!(
+                unsolvable_set(0).solve(size, seed).err().unwrap(),
+                "no solution found within 0 backtracks"
+            );
+        }
+    }
+
+    #[test]
+    fn undo_restores_the_domains_before_a_choice() {
+        let tile_set = load_dungeon();
+        let variants = tile_set.variants().unwrap();
+        let mut solver = Solver::new(&variants, IVec3::new(5, 2, 5));
+        solver.apply_boundary(&tile_set.boundary).unwrap();
+        let before = solver.domains.clone();
+
+        let mut rng = StdRng::seed_from_u64(1);
+        let mut choices = Vec::new();
+        for _ in 0..3 {
+            let cell = solver.lowest_entropy(&mut rng).unwrap();
+            choices.push((solver.trail.len(), solver.domains.clone()));
+            solver.collapse(cell, solver.choose(cell, &mut rng));
+            solver.propagate(vec![cell]);
+        }
+        assert_ne!(solver.domains, before);
+
+        // 나중 선택부터 되돌리면 그 선택 전 상태와 같아야 함
+        for (trail_len, domains) in choices.into_iter().rev() {
+            solver.undo(trail_len);
+            assert_eq!(solver.domains, domains);
+        }
+        assert_eq!(solver.domains, before);
+        assert!(solver.trail.is_empty());
+    }
+
+    #[test]
+    fn solves_dungeon_sized_grids() {
+        let size = IVec3::new(16, 2, 16);
+        let structure = load_dungeon().solve(size, 11).unwrap();
+        assert_eq!(structure.tiles.len(), volume(size));
+    }
+}
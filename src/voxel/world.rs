@@ -1,6 +1,7 @@
 use super::block::EBlockType;
 use super::mesh::*;
-use crate::{noise, WindowSize};
+use crate::day_night::{Sun, TimeOfDay};
+use crate::noise;
 use bevy::math::vec3;
 use bevy::pbr::CascadeShadowConfigBuilder;
 use bevy::prelude::*;
@@ -12,101 +13,540 @@ use bevy::utils::uuid::generate_composite_uuid;
 use other_noise::NoiseFn;
 extern crate noise as other_noise;
 use super::chunk::*;
+use super::chunk_render::{start_fade_in, BlockMaterials};
+use super::coord::{LocalVoxelPos, WorldVoxelPos};
+use super::floating_origin::FloatingOrigin;
+use super::occlusion::find_visible_chunks;
+use super::generator::{GeneratorConfig, NoiseGenerator, TerrainGenerator};
+use super::terrain::TerrainPreset;
+use serde::{Deserialize, Serialize};
 use rand::Rng;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::f32::consts::PI;
+use std::time::{Duration, Instant};
 
 extern crate bevy_flycam;
 use bevy_atmosphere::prelude::*;
 use bevy_flycam::prelude::*;
 
+// 월드를 만들 때 필요한 설정, RON 파일로 저장 가능
+// 게임 안의 시간도 월드와 같이 저장 (예전 파일처럼 없으면 기본값)
+#[derive(Resource, Clone, Debug, Serialize, Deserialize)]
+pub struct WorldConfig {
+    pub seed: u32,
+    pub generator: GeneratorConfig,
+    // 예전 파일처럼 없으면 블록 메쉬
+    #[serde(default)]
+    pub mesher: ChunkMesher,
+    #[serde(default)]
+    pub time_of_day: TimeOfDay,
+}
+
+impl Default for WorldConfig {
+    fn default() -> Self {
+        WorldConfig {
+            seed: VoxelData::DEFAULT_SEED,
+            generator: GeneratorConfig::default(),
+            mesher: ChunkMesher::default(),
+            time_of_day: TimeOfDay::default(),
+        }
+    }
+}
+
+impl WorldConfig {
+    pub fn load<P: AsRef<std::path::Path>>(path: P) -> std::io::Result<WorldConfig> {
+        let text = std::fs::read_to_string(path)?;
+        ron::from_str(&text)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+    }
+
+    pub fn save<P: AsRef<std::path::Path>>(&self, path: P) -> std::io::Result<()> {
+        let text = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        if let Some(parent) = path.as_ref().parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, text)
+    }
+}
+
+// 청크 좌표 -> 스폰된 청크 엔티티
+#[derive(Resource, Default)]
+pub struct ChunkEntities {
+    entities: HashMap<ChunkCoord, Entity>,
+}
+
+impl ChunkEntities {
+    pub fn get(&self, coord: &ChunkCoord) -> Option<Entity> {
+        self.entities.get(coord).copied()
+    }
+
+    pub fn insert(&mut self, coord: ChunkCoord, entity: Entity) {
+        self.entities.insert(coord, entity);
+    }
+
+    pub fn remove(&mut self, coord: &ChunkCoord) -> Option<Entity> {
+        self.entities.remove(coord)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entities.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entities.is_empty()
+    }
+}
+
+// 청크가 새로 생성되어 월드에 추가됨
+#[derive(Event, Clone, Debug)]
+pub struct ChunkLoaded {
+    pub coord: ChunkCoord,
+}
+
+// 청크가 뷰 거리 안으로 들어옴
+#[derive(Event, Clone, Debug)]
+pub struct ChunkActivated {
+    pub coord: ChunkCoord,
+}
+
+// 청크가 뷰 거리 밖으로 나가서 비활성화됨, 블록 데이터는 월드에 그대로 남아 있음
+#[derive(Event, Clone, Debug)]
+pub struct ChunkDeactivated {
+    pub coord: ChunkCoord,
+}
+
+// 청크가 언로드 거리 밖으로 나가서 월드에서 제거됨
+#[derive(Event, Clone, Debug)]
+pub struct ChunkUnloaded {
+    pub coord: ChunkCoord,
+}
+
+// check_view_distance 한 번으로 바뀐 청크들
+#[derive(Default, Debug)]
+pub struct ViewChange {
+    pub loaded: Vec<ChunkCoord>,
+    pub activated: Vec<ChunkCoord>,
+    pub deactivated: Vec<ChunkCoord>,
+    pub unloaded: Vec<ChunkCoord>,
+}
+
+// 청크 하나를 생성 / 메쉬 생성하는 데 걸린 시간, take_chunk_timings 로 가져갈 때까지 쌓임
+// VoxelWorldPlugin 의 진단 시스템이 매 프레임 가져감
+#[derive(Default, Debug)]
+pub struct ChunkTimings {
+    pub generation: Vec<Duration>,
+    pub meshing: Vec<Duration>,
+}
+
 #[derive(Resource)]
 pub struct World {
     chunk_map: std::collections::HashMap<ChunkCoord, Chunk>,
     prev_chunk_coord_list: Vec<ChunkCoord>,
+    // 뷰 거리 안의 (활성) 청크
     current_chunk_coord_list: Vec<ChunkCoord>,
+    generator: Box<dyn TerrainGenerator>,
+    // from_config 로 만든 경우 생성기 설정, with_generator 로 직접 준 생성기면 None
+    generator_config: Option<GeneratorConfig>,
+    // 마지막으로 뷰 거리를 계산한 카메라 청크와 카메라가 하늘에 있었는지
+    view_center: Option<(ChunkCoord, bool)>,
+    // 카메라에서 빈 공간으로 이어져 보일 수 있는 활성 청크, 카메라가 청크 경계를 넘을 때 갱신
+    visible_chunks: HashSet<ChunkCoord>,
+    // 아직 엔티티에 반영하지 않은 (청크, 보임 여부)
+    visibility_changes: Vec<(ChunkCoord, bool)>,
+    // 메쉬를 다시 만들어서 메쉬 에셋에 반영해야 하는 청크
+    remeshed_chunks: Vec<ChunkCoord>,
+    occlusion_culling: bool,
+    // 카메라 주변으로 활성화할 청크 범위
+    view_distance: i32,
+    // 카메라에서 이보다 먼 청크는 월드에서 제거, None 이면 제거하지 않음
+    unload_distance: Option<i32>,
+    // 새로 만드는 청크의 메쉬 방식
+    mesher: ChunkMesher,
+    timings: ChunkTimings,
+    // 메모리에 있는 청크의 정점 수와 블록 데이터 크기 합, 청크를 넣거나 빼거나 메쉬를 다시 만들 때 갱신
+    vertex_count: usize,
+    voxel_memory_bytes: usize,
+    // 마지막으로 generate_world 에 준 크기 (청크 단위)
+    world_size: i32,
 }
 
 impl World {
     pub fn new() -> Self {
+        Self::with_seed(VoxelData::DEFAULT_SEED)
+    }
+
+    pub fn with_seed(seed: u32) -> Self {
+        Self::with_preset(seed, &TerrainPreset::default())
+    }
+
+    pub fn with_preset(seed: u32, preset: &TerrainPreset) -> Self {
+        Self::with_generator(Box::new(NoiseGenerator::new(preset, seed)))
+    }
+
+    pub fn with_generator(generator: Box<dyn TerrainGenerator>) -> Self {
         World {
             chunk_map: std::collections::HashMap::new(),
             prev_chunk_coord_list: Vec::new(),
             current_chunk_coord_list: Vec::new(),
+            generator,
+            generator_config: None,
+            view_center: None,
+            visible_chunks: HashSet::new(),
+            visibility_changes: Vec::new(),
+            remeshed_chunks: Vec::new(),
+            occlusion_culling: true,
+            view_distance: VoxelData::VIEW_DISTANCE_IN_CHUNKS,
+            unload_distance: None,
+            mesher: ChunkMesher::Blocky,
+            timings: ChunkTimings::default(),
+            vertex_count: 0,
+            voxel_memory_bytes: 0,
+            world_size: VoxelData::WORLD_SIZE as i32,
         }
     }
 
-    pub fn generate_world(&mut self) {
-        let range = VoxelData::WORLD_SIZE as i32 / 2;
+    pub fn from_config(config: &WorldConfig) -> Result<Self, String> {
+        let mut world = Self::with_generator(config.generator.build(config.seed)?);
+        world.mesher = config.mesher;
+        world.generator_config = Some(config.generator.clone());
+        Ok(world)
+    }
+
+    pub fn generator_config(&self) -> Option<&GeneratorConfig> {
+        self.generator_config.as_ref()
+    }
+
+    pub fn mesher(&self) -> ChunkMesher {
+        self.mesher
+    }
+
+    pub fn seed(&self) -> u32 {
+        self.generator.seed()
+    }
+
+    // 원점 주변 world_size x world_size 청크를 미리 생성
+    pub fn generate_world(&mut self, world_size: i32) {
+        self.world_size = world_size;
+        let range = world_size / 2;
 
-        for y in -range..range {
-            for x in -range..range {
-                let coord = ChunkCoord { x, y };
-                let mut chunk = Chunk::new(coord.clone());
-                chunk.is_updated = true;
-                self.chunk_map.insert(coord.clone(), chunk);
+        self.generate_region(
+            &ChunkCoord::new(-range, -range),
+            &ChunkCoord::new(range, range),
+        );
+    }
+
+    // [min, max) 범위의 청크를 생성 (이미 있는 청크는 건너뜀)
+    pub fn generate_region(&mut self, min: &ChunkCoord, max: &ChunkCoord) {
+        for z in min.z..max.z {
+            for x in min.x..max.x {
+                let coord = ChunkCoord { x, z };
+                if self.chunk_map.contains_key(&coord) {
+                    continue;
+                }
+
+                let chunk = self.create_chunk(&coord);
+                self.insert_chunk(coord, chunk);
+            }
+        }
+    }
+
+    // 블록 생성과 메쉬 생성을 따로 재서 timings 에 쌓음
+    fn create_chunk(&mut self, coord: &ChunkCoord) -> Chunk {
+        let start = Instant::now();
+        let mut chunk = Chunk::generate(coord.clone(), self.generator.as_ref());
+        chunk.mesher = self.mesher;
+        if self.mesher == ChunkMesher::SurfaceNets {
+            chunk.sample_density(self.generator.as_ref());
+        }
+        let generated = Instant::now();
+        chunk.create_mesh_data();
+        chunk.is_updated = true;
+
+        self.timings.generation.push(generated - start);
+        self.timings.meshing.push(generated.elapsed());
+        chunk
+    }
+
+    // 없는 좌표에만 넣음
+    fn insert_chunk(&mut self, coord: ChunkCoord, chunk: Chunk) {
+        self.vertex_count += chunk.vertices.len();
+        self.voxel_memory_bytes += chunk.voxel_memory_bytes();
+        self.chunk_map.insert(coord, chunk);
+    }
+
+    pub fn take_chunk_timings(&mut self) -> ChunkTimings {
+        std::mem::take(&mut self.timings)
+    }
+
+    pub fn get_chunk(&self, coord: &ChunkCoord) -> Option<&Chunk> {
+        self.chunk_map.get(coord)
+    }
+
+    // 메쉬는 rebuild_chunk_mesh 로 다시 만들어야 정점 수 합이 맞음
+    pub fn get_chunk_mut(&mut self, coord: &ChunkCoord) -> Option<&mut Chunk> {
+        self.chunk_map.get_mut(coord)
+    }
+
+    // 월드 좌표의 블록 id, 생성되지 않은 청크나 높이 범위 밖이면 None
+    pub fn get_block_id(&self, pos: WorldVoxelPos) -> Option<i32> {
+        let chunk = self.chunk_map.get(&pos.chunk_coord())?;
+        chunk.get_voxel(pos.local())
+    }
+
+    // 월드 컬럼 (x, z) 에서 가장 높은 고체 블록의 y, 청크의 heightmap 을 사용
+    pub fn highest_solid_block(&self, x: i32, z: i32) -> Option<i32> {
+        let pos = WorldVoxelPos::new(x, 0, z);
+        let local = pos.local();
+        self.chunk_map
+            .get(&pos.chunk_coord())?
+            .highest_solid_block(local.x, local.z)
+    }
+
+    // 블록만 바꾸고 메쉬는 다시 만들지 않음, 메쉬를 다시 만들어야 하는 청크의 좌표를 반환
+    // SurfaceNets 청크는 경계 블록을 밀도 샘플로 가진 이웃 청크도 같이 고쳐서 반환 (경계의 틈 방지)
+    // 여러 블록을 바꾼 뒤 청크마다 rebuild_chunk_mesh 를 한 번만 호출하기 위함
+    pub fn set_block_id(&mut self, pos: WorldVoxelPos, block_id: i32) -> Vec<ChunkCoord> {
+        let coord = pos.chunk_coord();
+        let local = pos.local();
+        let Some(chunk) = self.chunk_map.get_mut(&coord) else {
+            return Vec::new();
+        };
+        if !chunk.set_voxel(local, block_id) {
+            return Vec::new();
+        }
+
+        let mut changed = vec![coord.clone()];
+        for dz in -1..=1 {
+            for dx in -1..=1 {
+                if dx == 0 && dz == 0 {
+                    continue;
+                }
+                let neighbor_coord = ChunkCoord::new(coord.x + dx, coord.z + dz);
+                let Some(neighbor) = self.chunk_map.get_mut(&neighbor_coord) else {
+                    continue;
+                };
+                // 이웃 청크 기준 로컬 좌표, 이웃의 밀도 격자 (-1 ~ W) 안에 있을 때만 바뀜
+                let neighbor_local = LocalVoxelPos::new(
+                    local.x - dx * VoxelData::CHUNK_WIDTH,
+                    local.y,
+                    local.z - dz * VoxelData::CHUNK_WIDTH,
+                );
+                if neighbor.set_density_sample(neighbor_local, block_id) {
+                    changed.push(neighbor_coord);
+                }
             }
         }
+        changed
+    }
+
+    // 청크 메쉬를 다시 만들고 다음 프레임에 메쉬 에셋을 갱신하도록 표시
+    // 빈 공간 연결이 바뀌었을 수 있으므로 오클루전 컬링도 다시 계산
+    pub fn rebuild_chunk_mesh(&mut self, coord: &ChunkCoord) -> bool {
+        let Some(chunk) = self.chunk_map.get_mut(coord) else {
+            return false;
+        };
+
+        let start = Instant::now();
+        let old_vertices = chunk.vertices.len();
+        chunk.rebuild_mesh();
+        self.timings.meshing.push(start.elapsed());
+        self.vertex_count = self.vertex_count - old_vertices + chunk.vertices.len();
+        self.remeshed_chunks.push(coord.clone());
+        self.view_center = None;
+        true
+    }
+
+    pub fn take_remeshed_chunks(&mut self) -> Vec<ChunkCoord> {
+        std::mem::take(&mut self.remeshed_chunks)
+    }
+
+    // 메쉬 에셋에 아직 반영하지 않은 청크 수
+    pub fn remesh_queue_len(&self) -> usize {
+        self.remeshed_chunks.len()
+    }
+
+    // 엔티티에 아직 반영하지 않은 보임 여부 변경 수
+    pub fn visibility_queue_len(&self) -> usize {
+        self.visibility_changes.len()
     }
 
     pub fn get_chunkcoord_from_pos(pos: Vec3) -> ChunkCoord {
-        let x = (pos.x / VoxelData::CHUNK_WIDTH as f32).floor() as i32;
-        let y = (pos.z / VoxelData::CHUNK_HEIGHT as f32).floor() as i32;
+        ChunkCoord::from_world_pos(pos)
+    }
 
-        ChunkCoord { x, y }
+    pub fn view_distance(&self) -> i32 {
+        self.view_distance
     }
 
-    pub fn check_view_distance(&mut self, pos: Vec3) {
-        let coord = Self::get_chunkcoord_from_pos(pos);
-        let range = VoxelData::VIEW_DISTANCE_IN_CHUNKS;
+    // 다음 update 에서 뷰 범위를 다시 계산하도록 함
+    pub fn set_view_distance(&mut self, view_distance: i32) {
+        self.view_distance = view_distance.max(1);
+        self.view_center = None;
+    }
+
+    pub fn unload_distance(&self) -> Option<i32> {
+        self.unload_distance
+    }
+
+    // 뷰 거리보다 작으면 뷰 거리로 맞춤
+    pub fn set_unload_distance(&mut self, unload_distance: Option<i32>) {
+        self.unload_distance = unload_distance;
+        self.view_center = None;
+    }
+
+    // 마지막으로 뷰 거리를 계산한 (카메라 청크, 하늘에 있었는지), 아직 계산하지 않았으면 None
+    pub fn view_center(&self) -> Option<&(ChunkCoord, bool)> {
+        self.view_center.as_ref()
+    }
 
-        // 모든 청크를 비활성화
-        for chunk in self.chunk_map.values_mut() {
-            chunk.is_active = false;
+    // 카메라가 다른 청크로 넘어갔거나 하늘을 드나들어서 뷰 거리 / 컬링을 다시 계산해야 하는지
+    pub fn needs_view_update(&self, camera_chunk: &ChunkCoord, camera_in_sky: bool) -> bool {
+        match &self.view_center {
+            Some((coord, in_sky)) => coord != camera_chunk || *in_sky != camera_in_sky,
+            None => true,
         }
+    }
 
-        for y in (coord.y - range as i32)..(coord.y + range as i32) {
-            for x in (coord.x - range as i32)..(coord.x + range as i32) {
-                let coord = ChunkCoord { x, y };
+    // coord : 카메라가 있는 청크
+    // 이전 뷰 범위와 비교해서 바뀐 청크만 활성 / 비활성으로 바꾸고 없는 청크는 생성
+    pub fn check_view_distance(&mut self, coord: ChunkCoord) -> ViewChange {
+        let range = self.view_distance;
+        let mut change = ViewChange::default();
+        let mut in_range = Vec::new();
 
-                if let Some(chunk) = self.chunk_map.get_mut(&coord) {
-                    chunk.is_active = true;
-                }
+        for z in (coord.z - range)..(coord.z + range) {
+            for x in (coord.x - range)..(coord.x + range) {
+                let coord = ChunkCoord { x, z };
 
                 if !self.chunk_map.contains_key(&coord) {
-                    let mut chunk = Chunk::new(coord.clone());
-                    chunk.is_updated = true;
-                    self.chunk_map.insert(coord.clone(), chunk);
+                    let chunk = self.create_chunk(&coord);
+                    self.insert_chunk(coord.clone(), chunk);
+                    change.loaded.push(coord.clone());
                 }
+
+                if let Some(chunk) = self.chunk_map.get_mut(&coord) {
+                    if !chunk.is_active {
+                        chunk.is_active = true;
+                        change.activated.push(coord.clone());
+                    }
+                }
+
+                in_range.push(coord);
             }
         }
+
+        // 범위 밖으로 나간 청크 비활성화
+        let in_range_set: HashSet<&ChunkCoord> = in_range.iter().collect();
+        for coord in &self.current_chunk_coord_list {
+            if in_range_set.contains(coord) {
+                continue;
+            }
+            if let Some(chunk) = self.chunk_map.get_mut(coord) {
+                chunk.is_active = false;
+            }
+            change.deactivated.push(coord.clone());
+        }
+        self.current_chunk_coord_list = in_range;
+
+        // 언로드 거리 밖의 청크 제거
+        if let Some(unload_distance) = self.unload_distance {
+            let unload_distance = unload_distance.max(range);
+            self.chunk_map.retain(|chunk_coord, chunk| {
+                let distance = (chunk_coord.x - coord.x)
+                    .abs()
+                    .max((chunk_coord.z - coord.z).abs());
+                if distance > unload_distance {
+                    change.unloaded.push(chunk_coord.clone());
+                    self.vertex_count -= chunk.vertices.len();
+                    self.voxel_memory_bytes -= chunk.voxel_memory_bytes();
+                    false
+                } else {
+                    true
+                }
+            });
+            self.visible_chunks
+                .retain(|chunk_coord| self.chunk_map.contains_key(chunk_coord));
+        }
+
+        change
     }
 
-    pub fn is_chunk_in_world(&mut self, coord: &ChunkCoord) -> bool {
-        //println!("{0} , {1} , {2}", coord.x, coord.y, VoxelData::WORLD_SIZE as i32 * VoxelData::CHUNK_WIDTH - 1);
+    // 활성 청크 중 카메라에서 보일 수 있는 청크를 다시 계산하고, 이전과 달라진 청크를 visibility_changes 에 쌓음
+    // camera_in_sky : 카메라가 청크 높이보다 위에 있음
+    pub fn update_occlusion(&mut self, camera_chunk: &ChunkCoord, camera_in_sky: bool) {
+        self.view_center = Some((camera_chunk.clone(), camera_in_sky));
 
-        if coord.x >= 0
-            && coord.x < VoxelData::WORLD_SIZE as i32
-            && coord.y >= 0
-            && coord.y < VoxelData::WORLD_SIZE as i32
-        {
-            return true;
+        let active: HashSet<ChunkCoord> = self.current_chunk_coord_list.iter().cloned().collect();
+        let visible = if self.occlusion_culling {
+            find_visible_chunks(camera_chunk, camera_in_sky, &active, |coord| {
+                self.chunk_map.get(coord).map(|chunk| chunk.visibility_graph)
+            })
+        } else {
+            active
+        };
+
+        for coord in self.visible_chunks.difference(&visible) {
+            self.visibility_changes.push((coord.clone(), false));
         }
-        return false;
-    }
-
-    pub fn is_voxel_in_world(&mut self, pos: Vec3) -> bool {
-        if pos.x > 0.0
-            && pos.x < (VoxelData::WORLD_SIZE as i32 * VoxelData::CHUNK_WIDTH - 1) as f32
-            && pos.y > 0.0
-            && pos.y < (VoxelData::WORLD_SIZE as i32 * VoxelData::CHUNK_WIDTH - 1) as f32
-            && pos.z > 0.0
-            && pos.z < (VoxelData::WORLD_SIZE as i32 * VoxelData::CHUNK_WIDTH - 1) as f32
-        {
-            return true;
+        for coord in visible.difference(&self.visible_chunks) {
+            self.visibility_changes.push((coord.clone(), true));
         }
+        self.visible_chunks = visible;
+    }
+
+    // 다음 update 에서 컬링을 다시 계산하도록 함
+    pub fn set_occlusion_culling(&mut self, enabled: bool) {
+        self.occlusion_culling = enabled;
+        self.view_center = None;
+    }
+
+    pub fn take_visibility_changes(&mut self) -> Vec<(ChunkCoord, bool)> {
+        std::mem::take(&mut self.visibility_changes)
+    }
+
+    // 뷰 거리 안에 있고 오클루전 컬링으로 가려지지 않은 청크
+    pub fn is_chunk_visible(&self, coord: &ChunkCoord) -> bool {
+        self.visible_chunks.contains(coord)
+    }
+
+    // 메모리에 있는 (생성된) 청크
+    pub fn chunks(&self) -> impl Iterator<Item = &Chunk> {
+        self.chunk_map.values()
+    }
+
+    pub fn chunk_count(&self) -> usize {
+        self.chunk_map.len()
+    }
+
+    // 메모리에 있는 청크의 정점 수 합
+    pub fn vertex_count(&self) -> usize {
+        self.vertex_count
+    }
 
-        return false;
+    // 메모리에 있는 청크의 블록 데이터 (voxel_map, heightmap, 밀도) 크기 합 (바이트)
+    pub fn voxel_memory_bytes(&self) -> usize {
+        self.voxel_memory_bytes
+    }
+
+    pub fn active_chunk_count(&self) -> usize {
+        self.current_chunk_coord_list.len()
+    }
+
+    pub fn visible_chunk_count(&self) -> usize {
+        self.visible_chunks.len()
+    }
+
+    // generate_world 로 미리 생성하는 원점 중심 범위 안인지, [-world_size / 2, world_size / 2)
+    pub fn is_chunk_in_world(&self, coord: &ChunkCoord) -> bool {
+        let range = self.world_size / 2;
+        (-range..range).contains(&coord.x) && (-range..range).contains(&coord.z)
+    }
+
+    pub fn is_voxel_in_world(&self, pos: Vec3) -> bool {
+        let pos = WorldVoxelPos::from_world_pos(pos);
+        (0..VoxelData::CHUNK_HEIGHT).contains(&pos.y)
+            && self.is_chunk_in_world(&pos.chunk_coord())
     }
 
     pub fn get_voxel(pos: Vec3) -> i32 {
@@ -142,61 +582,88 @@ impl World {
     }
 }
 
+// World 가 바뀐 프레임에만 실행 (run_if(resource_changed::<World>))
+// 보임 여부가 바뀐 청크의 엔티티만 찾아서 Visibility 를 바꿈
+// 숨겨져 있다가 보이게 된 청크는 페이드 인 (렌더링이 없으면 BlockMaterials 도 없음)
 pub fn update_chunk_in_view_range(
     mut world: ResMut<World>,
-    mut query: Query<(&mut Visibility, &mut ChunkCoord)>,
+    chunk_entities: Res<ChunkEntities>,
+    block_materials: Option<Res<BlockMaterials>>,
+    mut commands: Commands,
+    mut query: Query<&mut Visibility, With<ChunkCoord>>,
 ) {
-    for (mut visible, chunk) in query.iter_mut() {
-        if world.get_chunk_is_active(chunk.clone()) {
-            *visible = Visibility::Visible;
+    // 여기서 비우는 것 때문에 다음 프레임에 다시 실행되지 않도록 change detection 을 우회
+    for (coord, is_visible) in world.bypass_change_detection().take_visibility_changes() {
+        let Some(entity) = chunk_entities.get(&coord) else {
+            continue;
+        };
+        let Ok(mut visibility) = query.get_mut(entity) else {
+            continue;
+        };
+
+        if is_visible {
+            if *visibility == Visibility::Hidden {
+                if let Some(block_materials) = &block_materials {
+                    start_fade_in(&mut commands, block_materials, entity);
+                }
+            }
+            *visibility = Visibility::Visible;
         } else {
-            *visible = Visibility::Hidden;
+            *visibility = Visibility::Hidden;
         }
     }
 }
 
+// 청크 엔티티를 숨긴 상태로 스폰, 보이는지는 update_chunk_in_view_range 에서 정함
+fn spawn_chunk(
+    commands: &mut Commands,
+    meshes: &mut Assets<Mesh>,
+    material: &Handle<StandardMaterial>,
+    floating_origin: &FloatingOrigin,
+    chunk: &Chunk,
+) -> Entity {
+    commands
+        .spawn((
+            PbrBundle {
+                mesh: meshes.add(chunk.create_mesh()),
+                material: material.clone(),
+                transform: Transform::from_translation(
+                    floating_origin.chunk_translation(&chunk.chunk_coord),
+                ),
+                visibility: Visibility::Hidden,
+                ..default()
+            },
+            chunk.chunk_coord.clone(),
+            chunk.aabb(),
+        ))
+        .id()
+}
+
 pub fn setup(
     mut voxel_world: ResMut<World>,
     mut commands: Commands,
-    asset_server: Res<AssetServer>,
     mut meshes: ResMut<Assets<Mesh>>,
-    mut materials: ResMut<Assets<StandardMaterial>>,
-    _window_size: Res<WindowSize>,
+    block_materials: Res<BlockMaterials>,
+    floating_origin: Res<FloatingOrigin>,
+    mut chunk_entities: ResMut<ChunkEntities>,
 ) {
-    let texture_handle: Handle<Image> = asset_server.load("Blocks.png");
-
+    // 앱 시작 전에 미리 생성해 둔 청크
     for (coord, chunk) in &mut voxel_world.chunk_map {
         if chunk.is_updated {
-            let temp_mesh = Mesh::new(
-                PrimitiveTopology::TriangleList,
-                RenderAssetUsages::MAIN_WORLD | RenderAssetUsages::RENDER_WORLD,
-            )
-            .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, chunk.vertices.clone())
-            .with_inserted_attribute(Mesh::ATTRIBUTE_UV_0, chunk.uvs.clone())
-            .with_inserted_indices(Indices::U32(chunk.triangles.clone()));
-
-            let cube_mesh_handle = meshes.add(temp_mesh.clone());
-            commands
-                .spawn((
-                    PbrBundle {
-                        mesh: cube_mesh_handle,
-                        material: materials.add(StandardMaterial {
-                            base_color_texture: Some(texture_handle.clone()),
-                            ..default()
-                        }),
-                        ..default()
-                    },
-                    ChunkCoord {
-                        x: coord.x,
-                        y: coord.y,
-                    },
-                ))
-                .insert(VisibilityBundle {
-                    ..Default::default()
-                });
+            let entity = spawn_chunk(
+                &mut commands,
+                &mut meshes,
+                &block_materials.opaque,
+                &floating_origin,
+                chunk,
+            );
+            chunk_entities.insert(coord.clone(), entity);
         }
     }
+}
 
+// 기본 조명과 대기 카메라, VoxelWorldPlugin 에는 포함되지 않음
+pub fn setup_lighting(mut commands: Commands) {
     // ambient light
     commands.insert_resource(AmbientLight {
         color: Color::Rgba {
@@ -207,73 +674,296 @@ pub fn setup(
         },
         brightness: 200.0,
     });
-    // directional light
-    commands.spawn(DirectionalLightBundle {
-        directional_light: DirectionalLight {
-            illuminance: light_consts::lux::OVERCAST_DAY,
-            shadows_enabled: true,
+    // directional light, 방향과 조도는 DayNightPlugin 이 TimeOfDay 에 맞춰 바꿈
+    commands.spawn((
+        DirectionalLightBundle {
+            directional_light: DirectionalLight {
+                illuminance: light_consts::lux::OVERCAST_DAY,
+                shadows_enabled: true,
 
+                ..default()
+            },
+            transform: Transform::from_translation(Vec3::new(20.0, 100.0, 0.0)),
+            cascade_shadow_config: CascadeShadowConfigBuilder {
+                first_cascade_far_bound: 10.0,
+                maximum_distance: 10.0,
+                ..default()
+            }
+            .into(),
             ..default()
         },
-        transform: Transform {
-            translation: Vec3::new(20.0, 100.0, 0.0),
-            rotation: Quat::from_rotation_x(10.0),
-            ..default()
-        },
-        cascade_shadow_config: CascadeShadowConfigBuilder {
-            first_cascade_far_bound: 10.0,
-            maximum_distance: 10.0,
-            ..default()
-        }
-        .into(),
-        ..default()
-    });
+        Sun,
+    ));
 
     commands.spawn(AtmosphereCamera::default());
 }
 
+// 카메라가 청크 경계를 넘었을 때만 뷰 거리와 컬링을 다시 계산하고 이벤트를 보냄
 pub fn update(
     mut voxel_world: ResMut<World>,
+    floating_origin: Res<FloatingOrigin>,
+    query: Query<&Transform, With<FlyCam>>,
+    mut loaded_events: EventWriter<ChunkLoaded>,
+    mut activated_events: EventWriter<ChunkActivated>,
+    mut deactivated_events: EventWriter<ChunkDeactivated>,
+    mut unloaded_events: EventWriter<ChunkUnloaded>,
+) {
+    // 카메라가 없으면 (헤드리스 테스트 등) 아무것도 하지 않음
+    let Ok(camera_transform) = query.get_single() else {
+        return;
+    };
+    let camera_pos = camera_transform.translation;
+    let camera_chunk = floating_origin.chunk_coord_of(camera_pos);
+    let camera_in_sky =
+        camera_pos.y - VoxelData::CHUNK_Y_OFFSET >= VoxelData::CHUNK_HEIGHT as f32;
+
+    // 그대로면 World 를 건드리지 않아 resource_changed 도 false 로 남음
+    if !voxel_world.needs_view_update(&camera_chunk, camera_in_sky) {
+        return;
+    }
+
+    // 뷰 거리 체크
+    let change = voxel_world.check_view_distance(camera_chunk.clone());
+    loaded_events.send_batch(change.loaded.into_iter().map(|coord| ChunkLoaded { coord }));
+    activated_events.send_batch(
+        change
+            .activated
+            .into_iter()
+            .map(|coord| ChunkActivated { coord }),
+    );
+    deactivated_events.send_batch(
+        change
+            .deactivated
+            .into_iter()
+            .map(|coord| ChunkDeactivated { coord }),
+    );
+    unloaded_events.send_batch(change.unloaded.into_iter().map(|coord| ChunkUnloaded { coord }));
+
+    // 오클루전 컬링
+    voxel_world.update_occlusion(&camera_chunk, camera_in_sky);
+}
+
+// 새로 생성된 청크의 엔티티를 스폰
+pub fn spawn_loaded_chunks(
+    voxel_world: Res<World>,
+    mut loaded_events: EventReader<ChunkLoaded>,
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
-    mut materials: ResMut<Assets<StandardMaterial>>,
-    asset_server: Res<AssetServer>,
-    mut query: Query<&mut Transform, With<FlyCam>>,
+    block_materials: Res<BlockMaterials>,
+    floating_origin: Res<FloatingOrigin>,
+    mut chunk_entities: ResMut<ChunkEntities>,
 ) {
-    // 뷰 거리 체크
-    voxel_world.check_view_distance(query.single().translation);
-    return;
-    // 새로운 청크 스폰
-    let texture_handle: Handle<Image> = asset_server.load("Blocks.png");
-    for (coord, chunk) in &mut voxel_world.chunk_map {
-        if chunk.is_updated && !chunk.is_active {
-            let temp_mesh = Mesh::new(
-                PrimitiveTopology::TriangleList,
-                RenderAssetUsages::MAIN_WORLD | RenderAssetUsages::RENDER_WORLD,
-            )
-            .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, chunk.vertices.clone())
-            .with_inserted_attribute(Mesh::ATTRIBUTE_UV_0, chunk.uvs.clone())
-            .with_inserted_indices(Indices::U32(chunk.triangles.clone()));
-
-            let cube_mesh_handle = meshes.add(temp_mesh.clone());
-            commands
-                .spawn((
-                    PbrBundle {
-                        mesh: cube_mesh_handle,
-                        material: materials.add(StandardMaterial {
-                            base_color_texture: Some(texture_handle.clone()),
-                            ..default()
-                        }),
-                        ..default()
-                    },
-                    ChunkCoord {
-                        x: coord.x,
-                        y: coord.y,
-                    },
-                ))
-                .insert(VisibilityBundle {
-                    ..Default::default()
-                });
+    for event in loaded_events.read() {
+        let Some(chunk) = voxel_world.get_chunk(&event.coord) else {
+            continue;
+        };
+        if chunk_entities.get(&event.coord).is_some() {
+            continue;
+        }
+
+        let entity = spawn_chunk(
+            &mut commands,
+            &mut meshes,
+            &block_materials.opaque,
+            &floating_origin,
+            chunk,
+        );
+        chunk_entities.insert(event.coord.clone(), entity);
+    }
+}
+
+// 월드에서 제거된 청크의 엔티티를 despawn, 메쉬 에셋은 핸들이 없어지면서 같이 해제됨
+pub fn despawn_unloaded_chunks(
+    mut unloaded_events: EventReader<ChunkUnloaded>,
+    mut commands: Commands,
+    mut chunk_entities: ResMut<ChunkEntities>,
+) {
+    for event in unloaded_events.read() {
+        if let Some(entity) = chunk_entities.remove(&event.coord) {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::block::Block;
+    use super::super::surface_nets;
+    use super::*;
+
+    fn assert_totals_match_chunks(world: &World) {
+        let vertices: usize = world.chunks().map(|chunk| chunk.vertices.len()).sum();
+        let memory: usize = world.chunks().map(|chunk| chunk.voxel_memory_bytes()).sum();
+        assert_eq!(world.vertex_count(), vertices);
+        assert_eq!(world.voxel_memory_bytes(), memory);
+    }
+
+    #[test]
+    fn vertex_and_memory_totals_follow_loading_remeshing_and_unloading() {
+        let mut world = World::with_generator(GeneratorConfig::default().build(3).unwrap());
+        world.generate_world(4);
+        assert!(world.vertex_count() > 0);
+        assert_totals_match_chunks(&world);
+
+        let top = world.highest_solid_block(0, 0).unwrap();
+        for coord in world.set_block_id(WorldVoxelPos::new(0, top, 0), EBlockType::Air as i32) {
+            world.rebuild_chunk_mesh(&coord);
+        }
+        assert_totals_match_chunks(&world);
+
+        world.set_view_distance(1);
+        world.set_unload_distance(Some(2));
+        world.check_view_distance(ChunkCoord::new(0, 0));
+        assert_totals_match_chunks(&world);
+        let change = world.check_view_distance(ChunkCoord::new(20, 0));
+        assert!(!change.unloaded.is_empty());
+        assert_eq!(world.chunk_count(), world.active_chunk_count());
+        assert_totals_match_chunks(&world);
+    }
+
+    #[test]
+    fn chunks_in_world_match_the_centered_generation() {
+        let mut world = World::with_generator(GeneratorConfig::Void.build(0).unwrap());
+        world.generate_world(4);
+
+        for z in -4..4 {
+            for x in -4..4 {
+                let coord = ChunkCoord::new(x, z);
+                assert_eq!(
+                    world.is_chunk_in_world(&coord),
+                    world.get_chunk(&coord).is_some(),
+                    "{:?}",
+                    coord
+                );
+            }
+        }
+
+        let w = VoxelData::CHUNK_WIDTH as f32;
+        assert!(world.is_voxel_in_world(Vec3::new(-2.0 * w, 0.0, 2.0 * w - 0.5)));
+        assert!(!world.is_voxel_in_world(Vec3::new(-2.0 * w - 0.5, 0.0, 0.0)));
+        assert!(!world.is_voxel_in_world(Vec3::new(0.0, 0.0, 2.0 * w)));
+        assert!(!world.is_voxel_in_world(Vec3::new(0.0, -0.5, 0.0)));
+        assert!(!world.is_voxel_in_world(Vec3::new(0.0, VoxelData::CHUNK_HEIGHT as f32, 0.0)));
+    }
+
+    // 컬럼을 위에서부터 직접 훑어서 찾은 가장 높은 고체 블록
+    fn scan_highest_solid_block(world: &World, x: i32, z: i32) -> Option<i32> {
+        let block = Block::new();
+        (0..VoxelData::CHUNK_HEIGHT).rev().find(|y| {
+            let block_id = world.get_block_id(WorldVoxelPos::new(x, *y, z)).unwrap();
+            block.block_types[block_id as usize].is_solid
+        })
+    }
+
+    #[test]
+    fn cached_highest_solid_block_matches_a_column_scan() {
+        let mut world = World::with_generator(GeneratorConfig::default().build(3).unwrap());
+        world.generate_region(&ChunkCoord::new(-1, -1), &ChunkCoord::new(1, 1));
+
+        let w = VoxelData::CHUNK_WIDTH;
+        let check = |world: &World| {
+            for z in -w..w {
+                for x in -w..w {
+                    assert_eq!(
+                        world.highest_solid_block(x, z),
+                        scan_highest_solid_block(world, x, z),
+                        "({}, {})",
+                        x,
+                        z
+                    );
+                }
+            }
+        };
+        check(&world);
+
+        // 블록을 바꾸고 메쉬를 다시 만든 뒤에도 캐시가 맞아야 함
+        let top = VoxelData::CHUNK_HEIGHT - 1;
+        let edits = [
+            (WorldVoxelPos::new(0, top, 0), EBlockType::Stone),
+            (WorldVoxelPos::new(-1, top, -1), EBlockType::Dirt),
+            (WorldVoxelPos::new(w - 1, 0, -w), EBlockType::Air),
+        ];
+        let mut dirty = HashSet::new();
+        for (pos, block_type) in edits {
+            dirty.extend(world.set_block_id(pos, block_type as i32));
+        }
+        let column = world.highest_solid_block(5, 5).unwrap();
+        for y in 0..=column {
+            dirty.extend(world.set_block_id(WorldVoxelPos::new(5, y, 5), EBlockType::Air as i32));
+        }
+        for coord in &dirty {
+            world.rebuild_chunk_mesh(coord);
+        }
+
+        assert_eq!(world.highest_solid_block(0, 0), Some(top));
+        assert_eq!(world.highest_solid_block(5, 5), None);
+        check(&world);
+    }
+
+    #[test]
+    fn border_edits_update_the_neighbor_density_samples() {
+        let config = WorldConfig {
+            mesher: ChunkMesher::SurfaceNets,
+            ..Default::default()
+        };
+        let mut world = World::from_config(&config).unwrap();
+        world.generate_region(&ChunkCoord::new(0, 0), &ChunkCoord::new(2, 2));
+
+        let w = VoxelData::CHUNK_WIDTH;
+        let y = world.highest_solid_block(w - 1, w - 1).unwrap();
+
+        // 모서리 블록은 네 청크의 밀도 격자에 들어 있음
+        let mut changed =
+            world.set_block_id(WorldVoxelPos::new(w - 1, y, w - 1), EBlockType::Air as i32);
+        changed.extend(world.set_block_id(
+            WorldVoxelPos::new(w - 1, y + 1, 3),
+            EBlockType::Stone as i32,
+        ));
+        changed
+            .extend(world.set_block_id(WorldVoxelPos::new(3, y + 1, 3), EBlockType::Stone as i32));
+        let changed: HashSet<ChunkCoord> = changed.into_iter().collect();
+        let expected: HashSet<ChunkCoord> = [(0, 0), (1, 0), (0, 1), (1, 1)]
+            .into_iter()
+            .map(|(x, z)| ChunkCoord::new(x, z))
+            .collect();
+        assert_eq!(changed, expected);
+        // 안쪽 블록은 자기 청크만
+        assert_eq!(
+            world.set_block_id(WorldVoxelPos::new(4, y, 4), EBlockType::Air as i32),
+            vec![ChunkCoord::new(0, 0)]
+        );
+        for coord in &changed {
+            world.rebuild_chunk_mesh(coord);
+        }
+
+        // 두 청크가 겹치는 샘플 (경계 양쪽 한 칸) 은 같아야 함
+        let sample = |coord: (i32, i32), x: i32, y: i32, z: i32| {
+            let chunk = world.get_chunk(&ChunkCoord::new(coord.0, coord.1)).unwrap();
+            chunk.density[surface_nets::density_index(x, y, z).unwrap()]
+        };
+        for y in -1..=VoxelData::CHUNK_HEIGHT {
+            for i in -1..=w {
+                for (a, b) in [(w - 1, -1), (w, 0)] {
+                    assert_eq!(
+                        sample((0, 0), a, y, i),
+                        sample((1, 0), b, y, i),
+                        "x {} {} {}",
+                        a,
+                        y,
+                        i
+                    );
+                    assert_eq!(
+                        sample((0, 0), i, y, a),
+                        sample((0, 1), i, y, b),
+                        "z {} {} {}",
+                        i,
+                        y,
+                        a
+                    );
+                }
+            }
         }
+        assert!(sample((1, 1), -1, y, -1) < 0.0);
+        assert!(sample((1, 0), -1, y + 1, 3) > 0.0);
     }
 }